@@ -780,4 +780,38 @@ impl AccountService for AccountController {
             role: account_data.1.to_grpc_enum(),
         }))
     }
+
+    async fn get_account_emails(
+        &self,
+        req: Request<proto::account::GetAccountEmailsReq>,
+    ) -> Result<Response<proto::account::AccountEmails>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        let account_ids = req
+            .get_ref()
+            .ids
+            .iter()
+            .map(|id| Uuid::from_str(id))
+            .collect::<Result<Vec<Uuid>, uuid::Error>>()
+            .map_err(|e| Status::aborted(e.to_string()))?;
+
+        // Get the emails of the accounts
+        let accounts = schema::account::table
+            .filter(schema::account::id.eq_any(&account_ids))
+            .select((schema::account::id, schema::account::email))
+            .load::<(Uuid, String)>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(proto::account::AccountEmails {
+            accounts: accounts
+                .into_iter()
+                .map(|(id, email)| proto::account::AccountEmail {
+                    id: id.to_string(),
+                    email,
+                })
+                .collect(),
+        }))
+    }
 }
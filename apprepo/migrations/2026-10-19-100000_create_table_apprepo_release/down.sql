@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS "apprepo_release";
+DROP TYPE IF EXISTS "apprepo_release_arch";
+DROP TYPE IF EXISTS "apprepo_release_platform";
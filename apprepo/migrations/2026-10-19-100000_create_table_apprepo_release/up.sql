@@ -0,0 +1,22 @@
+-- Your SQL goes here
+CREATE TYPE "apprepo_release_platform" AS ENUM ('windows', 'macos', 'linux', 'android', 'ios');
+CREATE TYPE "apprepo_release_arch" AS ENUM ('x86_64', 'aarch64', 'x86', 'arm', 'universal');
+
+CREATE TABLE IF NOT EXISTS "apprepo_release" (
+    "id" uuid DEFAULT gen_random_uuid (),
+    "apprepo_id" uuid NOT NULL,
+    "version" TEXT NOT NULL,
+    "platform" apprepo_release_platform NOT NULL,
+    "arch" apprepo_release_arch NOT NULL,
+    "download_url" TEXT NOT NULL,
+    "checksum" TEXT NOT NULL,
+    "changelog" TEXT NOT NULL DEFAULT '',
+    "released_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "yanked_at" TIMESTAMP,
+    "yanked_reason" TEXT,
+    "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "updated_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY ("id"),
+    FOREIGN KEY ("apprepo_id") REFERENCES "apprepo" ("id") ON DELETE CASCADE,
+    UNIQUE ("apprepo_id", "version", "platform", "arch")
+);
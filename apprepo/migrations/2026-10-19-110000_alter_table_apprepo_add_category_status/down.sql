@@ -0,0 +1,12 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS "apprepo_category_id_idx";
+DROP INDEX IF EXISTS "apprepo_status_position_idx";
+
+ALTER TABLE "apprepo"
+    DROP COLUMN IF EXISTS "is_featured",
+    DROP COLUMN IF EXISTS "status",
+    DROP COLUMN IF EXISTS "position",
+    DROP COLUMN IF EXISTS "category_id";
+
+DROP TABLE IF EXISTS "apprepo_category";
+DROP TYPE IF EXISTS "apprepo_status";
@@ -0,0 +1,31 @@
+-- Your SQL goes here
+CREATE TYPE "apprepo_status" AS ENUM ('draft', 'published', 'archived');
+
+CREATE TABLE IF NOT EXISTS "apprepo_category" (
+    "id" uuid DEFAULT gen_random_uuid (),
+    "name" TEXT NOT NULL,
+    "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "updated_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY ("id"),
+    UNIQUE ("name")
+);
+
+-- The existing apprepos stay published in the order they were added, new ones start as drafts
+ALTER TABLE "apprepo"
+    ADD COLUMN "category_id" uuid REFERENCES "apprepo_category" ("id") ON DELETE SET NULL,
+    ADD COLUMN "position" INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN "status" apprepo_status NOT NULL DEFAULT 'published',
+    ADD COLUMN "is_featured" BOOLEAN NOT NULL DEFAULT FALSE;
+
+UPDATE "apprepo"
+SET "position" = "ordered"."position"
+FROM (
+    SELECT "id", ROW_NUMBER() OVER (ORDER BY "created_at", "name") - 1 AS "position"
+    FROM "apprepo"
+) AS "ordered"
+WHERE "apprepo"."id" = "ordered"."id";
+
+ALTER TABLE "apprepo" ALTER COLUMN "status" SET DEFAULT 'draft';
+
+CREATE INDEX IF NOT EXISTS "apprepo_status_position_idx" ON "apprepo" ("status", "position");
+CREATE INDEX IF NOT EXISTS "apprepo_category_id_idx" ON "apprepo" ("category_id");
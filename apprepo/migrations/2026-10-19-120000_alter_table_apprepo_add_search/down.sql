@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS "apprepo_search_idx";
+DROP FUNCTION IF EXISTS "apprepo_search_vector"(TEXT, TEXT, TEXT[]);
+DROP FUNCTION IF EXISTS "apprepo_escape_html"(TEXT);
+
+ALTER TABLE "apprepo"
+    DROP COLUMN IF EXISTS "tags",
+    DROP COLUMN IF EXISTS "description";
@@ -0,0 +1,28 @@
+-- Your SQL goes here
+ALTER TABLE "apprepo"
+    ADD COLUMN "description" TEXT NOT NULL DEFAULT '',
+    ADD COLUMN "tags" TEXT[] NOT NULL DEFAULT '{}';
+
+-- The names weigh the most, then the tags, then the descriptions. The simple configuration
+-- keeps the words as they are written so that they can be matched by their prefix.
+CREATE OR REPLACE FUNCTION "apprepo_search_vector"("name" TEXT, "description" TEXT, "tags" TEXT[])
+RETURNS tsvector
+LANGUAGE SQL IMMUTABLE PARALLEL SAFE
+AS $$
+    SELECT setweight(to_tsvector('simple', "name"), 'A')
+        || setweight(to_tsvector('simple', array_to_string("tags", ' ')), 'B')
+        || setweight(to_tsvector('simple', "description"), 'C')
+$$;
+
+CREATE INDEX IF NOT EXISTS "apprepo_search_idx" ON "apprepo"
+    USING GIN (apprepo_search_vector("name", "description", "tags"));
+
+-- The highlights of the search results are HTML, the text is escaped before the matched
+-- words are wrapped in marks
+CREATE OR REPLACE FUNCTION "apprepo_escape_html"("text" TEXT)
+RETURNS TEXT
+LANGUAGE SQL IMMUTABLE PARALLEL SAFE
+AS $$
+    SELECT replace(replace(replace(replace(replace("text",
+        '&', '&amp;'), '<', '&lt;'), '>', '&gt;'), '"', '&quot;'), '''', '&#39;')
+$$;
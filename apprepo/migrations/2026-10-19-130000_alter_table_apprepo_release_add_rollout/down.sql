@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS "apprepo_release_update_idx";
+
+ALTER TABLE "apprepo_release"
+    DROP COLUMN IF EXISTS "signature",
+    DROP COLUMN IF EXISTS "rollout_percentage",
+    DROP COLUMN IF EXISTS "channel";
+
+DROP TYPE IF EXISTS "apprepo_release_channel";
@@ -0,0 +1,16 @@
+-- Your SQL goes here
+CREATE TYPE "apprepo_release_channel" AS ENUM ('stable', 'beta');
+
+ALTER TABLE "apprepo_release"
+    ADD COLUMN "channel" apprepo_release_channel NOT NULL DEFAULT 'stable',
+    ADD COLUMN "rollout_percentage" INTEGER NOT NULL DEFAULT 100
+        CHECK ("rollout_percentage" BETWEEN 0 AND 100),
+    ADD COLUMN "signature" TEXT;
+
+-- The pre-releases that were published before the channels go to the beta channel
+UPDATE "apprepo_release"
+SET "channel" = 'beta'
+WHERE split_part("version", '+', 1) LIKE '%-%';
+
+CREATE INDEX IF NOT EXISTS "apprepo_release_update_idx" ON "apprepo_release" ("apprepo_id", "platform")
+    WHERE "yanked_at" IS NULL;
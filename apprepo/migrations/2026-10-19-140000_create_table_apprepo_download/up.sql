@@ -0,0 +1,16 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS "apprepo_download" (
+    "release_id" uuid NOT NULL,
+    "apprepo_id" uuid NOT NULL,
+    "date" DATE NOT NULL,
+    "platform" apprepo_release_platform NOT NULL,
+    "referrer" TEXT NOT NULL DEFAULT '',
+    "count" INTEGER NOT NULL DEFAULT 0,
+    "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "updated_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY ("release_id", "date", "referrer"),
+    FOREIGN KEY ("release_id") REFERENCES "apprepo_release" ("id") ON DELETE CASCADE,
+    FOREIGN KEY ("apprepo_id") REFERENCES "apprepo" ("id") ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS "apprepo_download_apprepo_id_date_idx" ON "apprepo_download" ("apprepo_id", "date");
@@ -1,10 +1,21 @@
-use std::str::FromStr;
+use std::{collections::HashMap, str::FromStr};
 
-use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
-use tonic::{Request, Response, Result, Status};
+use chrono::{NaiveDate, Utc};
+
+use diesel::{
+    expression::BoxableExpression, pg::Pg, sql_types::Bool, BoolExpressionMethods, Connection,
+    ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl,
+};
+use futures::{Stream, StreamExt};
+use tonic::{Request, Response, Result, Status, Streaming};
+use tools_apprepo::manifest;
 use uuid::Uuid;
 
 use crate::{
+    helper::{
+        apprepo, apprepo_download, apprepo_file, apprepo_manifest, apprepo_release, apprepo_search,
+        apprepo_update,
+    },
     model,
     proto::{self, apprepo::ApprepoService},
     schema,
@@ -16,29 +27,117 @@ use super::ApprepoController;
 impl ApprepoService for ApprepoController {
     async fn get_apprepos(
         &self,
-        _req: Request<proto::apprepo::GetAppreposReq>,
+        req: Request<proto::apprepo::GetAppreposReq>,
     ) -> Result<Response<proto::apprepo::Apprepos>> {
         let db_conn =
             &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
                 .map_err(|e| Status::internal(e.to_string()))?;
 
-        // Get all apprepos
-        let apprepos = schema::apprepo::table
+        // Check if the filters are valid
+        let status = match req.get_ref().status {
+            Some(status) => Some(
+                model::ApprepoStatus::from_grpc_enum(status)
+                    .ok_or_else(|| Status::aborted("The status is not valid."))?,
+            ),
+            None => None,
+        };
+        let category_id = req
+            .get_ref()
+            .category_id
+            .as_ref()
+            .map(|category_id| Uuid::from_str(category_id))
+            .transpose()
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        if req.get_ref().offset < 0 || req.get_ref().limit < 0 {
+            return Err(Status::aborted(
+                "The offset and the limit can't be negative.",
+            ));
+        }
+
+        // Count the apprepos that match the filters, then get the page of them
+        let query = || {
+            let mut query = schema::apprepo::table.into_boxed();
+            if let Some(status) = status {
+                query = query.filter(schema::apprepo::status.eq(status));
+            }
+            if let Some(category_id) = category_id {
+                query = query.filter(schema::apprepo::category_id.eq(category_id));
+            }
+            if let Some(is_featured) = req.get_ref().is_featured {
+                query = query.filter(schema::apprepo::is_featured.eq(is_featured));
+            }
+            query
+        };
+        let total = query()
+            .count()
+            .get_result::<i64>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let mut page = query()
+            .order((schema::apprepo::position.asc(), schema::apprepo::name.asc()))
+            .offset(req.get_ref().offset);
+        if req.get_ref().limit > 0 {
+            page = page.limit(req.get_ref().limit);
+        }
+        let apprepos = page
             .load::<model::Apprepo>(db_conn)
             .map_err(|e| Status::internal(e.to_string()))?;
 
         Ok(Response::new(proto::apprepo::Apprepos {
-            apprepos: apprepos
+            apprepos: apprepos.iter().map(model::Apprepo::to_grpc).collect(),
+            total,
+        }))
+    }
+
+    async fn search_apprepos(
+        &self,
+        req: Request<proto::apprepo::SearchAppreposReq>,
+    ) -> Result<Response<proto::apprepo::ApprepoSearchResults>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Check if the query and the filters are valid
+        let tsquery = apprepo_search::to_prefix_tsquery(&req.get_ref().query)
+            .ok_or_else(|| Status::aborted("The query has no words to search."))?;
+        let status = match req.get_ref().status {
+            Some(status) => Some(
+                model::ApprepoStatus::from_grpc_enum(status)
+                    .ok_or_else(|| Status::aborted("The status is not valid."))?,
+            ),
+            None => None,
+        };
+        let category_id = req
+            .get_ref()
+            .category_id
+            .as_ref()
+            .map(|category_id| Uuid::from_str(category_id))
+            .transpose()
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        if req.get_ref().offset < 0 || req.get_ref().limit < 0 {
+            return Err(Status::aborted(
+                "The offset and the limit can't be negative.",
+            ));
+        }
+
+        // Search the apprepos
+        let (results, total) = apprepo_search::search(
+            db_conn,
+            &tsquery,
+            &apprepo_search::Filter {
+                status,
+                category_id,
+            },
+            req.get_ref().offset,
+            req.get_ref().limit,
+        )
+        .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(proto::apprepo::ApprepoSearchResults {
+            results: results
                 .iter()
-                .map(|apprepo| proto::apprepo::Apprepo {
-                    id: apprepo.id.to_string(),
-                    name: apprepo.name.to_owned(),
-                    icon: apprepo.icon.to_owned(),
-                    link: apprepo.link.to_owned(),
-                    created_at: apprepo.created_at.to_string(),
-                    updated_at: apprepo.updated_at.to_string(),
-                })
+                .map(model::ApprepoSearchResult::to_grpc)
                 .collect(),
+            total,
         }))
     }
 
@@ -50,24 +149,46 @@ impl ApprepoService for ApprepoController {
             &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
                 .map_err(|e| Status::internal(e.to_string()))?;
 
-        // Add an apprepo to database
-        let apprepo = diesel::insert_into(schema::apprepo::table)
-            .values((
-                schema::apprepo::name.eq(&req.get_ref().name),
-                schema::apprepo::icon.eq(&req.get_ref().icon),
-                schema::apprepo::link.eq(&req.get_ref().link),
-            ))
-            .get_result::<model::Apprepo>(db_conn)
+        // Check if the category and the status are valid
+        let category_id = req
+            .get_ref()
+            .category_id
+            .as_ref()
+            .map(|category_id| Uuid::from_str(category_id))
+            .transpose()
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        let status = match req.get_ref().status {
+            Some(status) => model::ApprepoStatus::from_grpc_enum(status)
+                .ok_or_else(|| Status::aborted("The status is not valid."))?,
+            None => model::ApprepoStatus::Draft,
+        };
+        let tags = apprepo::parse_tags(&req.get_ref().tags).map_err(Status::aborted)?;
+
+        // Add an apprepo to database after the others
+        let apprepo = db_conn
+            .transaction(|db_conn| {
+                let position = schema::apprepo::table
+                    .select(diesel::dsl::max(schema::apprepo::position))
+                    .first::<Option<i32>>(db_conn)?
+                    .map_or(0, |position| position + 1);
+
+                diesel::insert_into(schema::apprepo::table)
+                    .values((
+                        schema::apprepo::name.eq(&req.get_ref().name),
+                        schema::apprepo::icon.eq(&req.get_ref().icon),
+                        schema::apprepo::link.eq(&req.get_ref().link),
+                        schema::apprepo::category_id.eq(category_id),
+                        schema::apprepo::position.eq(position),
+                        schema::apprepo::status.eq(status),
+                        schema::apprepo::is_featured.eq(req.get_ref().is_featured),
+                        schema::apprepo::description.eq(&req.get_ref().description),
+                        schema::apprepo::tags.eq(&tags),
+                    ))
+                    .get_result::<model::Apprepo>(db_conn)
+            })
             .map_err(|e| Status::internal(e.to_string()))?;
 
-        Ok(Response::new(proto::apprepo::Apprepo {
-            id: apprepo.id.to_string(),
-            name: apprepo.name,
-            icon: apprepo.icon,
-            link: apprepo.link,
-            created_at: apprepo.created_at.to_string(),
-            updated_at: apprepo.updated_at.to_string(),
-        }))
+        Ok(Response::new(apprepo.to_grpc()))
     }
 
     async fn update_apprepo(
@@ -78,6 +199,30 @@ impl ApprepoService for ApprepoController {
             &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
                 .map_err(|e| Status::internal(e.to_string()))?;
 
+        // Check if the category and the status are valid
+        let category_id = match &req.get_ref().category_id {
+            Some(category_id) if category_id.is_empty() => Some(None),
+            Some(category_id) => Some(Some(
+                Uuid::from_str(category_id).map_err(|e| Status::aborted(e.to_string()))?,
+            )),
+            None => None,
+        };
+        let status = match req.get_ref().status {
+            Some(status) => Some(
+                model::ApprepoStatus::from_grpc_enum(status)
+                    .ok_or_else(|| Status::aborted("The status is not valid."))?,
+            ),
+            None => None,
+        };
+
+        let tags = req
+            .get_ref()
+            .tags
+            .as_ref()
+            .map(|tags| apprepo::parse_tags(&tags.tags))
+            .transpose()
+            .map_err(Status::aborted)?;
+
         // Update the link
         let apprepo_id =
             Uuid::from_str(&req.get_ref().id).map_err(|e| Status::aborted(e.to_string()))?;
@@ -87,20 +232,18 @@ impl ApprepoService for ApprepoController {
                     name: req.get_ref().name.to_owned(),
                     icon: req.get_ref().icon.to_owned(),
                     link: req.get_ref().link.to_owned(),
+                    category_id,
+                    status,
+                    is_featured: req.get_ref().is_featured,
+                    description: req.get_ref().description.to_owned(),
+                    tags,
                 },
                 schema::apprepo::updated_at.eq(diesel::dsl::now),
             ))
             .get_result::<model::Apprepo>(db_conn)
             .map_err(|e| Status::internal(e.to_string()))?;
 
-        Ok(Response::new(proto::apprepo::Apprepo {
-            id: apprepo.id.to_string(),
-            name: apprepo.name,
-            icon: apprepo.icon,
-            link: apprepo.link,
-            created_at: apprepo.created_at.to_string(),
-            updated_at: apprepo.updated_at.to_string(),
-        }))
+        Ok(Response::new(apprepo.to_grpc()))
     }
 
     async fn delete_apprepo(
@@ -120,4 +263,817 @@ impl ApprepoService for ApprepoController {
 
         Ok(Response::new(proto::apprepo::OpRes { is_success: true }))
     }
+
+    async fn reorder_apprepos(
+        &self,
+        req: Request<proto::apprepo::ReorderAppreposReq>,
+    ) -> Result<Response<proto::apprepo::OpRes>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        let ids = req
+            .get_ref()
+            .ids
+            .iter()
+            .map(|id| Uuid::from_str(id))
+            .collect::<Result<Vec<Uuid>, uuid::Error>>()
+            .map_err(|e| Status::aborted(e.to_string()))?;
+
+        // Put the apprepos in the order of the ids
+        let current_ids = schema::apprepo::table
+            .select(schema::apprepo::id)
+            .order((schema::apprepo::position.asc(), schema::apprepo::name.asc()))
+            .load::<Uuid>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let ordered_ids = apprepo::reorder(&current_ids, &ids).map_err(Status::aborted)?;
+
+        // Set the positions of the apprepos to the new order
+        db_conn
+            .transaction::<_, diesel::result::Error, _>(|db_conn| {
+                for (position, id) in ordered_ids.iter().enumerate() {
+                    diesel::update(schema::apprepo::table.find(id))
+                        .set(schema::apprepo::position.eq(position as i32))
+                        .execute(db_conn)?;
+                }
+
+                Ok(())
+            })
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(proto::apprepo::OpRes { is_success: true }))
+    }
+
+    async fn get_apprepo_categories(
+        &self,
+        _req: Request<proto::apprepo::GetApprepoCategoriesReq>,
+    ) -> Result<Response<proto::apprepo::ApprepoCategories>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Get all categories by name
+        let categories = schema::apprepo_category::table
+            .order(schema::apprepo_category::name.asc())
+            .load::<model::ApprepoCategory>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(proto::apprepo::ApprepoCategories {
+            categories: categories
+                .iter()
+                .map(model::ApprepoCategory::to_grpc)
+                .collect(),
+        }))
+    }
+
+    async fn create_apprepo_category(
+        &self,
+        req: Request<proto::apprepo::CreateApprepoCategoryReq>,
+    ) -> Result<Response<proto::apprepo::ApprepoCategory>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Check if the name is valid
+        let name = req.get_ref().name.trim();
+        if name.is_empty() {
+            return Err(Status::aborted("The name can't be empty."));
+        }
+
+        // Add a category to database
+        let category = diesel::insert_into(schema::apprepo_category::table)
+            .values(schema::apprepo_category::name.eq(name))
+            .get_result::<model::ApprepoCategory>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(category.to_grpc()))
+    }
+
+    async fn update_apprepo_category(
+        &self,
+        req: Request<proto::apprepo::UpdateApprepoCategoryReq>,
+    ) -> Result<Response<proto::apprepo::ApprepoCategory>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Check if the name is valid
+        let category_id =
+            Uuid::from_str(&req.get_ref().id).map_err(|e| Status::aborted(e.to_string()))?;
+        let name = req.get_ref().name.trim();
+        if name.is_empty() {
+            return Err(Status::aborted("The name can't be empty."));
+        }
+
+        // Rename the category
+        let category = diesel::update(schema::apprepo_category::table.find(&category_id))
+            .set((
+                schema::apprepo_category::name.eq(name),
+                schema::apprepo_category::updated_at.eq(diesel::dsl::now),
+            ))
+            .get_result::<model::ApprepoCategory>(db_conn)
+            .optional()
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| Status::aborted("The category is not found."))?;
+
+        Ok(Response::new(category.to_grpc()))
+    }
+
+    async fn delete_apprepo_category(
+        &self,
+        req: Request<proto::apprepo::DeleteApprepoCategoryReq>,
+    ) -> Result<Response<proto::apprepo::OpRes>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Delete the category, its apprepos are kept without one
+        let category_id =
+            Uuid::from_str(&req.get_ref().id).map_err(|e| Status::aborted(e.to_string()))?;
+        diesel::delete(schema::apprepo_category::table.find(&category_id))
+            .execute(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(proto::apprepo::OpRes { is_success: true }))
+    }
+
+    async fn get_apprepo_releases(
+        &self,
+        req: Request<proto::apprepo::GetApprepoReleasesReq>,
+    ) -> Result<Response<proto::apprepo::ApprepoReleases>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Get the releases of the apprepo
+        let apprepo_id = Uuid::from_str(&req.get_ref().apprepo_id)
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        let mut query = schema::apprepo_release::table
+            .filter(schema::apprepo_release::apprepo_id.eq(&apprepo_id))
+            .into_boxed();
+        if !req.get_ref().include_yanked {
+            query = query.filter(schema::apprepo_release::yanked_at.is_null());
+        }
+        let mut releases = query
+            .load::<model::ApprepoRelease>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        apprepo_release::sort_releases(&mut releases);
+
+        // The releases are downloaded through the redirect that counts them
+        Ok(Response::new(proto::apprepo::ApprepoReleases {
+            releases: releases
+                .iter()
+                .map(|release| {
+                    apprepo_download::to_tracked_grpc(release, &self.download_redirect_url)
+                })
+                .collect(),
+        }))
+    }
+
+    async fn get_latest_apprepo_releases(
+        &self,
+        req: Request<proto::apprepo::GetLatestApprepoReleasesReq>,
+    ) -> Result<Response<proto::apprepo::ApprepoReleases>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Get the releases that aren't yanked, of one platform or of all of them
+        let apprepo_id = Uuid::from_str(&req.get_ref().apprepo_id)
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        let mut query = schema::apprepo_release::table
+            .filter(schema::apprepo_release::apprepo_id.eq(&apprepo_id))
+            .filter(schema::apprepo_release::yanked_at.is_null())
+            .into_boxed();
+        if let Some(platform) = req.get_ref().platform {
+            let platform = model::ApprepoReleasePlatform::from_grpc_enum(platform)
+                .ok_or_else(|| Status::aborted("The platform is not valid."))?;
+            query = query.filter(schema::apprepo_release::platform.eq(platform));
+        }
+        let releases = query
+            .load::<model::ApprepoRelease>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Keep the newest of every platform and architecture
+        let releases =
+            apprepo_release::latest_releases(releases, req.get_ref().include_prereleases);
+
+        // The releases are downloaded through the redirect that counts them
+        Ok(Response::new(proto::apprepo::ApprepoReleases {
+            releases: releases
+                .iter()
+                .map(|release| {
+                    apprepo_download::to_tracked_grpc(release, &self.download_redirect_url)
+                })
+                .collect(),
+        }))
+    }
+
+    async fn publish_apprepo_release(
+        &self,
+        req: Request<proto::apprepo::PublishApprepoReleaseReq>,
+    ) -> Result<Response<proto::apprepo::ApprepoRelease>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Check the release
+        let apprepo_id = Uuid::from_str(&req.get_ref().apprepo_id)
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        let version =
+            apprepo_release::parse_version(&req.get_ref().version).map_err(Status::aborted)?;
+        let is_prerelease = !version.pre.is_empty();
+        let version = version.to_string();
+        let platform = model::ApprepoReleasePlatform::from_grpc_enum(req.get_ref().platform)
+            .ok_or_else(|| Status::aborted("The platform is not valid."))?;
+        let arch = model::ApprepoReleaseArch::from_grpc_enum(req.get_ref().arch)
+            .ok_or_else(|| Status::aborted("The architecture is not valid."))?;
+        apprepo_release::validate_download_url(&req.get_ref().download_url)
+            .map_err(Status::aborted)?;
+        let checksum =
+            apprepo_release::parse_checksum(&req.get_ref().checksum).map_err(Status::aborted)?;
+        let released_at = req
+            .get_ref()
+            .released_at
+            .as_deref()
+            .map(apprepo_release::parse_released_at)
+            .transpose()
+            .map_err(Status::aborted)?;
+        let channel = model::ApprepoReleaseChannel::from_grpc_enum(req.get_ref().channel)
+            .ok_or_else(|| Status::aborted("The channel is not valid."))?;
+        if channel == model::ApprepoReleaseChannel::Stable && is_prerelease {
+            return Err(Status::aborted(
+                "A pre-release can only be published to the beta channel.",
+            ));
+        }
+        let rollout_percentage = req.get_ref().rollout_percentage.unwrap_or(100);
+        apprepo_update::validate_rollout_percentage(rollout_percentage).map_err(Status::aborted)?;
+        let signature = req
+            .get_ref()
+            .signature
+            .as_deref()
+            .map(str::trim)
+            .filter(|signature| !signature.is_empty());
+
+        // Check if the apprepo exists
+        schema::apprepo::table
+            .find(&apprepo_id)
+            .select(schema::apprepo::id)
+            .first::<Uuid>(db_conn)
+            .optional()
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| Status::aborted("The apprepo is not found."))?;
+
+        // Check if the version has been published for the platform and the architecture,
+        // a yanked release keeps its version
+        let is_published = schema::apprepo_release::table
+            .filter(schema::apprepo_release::apprepo_id.eq(&apprepo_id))
+            .filter(schema::apprepo_release::version.eq(&version))
+            .filter(schema::apprepo_release::platform.eq(platform))
+            .filter(schema::apprepo_release::arch.eq(arch))
+            .select(schema::apprepo_release::id)
+            .first::<Uuid>(db_conn)
+            .optional()
+            .map_err(|e| Status::internal(e.to_string()))?
+            .is_some();
+        if is_published {
+            return Err(Status::aborted(
+                "The version has been published for the platform and the architecture.",
+            ));
+        }
+
+        // Add the release to database
+        let release = diesel::insert_into(schema::apprepo_release::table)
+            .values((
+                schema::apprepo_release::apprepo_id.eq(&apprepo_id),
+                schema::apprepo_release::version.eq(&version),
+                schema::apprepo_release::platform.eq(platform),
+                schema::apprepo_release::arch.eq(arch),
+                schema::apprepo_release::download_url.eq(&req.get_ref().download_url),
+                schema::apprepo_release::checksum.eq(&checksum),
+                schema::apprepo_release::changelog.eq(&req.get_ref().changelog),
+                schema::apprepo_release::released_at
+                    .eq(released_at.unwrap_or_else(|| Utc::now().naive_utc())),
+                schema::apprepo_release::channel.eq(channel),
+                schema::apprepo_release::rollout_percentage.eq(rollout_percentage),
+                schema::apprepo_release::signature.eq(signature),
+            ))
+            .get_result::<model::ApprepoRelease>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(release.to_grpc()))
+    }
+
+    async fn yank_apprepo_release(
+        &self,
+        req: Request<proto::apprepo::YankApprepoReleaseReq>,
+    ) -> Result<Response<proto::apprepo::ApprepoRelease>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Yank the release, it stays listed with the yanked ones but isn't offered anymore
+        let release_id =
+            Uuid::from_str(&req.get_ref().id).map_err(|e| Status::aborted(e.to_string()))?;
+        let release = diesel::update(
+            schema::apprepo_release::table
+                .find(&release_id)
+                .filter(schema::apprepo_release::yanked_at.is_null()),
+        )
+        .set((
+            schema::apprepo_release::yanked_at.eq(diesel::dsl::now),
+            schema::apprepo_release::yanked_reason.eq(&req.get_ref().reason),
+            schema::apprepo_release::updated_at.eq(diesel::dsl::now),
+        ))
+        .get_result::<model::ApprepoRelease>(db_conn)
+        .optional()
+        .map_err(|e| Status::internal(e.to_string()))?
+        .ok_or_else(|| Status::aborted("The release is not found or has been yanked."))?;
+
+        Ok(Response::new(release.to_grpc()))
+    }
+
+    async fn set_apprepo_release_rollout(
+        &self,
+        req: Request<proto::apprepo::SetApprepoReleaseRolloutReq>,
+    ) -> Result<Response<proto::apprepo::ApprepoRelease>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Check the percentage
+        let release_id =
+            Uuid::from_str(&req.get_ref().id).map_err(|e| Status::aborted(e.to_string()))?;
+        apprepo_update::validate_rollout_percentage(req.get_ref().rollout_percentage)
+            .map_err(Status::aborted)?;
+
+        // Roll the release out to the percentage of the installs
+        let release = diesel::update(schema::apprepo_release::table.find(&release_id))
+            .set((
+                schema::apprepo_release::rollout_percentage.eq(req.get_ref().rollout_percentage),
+                schema::apprepo_release::updated_at.eq(diesel::dsl::now),
+            ))
+            .get_result::<model::ApprepoRelease>(db_conn)
+            .optional()
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| Status::aborted("The release is not found."))?;
+
+        Ok(Response::new(release.to_grpc()))
+    }
+
+    async fn check_apprepo_update(
+        &self,
+        req: Request<proto::apprepo::CheckApprepoUpdateReq>,
+    ) -> Result<Response<proto::apprepo::CheckApprepoUpdateRes>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Check the install
+        let apprepo_id = Uuid::from_str(&req.get_ref().apprepo_id)
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        let current_version = apprepo_release::parse_version(&req.get_ref().current_version)
+            .map_err(Status::aborted)?;
+        let platform = model::ApprepoReleasePlatform::from_grpc_enum(req.get_ref().platform)
+            .ok_or_else(|| Status::aborted("The platform is not valid."))?;
+        let arch = match req.get_ref().arch {
+            Some(arch) => Some(
+                model::ApprepoReleaseArch::from_grpc_enum(arch)
+                    .ok_or_else(|| Status::aborted("The architecture is not valid."))?,
+            ),
+            None => None,
+        };
+        let channel = model::ApprepoReleaseChannel::from_grpc_enum(req.get_ref().channel)
+            .ok_or_else(|| Status::aborted("The channel is not valid."))?;
+        let install_id =
+            apprepo_update::parse_install_id(&req.get_ref().install_id).map_err(Status::aborted)?;
+
+        // Check if the apprepo is published, the others don't offer updates
+        schema::apprepo::table
+            .find(&apprepo_id)
+            .filter(schema::apprepo::status.eq(model::ApprepoStatus::Published))
+            .select(schema::apprepo::id)
+            .first::<Uuid>(db_conn)
+            .optional()
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| Status::aborted("The apprepo is not found."))?;
+
+        // Find the newest release for the install
+        let releases = schema::apprepo_release::table
+            .filter(schema::apprepo_release::apprepo_id.eq(&apprepo_id))
+            .filter(schema::apprepo_release::platform.eq(platform))
+            .filter(schema::apprepo_release::yanked_at.is_null())
+            .load::<model::ApprepoRelease>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let release =
+            apprepo_update::find_update(releases, &current_version, channel, arch, install_id);
+
+        // The install downloads the release through the redirect that counts it
+        let release = release.map(|release| {
+            apprepo_download::to_tracked_grpc(&release, &self.download_redirect_url)
+        });
+
+        Ok(Response::new(proto::apprepo::CheckApprepoUpdateRes {
+            is_available: release.is_some(),
+            release,
+        }))
+    }
+
+    async fn get_apprepo_manifest(
+        &self,
+        req: Request<proto::apprepo::GetApprepoManifestReq>,
+    ) -> Result<Response<proto::apprepo::ApprepoManifest>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Check the version
+        let apprepo_id = Uuid::from_str(&req.get_ref().apprepo_id)
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        let version = apprepo_release::parse_version(&req.get_ref().version)
+            .map_err(Status::aborted)?
+            .to_string();
+
+        // Check if the apprepo is published, the others don't offer downloads
+        let apprepo = schema::apprepo::table
+            .find(&apprepo_id)
+            .filter(schema::apprepo::status.eq(model::ApprepoStatus::Published))
+            .first::<model::Apprepo>(db_conn)
+            .optional()
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| Status::aborted("The apprepo is not found."))?;
+
+        // Get the releases of the version that aren't yanked
+        let releases = schema::apprepo_release::table
+            .filter(schema::apprepo_release::apprepo_id.eq(&apprepo_id))
+            .filter(schema::apprepo_release::version.eq(&version))
+            .filter(schema::apprepo_release::yanked_at.is_null())
+            .load::<model::ApprepoRelease>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        if releases.is_empty() {
+            return Err(Status::aborted("The release is not found."));
+        }
+
+        // Sign the exact bytes that are served, the apps verify them before parsing
+        let manifest = serde_json::to_vec(&apprepo_manifest::to_manifest(
+            &apprepo,
+            &version,
+            &releases,
+            &self.download_redirect_url,
+        ))
+        .map_err(|e| Status::internal(e.to_string()))?;
+        let signature = manifest::sign(&manifest, &self.signing_key);
+
+        Ok(Response::new(proto::apprepo::ApprepoManifest {
+            manifest,
+            key_id: signature.key_id,
+            algorithm: signature.algorithm,
+            signature: signature.signature,
+        }))
+    }
+
+    async fn get_apprepo_manifest_keys(
+        &self,
+        _req: Request<proto::apprepo::GetApprepoManifestKeysReq>,
+    ) -> Result<Response<proto::apprepo::ApprepoManifestKeys>> {
+        // The current key comes first, then the older ones that are still trusted
+        let current_key = self.signing_key.verifying_key();
+        let keys = std::iter::once(&current_key)
+            .chain(self.trusted_keys.iter().filter(|key| **key != current_key))
+            .map(|key| proto::apprepo::ApprepoManifestKey {
+                key_id: manifest::key_id(key),
+                algorithm: manifest::SIGNATURE_ALGORITHM.to_string(),
+                public_key: manifest::encode_public_key(key),
+                is_current: *key == current_key,
+            })
+            .collect();
+
+        Ok(Response::new(proto::apprepo::ApprepoManifestKeys { keys }))
+    }
+
+    async fn download_apprepo_release(
+        &self,
+        req: Request<proto::apprepo::DownloadApprepoReleaseReq>,
+    ) -> Result<Response<proto::apprepo::ApprepoDownload>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        let release_id = Uuid::from_str(&req.get_ref().release_id)
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        let referrer = apprepo_download::parse_referrer(req.get_ref().referrer.as_deref());
+
+        // Get the release, only the ones that aren't yanked of the published apprepos are
+        // downloaded
+        let release = schema::apprepo_release::table
+            .inner_join(schema::apprepo::table)
+            .filter(schema::apprepo_release::id.eq(&release_id))
+            .filter(schema::apprepo_release::yanked_at.is_null())
+            .filter(schema::apprepo::status.eq(model::ApprepoStatus::Published))
+            .select(schema::apprepo_release::all_columns)
+            .first::<model::ApprepoRelease>(db_conn)
+            .optional()
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| Status::aborted("The release is not found."))?;
+
+        // Count the download in the bucket of the day and the referrer
+        diesel::insert_into(schema::apprepo_download::table)
+            .values((
+                schema::apprepo_download::release_id.eq(&release.id),
+                schema::apprepo_download::apprepo_id.eq(&release.apprepo_id),
+                schema::apprepo_download::date.eq(Utc::now().date_naive()),
+                schema::apprepo_download::platform.eq(release.platform),
+                schema::apprepo_download::referrer.eq(&referrer),
+                schema::apprepo_download::count.eq(1),
+            ))
+            .on_conflict((
+                schema::apprepo_download::release_id,
+                schema::apprepo_download::date,
+                schema::apprepo_download::referrer,
+            ))
+            .do_update()
+            .set((
+                schema::apprepo_download::count.eq(schema::apprepo_download::count + 1),
+                schema::apprepo_download::updated_at.eq(diesel::dsl::now),
+            ))
+            .execute(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(proto::apprepo::ApprepoDownload {
+            download_url: release.download_url,
+        }))
+    }
+
+    async fn get_apprepo_download_stats(
+        &self,
+        req: Request<proto::apprepo::GetApprepoDownloadStatsReq>,
+    ) -> Result<Response<proto::apprepo::ApprepoDownloadStats>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Check the filters
+        let apprepo_id = Uuid::from_str(&req.get_ref().apprepo_id)
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        let release_id = req
+            .get_ref()
+            .release_id
+            .as_ref()
+            .map(|release_id| Uuid::from_str(release_id))
+            .transpose()
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        let (from, to) = apprepo_download::parse_date_range(
+            req.get_ref().from.as_deref(),
+            req.get_ref().to.as_deref(),
+        )
+        .map_err(Status::aborted)?;
+
+        // Sum the daily counts that match the filters, in total and by every dimension
+        let filter = || {
+            let mut filter: Box<
+                dyn BoxableExpression<schema::apprepo_download::table, Pg, SqlType = Bool>,
+            > = Box::new(schema::apprepo_download::apprepo_id.eq(apprepo_id));
+            if let Some(release_id) = release_id {
+                filter = Box::new(filter.and(schema::apprepo_download::release_id.eq(release_id)));
+            }
+            if let Some(from) = from {
+                filter = Box::new(filter.and(schema::apprepo_download::date.ge(from)));
+            }
+            if let Some(to) = to {
+                filter = Box::new(filter.and(schema::apprepo_download::date.le(to)));
+            }
+            filter
+        };
+        let downloads = diesel::dsl::sum(schema::apprepo_download::count);
+        let total = schema::apprepo_download::table
+            .filter(filter())
+            .select(downloads)
+            .first::<Option<i64>>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let by_date = schema::apprepo_download::table
+            .filter(filter())
+            .group_by(schema::apprepo_download::date)
+            .select((schema::apprepo_download::date, downloads))
+            .order(schema::apprepo_download::date.asc())
+            .load::<(NaiveDate, Option<i64>)>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let by_platform = schema::apprepo_download::table
+            .filter(filter())
+            .group_by(schema::apprepo_download::platform)
+            .select((schema::apprepo_download::platform, downloads))
+            .order(downloads.desc())
+            .load::<(model::ApprepoReleasePlatform, Option<i64>)>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let by_referrer = schema::apprepo_download::table
+            .filter(filter())
+            .group_by(schema::apprepo_download::referrer)
+            .select((schema::apprepo_download::referrer, downloads))
+            .order((downloads.desc(), schema::apprepo_download::referrer.asc()))
+            .load::<(String, Option<i64>)>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let by_release = schema::apprepo_download::table
+            .filter(filter())
+            .group_by(schema::apprepo_download::release_id)
+            .select((schema::apprepo_download::release_id, downloads))
+            .order(downloads.desc())
+            .load::<(Uuid, Option<i64>)>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Name the releases by their versions
+        let versions = schema::apprepo_release::table
+            .filter(
+                schema::apprepo_release::id
+                    .eq_any(by_release.iter().map(|(release_id, _)| release_id)),
+            )
+            .select((
+                schema::apprepo_release::id,
+                schema::apprepo_release::version,
+            ))
+            .load::<(Uuid, String)>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?
+            .into_iter()
+            .collect::<HashMap<Uuid, String>>();
+
+        Ok(Response::new(proto::apprepo::ApprepoDownloadStats {
+            total: total.unwrap_or(0),
+            by_date: by_date
+                .into_iter()
+                .map(|(date, count)| proto::apprepo::ApprepoDownloadsByDate {
+                    date: date.to_string(),
+                    count: count.unwrap_or(0),
+                })
+                .collect(),
+            by_platform: by_platform
+                .into_iter()
+                .map(
+                    |(platform, count)| proto::apprepo::ApprepoDownloadsByPlatform {
+                        platform: platform.to_grpc_enum(),
+                        count: count.unwrap_or(0),
+                    },
+                )
+                .collect(),
+            by_referrer: by_referrer
+                .into_iter()
+                .map(
+                    |(referrer, count)| proto::apprepo::ApprepoDownloadsByReferrer {
+                        referrer,
+                        count: count.unwrap_or(0),
+                    },
+                )
+                .collect(),
+            by_release: by_release
+                .into_iter()
+                .map(
+                    |(release_id, count)| proto::apprepo::ApprepoDownloadsByRelease {
+                        version: versions.get(&release_id).cloned().unwrap_or_default(),
+                        release_id: release_id.to_string(),
+                        count: count.unwrap_or(0),
+                    },
+                )
+                .collect(),
+        }))
+    }
+
+    async fn upload_apprepo_icon(
+        &self,
+        req: Request<Streaming<proto::apprepo::UploadApprepoFileReq>>,
+    ) -> Result<Response<proto::apprepo::ApprepoFile>> {
+        // Read the icon, then check and shrink it
+        let (content_type, content) = read_upload(req.into_inner(), self.icon_max_size).await?;
+        let icon_size = self.icon_size;
+        let file = tokio::task::spawn_blocking(move || {
+            apprepo_file::process_icon(&content, &content_type, icon_size)
+        })
+        .await
+        .map_err(|e| Status::internal(e.to_string()))?
+        .map_err(Status::aborted)?;
+
+        // Save the icon
+        let url = self
+            .storage
+            .put(&file.key, &file.content, &file.content_type)
+            .await
+            .map_err(Status::internal)?;
+
+        Ok(Response::new(proto::apprepo::ApprepoFile {
+            url,
+            size: file.content.len() as i64,
+            key: file.key,
+            content_type: file.content_type,
+            checksum: file.checksum,
+        }))
+    }
+
+    async fn upload_apprepo_artifact(
+        &self,
+        req: Request<Streaming<proto::apprepo::UploadApprepoFileReq>>,
+    ) -> Result<Response<proto::apprepo::ApprepoFile>> {
+        // Read the artifact
+        let (content_type, content) = read_upload(req.into_inner(), self.artifact_max_size).await?;
+        let file =
+            apprepo_file::process_artifact(content, &content_type).map_err(Status::aborted)?;
+
+        // Save the artifact
+        let url = self
+            .storage
+            .put(&file.key, &file.content, &file.content_type)
+            .await
+            .map_err(Status::internal)?;
+
+        Ok(Response::new(proto::apprepo::ApprepoFile {
+            url,
+            size: file.content.len() as i64,
+            key: file.key,
+            content_type: file.content_type,
+            checksum: file.checksum,
+        }))
+    }
+}
+
+/// Reads the content type from the first message of an upload and collects the chunks of
+/// the file.
+async fn read_upload(
+    mut stream: impl Stream<Item = Result<proto::apprepo::UploadApprepoFileReq>> + Unpin,
+    max_size: usize,
+) -> Result<(String, Vec<u8>)> {
+    let first_req = stream
+        .next()
+        .await
+        .transpose()?
+        .ok_or_else(|| Status::aborted("The file is empty."))?;
+    let content_type = first_req.content_type.trim().to_ascii_lowercase();
+    let mut content = first_req.chunk;
+    while let Some(req) = stream.next().await.transpose()? {
+        if content.len() + req.chunk.len() > max_size {
+            return Err(Status::aborted(format!(
+                "The file is larger than {max_size} bytes."
+            )));
+        }
+        content.extend_from_slice(&req.chunk);
+    }
+    apprepo_file::validate_size(&content, max_size).map_err(Status::aborted)?;
+
+    Ok((content_type, content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(clippy::result_large_err)]
+    fn upload(
+        content_type: &str,
+        chunks: &[&[u8]],
+    ) -> impl Stream<Item = Result<proto::apprepo::UploadApprepoFileReq>> + Unpin {
+        futures::stream::iter(
+            chunks
+                .iter()
+                .enumerate()
+                .map(|(index, chunk)| {
+                    Ok(proto::apprepo::UploadApprepoFileReq {
+                        content_type: match index {
+                            0 => content_type.to_string(),
+                            _ => String::new(),
+                        },
+                        chunk: chunk.to_vec(),
+                    })
+                })
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[tokio::test]
+    async fn collects_the_chunks_of_an_upload() {
+        let (content_type, content) =
+            read_upload(upload(" Image/PNG ", &[b"abc", b"def", b"g"]), 7)
+                .await
+                .unwrap();
+        assert_eq!(content_type, "image/png");
+        assert_eq!(content, b"abcdefg");
+    }
+
+    #[tokio::test]
+    async fn rejects_an_upload_larger_than_the_max_size() {
+        let status = read_upload(upload("image/png", &[b"abc", b"def", b"gh"]), 7)
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Aborted);
+        assert_eq!(status.message(), "The file is larger than 7 bytes.");
+
+        let status = read_upload(upload("image/png", &[b"abcdefgh"]), 7)
+            .await
+            .unwrap_err();
+        assert_eq!(status.message(), "The file is larger than 7 bytes.");
+    }
+
+    #[tokio::test]
+    async fn rejects_an_empty_upload() {
+        let status = read_upload(upload("image/png", &[]), 7).await.unwrap_err();
+        assert_eq!(status.message(), "The file is empty.");
+
+        let status = read_upload(upload("image/png", &[b""]), 7)
+            .await
+            .unwrap_err();
+        assert_eq!(status.message(), "The file is empty.");
+    }
 }
@@ -1,8 +1,18 @@
+use ed25519_dalek::{SigningKey, VerifyingKey};
 use tools_lib_db::pg::connection::DbPool;
 
+use crate::service::storage::Storage;
+
 mod apprepo;
 
 pub struct ApprepoController {
     pub app_mode: String,
     pub db_pool: DbPool,
+    pub storage: Storage,
+    pub icon_size: u32,
+    pub icon_max_size: usize,
+    pub artifact_max_size: usize,
+    pub download_redirect_url: String,
+    pub signing_key: SigningKey,
+    pub trusted_keys: Vec<VerifyingKey>,
 }
@@ -1,4 +1,4 @@
-use std::env;
+use std::{env, path::PathBuf};
 
 pub struct Env;
 
@@ -22,4 +22,77 @@ impl Env {
     pub fn database_url() -> String {
         env::var("DATABASE_URL").unwrap()
     }
+
+    pub fn use_storage() -> UseStorage {
+        UseStorage(env::var("USE_STORAGE").unwrap())
+    }
+
+    pub fn storage_public_url() -> String {
+        env::var("STORAGE_PUBLIC_URL").unwrap()
+    }
+
+    pub fn storage_local_dir() -> PathBuf {
+        PathBuf::from(env::var("STORAGE_LOCAL_DIR").unwrap())
+    }
+
+    pub fn s3_endpoint() -> String {
+        env::var("S3_ENDPOINT").unwrap()
+    }
+
+    pub fn s3_region() -> String {
+        env::var("S3_REGION").unwrap()
+    }
+
+    pub fn s3_bucket() -> String {
+        env::var("S3_BUCKET").unwrap()
+    }
+
+    pub fn s3_access_key() -> String {
+        env::var("S3_ACCESS_KEY").unwrap()
+    }
+
+    pub fn s3_secret_key() -> String {
+        env::var("S3_SECRET_KEY").unwrap()
+    }
+
+    pub fn icon_size() -> u32 {
+        env::var("ICON_SIZE").unwrap().parse().unwrap()
+    }
+
+    pub fn icon_max_size() -> usize {
+        env::var("ICON_MAX_SIZE").unwrap().parse().unwrap()
+    }
+
+    pub fn artifact_max_size() -> usize {
+        env::var("ARTIFACT_MAX_SIZE").unwrap().parse().unwrap()
+    }
+
+    pub fn download_redirect_url() -> String {
+        env::var("DOWNLOAD_REDIRECT_URL").unwrap()
+    }
+
+    pub fn manifest_signing_key() -> String {
+        env::var("MANIFEST_SIGNING_KEY").unwrap()
+    }
+
+    pub fn manifest_trusted_keys() -> Vec<String> {
+        env::var("MANIFEST_TRUSTED_KEYS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|key| key.trim().to_string())
+            .filter(|key| !key.is_empty())
+            .collect()
+    }
+}
+
+pub struct UseStorage(String);
+
+impl UseStorage {
+    pub fn is_local(&self) -> bool {
+        self.0 == "LOCAL"
+    }
+
+    pub fn is_s3(&self) -> bool {
+        self.0 == "S3"
+    }
 }
@@ -0,0 +1,51 @@
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
+/// Puts the given apprepos first in the order they are given, the others follow them in the
+/// order they had. Every given id has to be one of the current ones, once.
+pub fn reorder(current_ids: &[Uuid], ids: &[Uuid]) -> Result<Vec<Uuid>, String> {
+    let current = current_ids.iter().collect::<HashSet<&Uuid>>();
+    let mut seen = HashSet::new();
+    for id in ids {
+        if !current.contains(id) {
+            return Err(format!("The apprepo {id} is not found."));
+        }
+        if !seen.insert(id) {
+            return Err(format!("The apprepo {id} is given more than once."));
+        }
+    }
+
+    Ok(ids
+        .iter()
+        .chain(current_ids.iter().filter(|id| !seen.contains(id)))
+        .copied()
+        .collect())
+}
+
+const TAGS_MAX_LEN: usize = 20;
+const TAG_MAX_LEN: usize = 50;
+
+/// Trims and lowercases the tags, the empty ones and the repeated ones are dropped.
+pub fn parse_tags(tags: &[String]) -> Result<Vec<String>, String> {
+    let mut parsed_tags: Vec<String> = vec![];
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if tag.is_empty() || parsed_tags.contains(&tag) {
+            continue;
+        }
+        if tag.chars().count() > TAG_MAX_LEN {
+            return Err(format!(
+                "The tag {tag} is longer than {TAG_MAX_LEN} characters."
+            ));
+        }
+        parsed_tags.push(tag);
+    }
+    if parsed_tags.len() > TAGS_MAX_LEN {
+        return Err(format!(
+            "An apprepo can't have more than {TAGS_MAX_LEN} tags."
+        ));
+    }
+
+    Ok(parsed_tags)
+}
@@ -0,0 +1,198 @@
+use std::io::Cursor;
+
+use image::{imageops::FilterType, ImageFormat, ImageOutputFormat};
+use sha2::{Digest, Sha256};
+
+/// The content types of the icons, they are all saved as PNG.
+const ICON_CONTENT_TYPES: [(&str, ImageFormat); 3] = [
+    ("image/png", ImageFormat::Png),
+    ("image/jpeg", ImageFormat::Jpeg),
+    ("image/webp", ImageFormat::WebP),
+];
+/// The content types of the release artifacts with the extension of their file.
+const ARTIFACT_CONTENT_TYPES: [(&str, &str); 12] = [
+    ("application/octet-stream", "bin"),
+    ("application/zip", "zip"),
+    ("application/gzip", "tar.gz"),
+    ("application/x-xz", "tar.xz"),
+    ("application/x-msdownload", "exe"),
+    ("application/x-msi", "msi"),
+    ("application/x-apple-diskimage", "dmg"),
+    ("application/vnd.apple.installer+xml", "pkg"),
+    ("application/vnd.debian.binary-package", "deb"),
+    ("application/x-rpm", "rpm"),
+    ("application/x-appimage", "AppImage"),
+    ("application/vnd.android.package-archive", "apk"),
+];
+
+pub struct File {
+    pub key: String,
+    pub content: Vec<u8>,
+    pub content_type: String,
+    pub checksum: String,
+}
+
+/// The SHA-256 of the content in hex, the same as the checksum of a release.
+pub fn checksum(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+pub fn validate_size(content: &[u8], max_size: usize) -> Result<(), String> {
+    if content.is_empty() {
+        return Err("The file is empty.".to_string());
+    }
+    if content.len() > max_size {
+        return Err(format!("The file is larger than {max_size} bytes."));
+    }
+
+    Ok(())
+}
+
+/// Checks that the icon is the image its content type says, and shrinks it to fit in a
+/// square of the icon size. The icon is saved as PNG under its checksum.
+pub fn process_icon(content: &[u8], content_type: &str, icon_size: u32) -> Result<File, String> {
+    let format = ICON_CONTENT_TYPES
+        .iter()
+        .find(|(icon_content_type, _)| *icon_content_type == content_type)
+        .map(|(_, format)| *format)
+        .ok_or_else(|| {
+            format!(
+                "The icon must be one of {}.",
+                ICON_CONTENT_TYPES
+                    .map(|(content_type, _)| content_type)
+                    .join(", ")
+            )
+        })?;
+    if image::guess_format(content).ok() != Some(format) {
+        return Err(format!("The icon is not {content_type}."));
+    }
+
+    let icon = image::load_from_memory_with_format(content, format)
+        .map_err(|e| format!("The icon can't be read: {e}."))?;
+    let icon = match icon.width() > icon_size || icon.height() > icon_size {
+        true => icon.resize(icon_size, icon_size, FilterType::Lanczos3),
+        false => icon,
+    };
+
+    let mut content = Cursor::new(Vec::new());
+    icon.write_to(&mut content, ImageOutputFormat::Png)
+        .map_err(|e| e.to_string())?;
+    let content = content.into_inner();
+    let checksum = checksum(&content);
+
+    Ok(File {
+        key: format!("icons/{checksum}.png"),
+        content,
+        content_type: "image/png".to_string(),
+        checksum,
+    })
+}
+
+/// Names the artifact after its checksum, with the extension of its content type.
+pub fn process_artifact(content: Vec<u8>, content_type: &str) -> Result<File, String> {
+    let extension = ARTIFACT_CONTENT_TYPES
+        .iter()
+        .find(|(artifact_content_type, _)| *artifact_content_type == content_type)
+        .map(|(_, extension)| *extension)
+        .ok_or_else(|| {
+            format!(
+                "The artifact must be one of {}.",
+                ARTIFACT_CONTENT_TYPES
+                    .map(|(content_type, _)| content_type)
+                    .join(", ")
+            )
+        })?;
+    let checksum = checksum(&content);
+
+    Ok(File {
+        key: format!("artifacts/{checksum}.{extension}"),
+        content,
+        content_type: content_type.to_string(),
+        checksum,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{DynamicImage, RgbaImage};
+
+    use super::*;
+
+    fn encode(width: u32, height: u32, format: ImageOutputFormat) -> Vec<u8> {
+        let mut content = Cursor::new(Vec::new());
+        DynamicImage::ImageRgba8(RgbaImage::new(width, height))
+            .write_to(&mut content, format)
+            .unwrap();
+        content.into_inner()
+    }
+
+    #[test]
+    fn rejects_an_icon_that_is_not_its_content_type() {
+        let png = encode(16, 16, ImageOutputFormat::Png);
+
+        assert_eq!(
+            process_icon(&png, "image/jpeg", 256).err().as_deref(),
+            Some("The icon is not image/jpeg.")
+        );
+        assert_eq!(
+            process_icon(b"GIF89a", "image/gif", 256).err().as_deref(),
+            Some("The icon must be one of image/png, image/jpeg, image/webp.")
+        );
+        assert_eq!(
+            process_icon(b"not an image", "image/png", 256)
+                .err()
+                .as_deref(),
+            Some("The icon is not image/png.")
+        );
+    }
+
+    #[test]
+    fn shrinks_an_oversized_icon_to_fit_the_icon_size() {
+        let jpeg = encode(1024, 512, ImageOutputFormat::Jpeg(90));
+
+        let file = process_icon(&jpeg, "image/jpeg", 256).unwrap();
+        let icon = image::load_from_memory_with_format(&file.content, ImageFormat::Png).unwrap();
+        assert_eq!((icon.width(), icon.height()), (256, 128));
+        assert_eq!(file.content_type, "image/png");
+        assert_eq!(file.checksum, checksum(&file.content));
+        assert_eq!(file.key, format!("icons/{}.png", file.checksum));
+    }
+
+    #[test]
+    fn keeps_the_size_of_a_small_icon() {
+        let png = encode(64, 32, ImageOutputFormat::Png);
+
+        let file = process_icon(&png, "image/png", 256).unwrap();
+        let icon = image::load_from_memory_with_format(&file.content, ImageFormat::Png).unwrap();
+        assert_eq!((icon.width(), icon.height()), (64, 32));
+    }
+
+    #[test]
+    fn rejects_an_artifact_of_an_unknown_type() {
+        let error = process_artifact(b"hello".to_vec(), "text/plain")
+            .err()
+            .unwrap();
+        assert!(
+            error.starts_with("The artifact must be one of application/octet-stream, "),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn names_an_artifact_after_its_checksum() {
+        let file = process_artifact(b"hello".to_vec(), "application/gzip").unwrap();
+        assert_eq!(
+            file.checksum,
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        assert_eq!(
+            file.key,
+            "artifacts/2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824.tar.gz"
+        );
+        assert_eq!(file.content, b"hello");
+        assert_eq!(file.content_type, "application/gzip");
+
+        let file = process_artifact(b"hello".to_vec(), "application/x-appimage").unwrap();
+        assert_eq!(file.key, format!("artifacts/{}.AppImage", file.checksum));
+    }
+}
@@ -0,0 +1,36 @@
+use chrono::{TimeZone, Utc};
+use tools_apprepo::manifest::{Manifest, ManifestArtifact};
+
+use crate::model;
+
+use super::apprepo_download;
+
+/// Builds the manifest of a version from its releases, the artifacts are ordered by
+/// platform and architecture so the same releases always give the same bytes. They are
+/// downloaded through the redirect that counts them.
+pub fn to_manifest(
+    apprepo: &model::Apprepo,
+    version: &str,
+    releases: &[model::ApprepoRelease],
+    download_redirect_url: &str,
+) -> Manifest {
+    let mut artifacts = releases
+        .iter()
+        .map(|release| ManifestArtifact {
+            platform: release.platform.as_str().to_string(),
+            arch: release.arch.as_str().to_string(),
+            channel: release.channel.as_str().to_string(),
+            url: apprepo_download::redirect_url(download_redirect_url, &release.id),
+            sha256: release.checksum.to_owned(),
+            released_at: Utc.from_utc_datetime(&release.released_at).to_rfc3339(),
+        })
+        .collect::<Vec<_>>();
+    artifacts.sort_by(|a, b| (&a.platform, &a.arch).cmp(&(&b.platform, &b.arch)));
+
+    Manifest {
+        app_id: apprepo.id.to_string(),
+        name: apprepo.name.to_owned(),
+        version: version.to_string(),
+        artifacts,
+    }
+}
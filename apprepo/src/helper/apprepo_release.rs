@@ -0,0 +1,241 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use chrono::{DateTime, NaiveDateTime};
+use semver::Version;
+
+use crate::model;
+
+const CHECKSUM_LEN: usize = 64;
+
+/// Parses a semantic version like `1.4.0` or `2.0.0-beta.1`, without a leading `v`.
+pub fn parse_version(version: &str) -> Result<Version, String> {
+    Version::parse(version.trim())
+        .map_err(|e| format!("The version is not a semantic version: {e}."))
+}
+
+/// Gives the checksum in lowercase, it must be the SHA-256 of the download in hex.
+pub fn parse_checksum(checksum: &str) -> Result<String, String> {
+    let checksum = checksum.trim();
+    if checksum.len() != CHECKSUM_LEN || !checksum.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!(
+            "The checksum must be a SHA-256 of {CHECKSUM_LEN} hex digits."
+        ));
+    }
+
+    Ok(checksum.to_ascii_lowercase())
+}
+
+pub fn validate_download_url(download_url: &str) -> Result<(), String> {
+    if !download_url.starts_with("http://") && !download_url.starts_with("https://") {
+        return Err("The download url must start with http:// or https://.".to_string());
+    }
+    if download_url.chars().any(|c| c.is_whitespace()) {
+        return Err("The download url must not contain whitespace.".to_string());
+    }
+
+    Ok(())
+}
+
+pub fn parse_released_at(released_at: &str) -> Result<NaiveDateTime, String> {
+    DateTime::parse_from_rfc3339(released_at.trim())
+        .map(|released_at| released_at.naive_utc())
+        .map_err(|_| "The release date must be an RFC 3339 date and time.".to_string())
+}
+
+/// Orders the releases by version, the newest first. The versions were checked when the
+/// releases were published, so the ones that don't parse can only come last.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    match (Version::parse(a), Version::parse(b)) {
+        (Ok(a), Ok(b)) => b.cmp(&a),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => b.cmp(a),
+    }
+}
+
+pub fn sort_releases(releases: &mut [model::ApprepoRelease]) {
+    releases.sort_by(|a, b| {
+        compare_versions(&a.version, &b.version).then_with(|| b.released_at.cmp(&a.released_at))
+    });
+}
+
+/// Keeps the newest release of every platform and architecture, the pre-releases only
+/// count when they are included.
+pub fn latest_releases(
+    releases: Vec<model::ApprepoRelease>,
+    include_prereleases: bool,
+) -> Vec<model::ApprepoRelease> {
+    let mut latest = HashMap::new();
+    for release in releases {
+        let Ok(version) = Version::parse(&release.version) else {
+            continue;
+        };
+        if !version.pre.is_empty() && !include_prereleases {
+            continue;
+        }
+
+        let key = (release.platform.to_grpc_enum(), release.arch.to_grpc_enum());
+        match latest.get(&key) {
+            Some((latest_version, _)) if *latest_version >= version => {}
+            _ => {
+                latest.insert(key, (version, release));
+            }
+        }
+    }
+
+    let mut releases = latest
+        .into_values()
+        .map(|(_, release)| release)
+        .collect::<Vec<model::ApprepoRelease>>();
+    releases.sort_by_key(|release| (release.platform.to_grpc_enum(), release.arch.to_grpc_enum()));
+
+    releases
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::model::{ApprepoReleaseArch as Arch, ApprepoReleasePlatform as Platform};
+
+    fn release(
+        version: &str,
+        platform: Platform,
+        arch: Arch,
+        released_days_ago: i64,
+    ) -> model::ApprepoRelease {
+        let now = Utc::now().naive_utc();
+        model::ApprepoRelease {
+            id: Uuid::new_v4(),
+            apprepo_id: Uuid::from_u128(1),
+            version: version.to_string(),
+            platform,
+            arch,
+            download_url: format!("https://downloads.example.com/{version}"),
+            checksum: String::new(),
+            changelog: String::new(),
+            released_at: now - Duration::days(released_days_ago),
+            yanked_at: None,
+            yanked_reason: None,
+            created_at: now,
+            updated_at: now,
+            channel: model::ApprepoReleaseChannel::Stable,
+            rollout_percentage: 100,
+            signature: None,
+        }
+    }
+
+    fn versions(releases: &[model::ApprepoRelease]) -> Vec<&str> {
+        releases
+            .iter()
+            .map(|release| release.version.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn orders_the_versions_newest_first() {
+        assert_eq!(compare_versions("1.10.0", "1.9.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.9.0", "1.10.0"), Ordering::Greater);
+        assert_eq!(compare_versions("2.0.0", "2.0.0-beta.1"), Ordering::Less);
+        assert_eq!(compare_versions("1.0.0", "1.0.0"), Ordering::Equal);
+    }
+
+    #[test]
+    fn orders_the_unparseable_versions_last() {
+        assert_eq!(compare_versions("0.0.1", "latest"), Ordering::Less);
+        assert_eq!(compare_versions("latest", "0.0.1"), Ordering::Greater);
+        assert_eq!(compare_versions("v2", "v1"), Ordering::Less);
+    }
+
+    #[test]
+    fn sorts_the_releases_by_version_then_by_date() {
+        let mut releases = vec![
+            release("1.9.0", Platform::Linux, Arch::X86_64, 3),
+            release("nightly", Platform::Linux, Arch::X86_64, 0),
+            release("1.10.0", Platform::Linux, Arch::X86_64, 2),
+            release("1.10.0", Platform::Windows, Arch::X86_64, 1),
+            release("2.0.0-beta.1", Platform::Linux, Arch::X86_64, 1),
+        ];
+
+        sort_releases(&mut releases);
+        assert_eq!(
+            versions(&releases),
+            ["2.0.0-beta.1", "1.10.0", "1.10.0", "1.9.0", "nightly"]
+        );
+        assert_eq!(releases[1].platform, Platform::Windows);
+    }
+
+    #[test]
+    fn keeps_the_newest_release_of_every_platform_and_arch() {
+        let releases = || {
+            vec![
+                release("1.0.0", Platform::Linux, Arch::X86_64, 3),
+                release("1.1.0", Platform::Linux, Arch::X86_64, 2),
+                release("2.0.0-beta.1", Platform::Linux, Arch::X86_64, 1),
+                release("1.0.0", Platform::Linux, Arch::Aarch64, 3),
+                release("1.2.0", Platform::Windows, Arch::X86_64, 1),
+                release("nightly", Platform::Macos, Arch::Universal, 0),
+            ]
+        };
+
+        let latest = latest_releases(releases(), false);
+        assert_eq!(
+            latest
+                .iter()
+                .map(|release| (release.platform, release.arch, release.version.as_str()))
+                .collect::<Vec<(Platform, Arch, &str)>>(),
+            [
+                (Platform::Windows, Arch::X86_64, "1.2.0"),
+                (Platform::Linux, Arch::X86_64, "1.1.0"),
+                (Platform::Linux, Arch::Aarch64, "1.0.0"),
+            ]
+        );
+
+        let latest = latest_releases(releases(), true);
+        assert_eq!(versions(&latest), ["1.2.0", "2.0.0-beta.1", "1.0.0"]);
+    }
+
+    #[test]
+    fn parses_the_checksum_in_lowercase() {
+        let checksum = "2CF24DBA5FB0A30E26E83B2AC5B9E29E1B161E5C1FA7425E73043362938B9824";
+
+        assert_eq!(
+            parse_checksum(&format!(" {checksum}\n")),
+            Ok(checksum.to_ascii_lowercase())
+        );
+    }
+
+    #[test]
+    fn rejects_the_checksums_that_are_not_sha_256() {
+        let error = Err("The checksum must be a SHA-256 of 64 hex digits.".to_string());
+
+        assert_eq!(parse_checksum(""), error);
+        assert_eq!(parse_checksum(&"a".repeat(63)), error);
+        assert_eq!(parse_checksum(&"a".repeat(65)), error);
+        assert_eq!(parse_checksum(&"g".repeat(64)), error);
+        assert_eq!(parse_checksum(&format!("{} a", "a".repeat(31))), error);
+    }
+
+    #[test]
+    fn validates_the_download_url() {
+        assert_eq!(
+            validate_download_url("https://downloads.example.com/app.zip"),
+            Ok(())
+        );
+        assert_eq!(validate_download_url("http://localhost/app.zip"), Ok(()));
+        assert_eq!(
+            validate_download_url("ftp://downloads.example.com/app.zip"),
+            Err("The download url must start with http:// or https://.".to_string())
+        );
+        assert_eq!(
+            validate_download_url("downloads.example.com/app.zip"),
+            Err("The download url must start with http:// or https://.".to_string())
+        );
+        assert_eq!(
+            validate_download_url("https://downloads.example.com/my app.zip"),
+            Err("The download url must not contain whitespace.".to_string())
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use diesel::{
+    sql_types::{BigInt, Nullable, Text, Uuid as SqlUuid},
+    PgConnection, QueryableByName, RunQueryDsl,
+};
+use uuid::Uuid;
+
+use crate::{model, schema};
+
+const QUERY_MAX_TERMS: usize = 10;
+const NAME_HIGHLIGHT_OPTIONS: &str = "StartSel=<mark>, StopSel=</mark>, HighlightAll=true";
+const DESCRIPTION_HIGHLIGHT_OPTIONS: &str =
+    "StartSel=<mark>, StopSel=</mark>, MinWords=15, MaxWords=35, MaxFragments=2";
+
+/// The filters of a search, the ones left out match every apprepo. A limit of 0 gives all
+/// of the results from the offset.
+pub struct Filter {
+    pub status: Option<model::ApprepoStatus>,
+    pub category_id: Option<Uuid>,
+}
+
+#[derive(QueryableByName)]
+struct Count {
+    #[diesel(sql_type = BigInt)]
+    count: i64,
+}
+
+/// Turns the words of a query into a tsquery that matches the words starting with every one
+/// of them. Only letters and digits are kept, so the tsquery can't be malformed.
+pub fn to_prefix_tsquery(query: &str) -> Option<String> {
+    let terms = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .take(QUERY_MAX_TERMS)
+        .map(|term| format!("{}:*", term.to_lowercase()))
+        .collect::<Vec<String>>();
+    if terms.is_empty() {
+        return None;
+    }
+
+    Some(terms.join(" & "))
+}
+
+/// Gives the page of the apprepos that match a tsquery, the best match first, with the
+/// number of all of the apprepos that match it. The highlights are HTML, the name and the
+/// description are escaped before the matched words are marked.
+pub fn search(
+    db_conn: &mut PgConnection,
+    tsquery: &str,
+    filter: &Filter,
+    offset: i64,
+    limit: i64,
+) -> Result<(Vec<model::ApprepoSearchResult>, i64), diesel::result::Error> {
+    let condition =
+        "apprepo_search_vector(apprepo.name, apprepo.description, apprepo.tags) @@ query \
+        AND ($2::apprepo_status IS NULL OR apprepo.status = $2) \
+        AND ($3::uuid IS NULL OR apprepo.category_id = $3)";
+
+    let count = diesel::sql_query(format!(
+        "SELECT COUNT(*) AS count FROM apprepo, to_tsquery('simple', $1) query WHERE {condition}"
+    ))
+    .bind::<Text, _>(tsquery)
+    .bind::<Nullable<schema::sql_types::ApprepoStatus>, _>(filter.status)
+    .bind::<Nullable<SqlUuid>, _>(filter.category_id)
+    .get_result::<Count>(db_conn)?
+    .count;
+
+    let results = diesel::sql_query(format!(
+        "SELECT apprepo.*, \
+            ts_rank(apprepo_search_vector(apprepo.name, apprepo.description, apprepo.tags), query) AS rank, \
+            ts_headline('simple', apprepo_escape_html(apprepo.name), query, $4) AS name_highlight, \
+            ts_headline('simple', apprepo_escape_html(apprepo.description), query, $5) AS description_highlight \
+        FROM apprepo, to_tsquery('simple', $1) query \
+        WHERE {condition} \
+        ORDER BY rank DESC, apprepo.position ASC, apprepo.name ASC \
+        OFFSET $6 LIMIT $7"
+    ))
+    .bind::<Text, _>(tsquery)
+    .bind::<Nullable<schema::sql_types::ApprepoStatus>, _>(filter.status)
+    .bind::<Nullable<SqlUuid>, _>(filter.category_id)
+    .bind::<Text, _>(NAME_HIGHLIGHT_OPTIONS)
+    .bind::<Text, _>(DESCRIPTION_HIGHLIGHT_OPTIONS)
+    .bind::<BigInt, _>(offset)
+    .bind::<Nullable<BigInt>, _>((limit > 0).then_some(limit))
+    .load::<model::ApprepoSearchResult>(db_conn)?;
+
+    Ok((results, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use diesel::{Connection, PgConnection};
+    use tools_lib_db::pg::migration::run_migrations;
+
+    use super::*;
+
+    #[test]
+    fn matches_every_word_by_its_prefix() {
+        assert_eq!(
+            to_prefix_tsquery("Photo Editor"),
+            Some("photo:* & editor:*".to_string())
+        );
+        assert_eq!(to_prefix_tsquery("  notes  "), Some("notes:*".to_string()));
+    }
+
+    #[test]
+    fn strips_the_tsquery_operators() {
+        assert_eq!(
+            to_prefix_tsquery("a&b|c!d:e*f(g)h'i"),
+            Some("a:* & b:* & c:* & d:* & e:* & f:* & g:* & h:* & i:*".to_string())
+        );
+        assert_eq!(
+            to_prefix_tsquery("!photo:* & (editor | 'viewer')"),
+            Some("photo:* & editor:* & viewer:*".to_string())
+        );
+    }
+
+    #[test]
+    fn keeps_the_first_terms_only() {
+        let query = (0..QUERY_MAX_TERMS + 5)
+            .map(|i| format!("t{i}"))
+            .collect::<Vec<String>>()
+            .join(" ");
+        let expected = (0..QUERY_MAX_TERMS)
+            .map(|i| format!("t{i}:*"))
+            .collect::<Vec<String>>()
+            .join(" & ");
+
+        assert_eq!(to_prefix_tsquery(&query), Some(expected));
+    }
+
+    #[test]
+    fn gives_none_without_words() {
+        assert_eq!(to_prefix_tsquery(""), None);
+        assert_eq!(to_prefix_tsquery("   "), None);
+        assert_eq!(to_prefix_tsquery("&|!:*()'"), None);
+    }
+
+    /// Needs a database, it's skipped when the DATABASE_URL isn't set. Everything it writes,
+    /// the migrations too, is rolled back.
+    #[test]
+    fn escapes_the_highlights_before_marking_the_matches() {
+        let Ok(database_url) = std::env::var("DATABASE_URL") else {
+            eprintln!("skipped, the DATABASE_URL isn't set");
+            return;
+        };
+        let db_conn = &mut PgConnection::establish(&database_url).unwrap();
+        db_conn.begin_test_transaction().unwrap();
+        run_migrations(db_conn, crate::MIGRATIONS).unwrap();
+        diesel::sql_query(
+            "INSERT INTO apprepo (name, icon, link, status, description) \
+            VALUES ('<script>alert(1)</script> Notes', '', '', 'published', 'Notes & \"lists\" to share')",
+        )
+        .execute(db_conn)
+        .unwrap();
+
+        let filter = Filter {
+            status: None,
+            category_id: None,
+        };
+        let (results, count) =
+            search(db_conn, &to_prefix_tsquery("notes").unwrap(), &filter, 0, 0).unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(
+            results[0].name_highlight,
+            "&lt;script&gt;alert(1)&lt;/script&gt; <mark>Notes</mark>"
+        );
+
+        let (results, _) =
+            search(db_conn, &to_prefix_tsquery("lists").unwrap(), &filter, 0, 0).unwrap();
+        assert_eq!(
+            results[0].description_highlight,
+            "Notes &amp; &quot;<mark>lists</mark>&quot; to share"
+        );
+    }
+}
@@ -0,0 +1,342 @@
+use semver::Version;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::model;
+
+const INSTALL_ID_MAX_LEN: usize = 128;
+
+pub fn parse_install_id(install_id: &str) -> Result<&str, String> {
+    let install_id = install_id.trim();
+    if install_id.chars().count() > INSTALL_ID_MAX_LEN {
+        return Err(format!(
+            "The install id is longer than {INSTALL_ID_MAX_LEN} characters."
+        ));
+    }
+
+    Ok(install_id)
+}
+
+pub fn validate_rollout_percentage(rollout_percentage: i32) -> Result<(), String> {
+    if !(0..=100).contains(&rollout_percentage) {
+        return Err("The rollout percentage must be between 0 and 100.".to_string());
+    }
+
+    Ok(())
+}
+
+/// Gives the bucket of an install for a release, from 0 to 99. It comes from a hash of the
+/// app, the version and the install, so that every version is rolled out to other installs
+/// first and an install keeps its bucket as the percentage grows.
+pub fn rollout_bucket(apprepo_id: &Uuid, version: &str, install_id: &str) -> i32 {
+    let hash = Sha256::new()
+        .chain_update(apprepo_id.as_bytes())
+        .chain_update(version)
+        .chain_update([0])
+        .chain_update(install_id)
+        .finalize();
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&hash[..8]);
+
+    (u64::from_be_bytes(bytes) % 100) as i32
+}
+
+/// The installs without an id only get the releases that have been rolled out to all.
+fn is_rolled_out(release: &model::ApprepoRelease, install_id: &str) -> bool {
+    if release.rollout_percentage >= 100 {
+        return true;
+    }
+    if install_id.is_empty() {
+        return false;
+    }
+
+    rollout_bucket(&release.apprepo_id, &release.version, install_id) < release.rollout_percentage
+}
+
+/// Finds the newest release of the channel that is newer than the current version and has
+/// been rolled out to the install. A release built for the architecture wins over a
+/// universal one of the same version.
+pub fn find_update(
+    releases: Vec<model::ApprepoRelease>,
+    current_version: &Version,
+    channel: model::ApprepoReleaseChannel,
+    arch: Option<model::ApprepoReleaseArch>,
+    install_id: &str,
+) -> Option<model::ApprepoRelease> {
+    let mut update: Option<(Version, model::ApprepoRelease)> = None;
+    for release in releases {
+        if channel == model::ApprepoReleaseChannel::Stable
+            && release.channel != model::ApprepoReleaseChannel::Stable
+        {
+            continue;
+        }
+        if release.arch != model::ApprepoReleaseArch::Universal && Some(release.arch) != arch {
+            continue;
+        }
+        let Ok(version) = Version::parse(&release.version) else {
+            continue;
+        };
+        if version <= *current_version || !is_rolled_out(&release, install_id) {
+            continue;
+        }
+
+        let is_newer = match &update {
+            Some((update_version, update)) => {
+                version > *update_version
+                    || (version == *update_version
+                        && update.arch == model::ApprepoReleaseArch::Universal
+                        && release.arch != model::ApprepoReleaseArch::Universal)
+            }
+            None => true,
+        };
+        if is_newer {
+            update = Some((version, release));
+        }
+    }
+
+    update.map(|(_, release)| release)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    const APPREPO_ID: Uuid = Uuid::from_u128(1);
+
+    fn release(
+        version: &str,
+        channel: model::ApprepoReleaseChannel,
+        arch: model::ApprepoReleaseArch,
+        rollout_percentage: i32,
+    ) -> model::ApprepoRelease {
+        let now = Utc::now().naive_utc();
+        model::ApprepoRelease {
+            id: Uuid::new_v4(),
+            apprepo_id: APPREPO_ID,
+            version: version.to_string(),
+            platform: model::ApprepoReleasePlatform::Linux,
+            arch,
+            download_url: format!("https://downloads.example.com/{version}"),
+            checksum: String::new(),
+            changelog: String::new(),
+            released_at: now,
+            yanked_at: None,
+            yanked_reason: None,
+            created_at: now,
+            updated_at: now,
+            channel,
+            rollout_percentage,
+            signature: None,
+        }
+    }
+
+    fn stable(version: &str) -> model::ApprepoRelease {
+        release(
+            version,
+            model::ApprepoReleaseChannel::Stable,
+            model::ApprepoReleaseArch::Universal,
+            100,
+        )
+    }
+
+    fn find(
+        releases: Vec<model::ApprepoRelease>,
+        current_version: &str,
+        channel: model::ApprepoReleaseChannel,
+        arch: Option<model::ApprepoReleaseArch>,
+        install_id: &str,
+    ) -> Option<(String, model::ApprepoReleaseArch)> {
+        find_update(
+            releases,
+            &Version::parse(current_version).unwrap(),
+            channel,
+            arch,
+            install_id,
+        )
+        .map(|release| (release.version, release.arch))
+    }
+
+    #[test]
+    fn keeps_the_bucket_of_an_install() {
+        for index in 0..200 {
+            let install_id = format!("install-{index}");
+            let bucket = rollout_bucket(&APPREPO_ID, "1.1.0", &install_id);
+            assert!((0..100).contains(&bucket));
+            assert_eq!(rollout_bucket(&APPREPO_ID, "1.1.0", &install_id), bucket);
+        }
+    }
+
+    #[test]
+    fn keeps_an_install_in_as_the_rollout_grows() {
+        for index in 0..200 {
+            let install_id = format!("install-{index}");
+            let mut was_rolled_out = false;
+            for rollout_percentage in 0..=100 {
+                let is_rolled_out = is_rolled_out(
+                    &release(
+                        "1.1.0",
+                        model::ApprepoReleaseChannel::Stable,
+                        model::ApprepoReleaseArch::Universal,
+                        rollout_percentage,
+                    ),
+                    &install_id,
+                );
+                assert!(
+                    is_rolled_out || !was_rolled_out,
+                    "{install_id} left at {rollout_percentage}%"
+                );
+                was_rolled_out = is_rolled_out;
+            }
+            assert!(was_rolled_out);
+        }
+    }
+
+    #[test]
+    fn gives_the_installs_without_an_id_only_the_full_rollouts() {
+        let partial = |rollout_percentage| {
+            release(
+                "1.2.0",
+                model::ApprepoReleaseChannel::Stable,
+                model::ApprepoReleaseArch::Universal,
+                rollout_percentage,
+            )
+        };
+
+        for index in 0..200 {
+            let install_id = format!("install-{index}");
+            assert_eq!(
+                find(
+                    vec![stable("1.1.0"), partial(0)],
+                    "1.0.0",
+                    model::ApprepoReleaseChannel::Stable,
+                    None,
+                    &install_id,
+                ),
+                Some(("1.1.0".to_string(), model::ApprepoReleaseArch::Universal))
+            );
+        }
+        assert_eq!(
+            find(
+                vec![stable("1.1.0"), partial(99)],
+                "1.0.0",
+                model::ApprepoReleaseChannel::Stable,
+                None,
+                "",
+            ),
+            Some(("1.1.0".to_string(), model::ApprepoReleaseArch::Universal))
+        );
+    }
+
+    #[test]
+    fn keeps_the_beta_releases_from_stable_installs() {
+        let releases = || {
+            vec![
+                stable("1.1.0"),
+                release(
+                    "1.2.0-beta.1",
+                    model::ApprepoReleaseChannel::Beta,
+                    model::ApprepoReleaseArch::Universal,
+                    100,
+                ),
+            ]
+        };
+
+        assert_eq!(
+            find(
+                releases(),
+                "1.0.0",
+                model::ApprepoReleaseChannel::Stable,
+                None,
+                "install"
+            ),
+            Some(("1.1.0".to_string(), model::ApprepoReleaseArch::Universal))
+        );
+        assert_eq!(
+            find(
+                releases(),
+                "1.0.0",
+                model::ApprepoReleaseChannel::Beta,
+                None,
+                "install"
+            ),
+            Some((
+                "1.2.0-beta.1".to_string(),
+                model::ApprepoReleaseArch::Universal
+            ))
+        );
+        assert_eq!(
+            find(
+                vec![stable("1.1.0")],
+                "1.0.0",
+                model::ApprepoReleaseChannel::Beta,
+                None,
+                "install"
+            ),
+            Some(("1.1.0".to_string(), model::ApprepoReleaseArch::Universal))
+        );
+    }
+
+    #[test]
+    fn prefers_a_release_built_for_the_arch() {
+        let arch_release = || {
+            release(
+                "1.1.0",
+                model::ApprepoReleaseChannel::Stable,
+                model::ApprepoReleaseArch::X86_64,
+                100,
+            )
+        };
+
+        for releases in [
+            vec![stable("1.1.0"), arch_release()],
+            vec![arch_release(), stable("1.1.0")],
+        ] {
+            assert_eq!(
+                find(
+                    releases,
+                    "1.0.0",
+                    model::ApprepoReleaseChannel::Stable,
+                    Some(model::ApprepoReleaseArch::X86_64),
+                    "install"
+                ),
+                Some(("1.1.0".to_string(), model::ApprepoReleaseArch::X86_64))
+            );
+        }
+        assert_eq!(
+            find(
+                vec![stable("1.1.0"), arch_release()],
+                "1.0.0",
+                model::ApprepoReleaseChannel::Stable,
+                Some(model::ApprepoReleaseArch::Aarch64),
+                "install"
+            ),
+            Some(("1.1.0".to_string(), model::ApprepoReleaseArch::Universal))
+        );
+    }
+
+    #[test]
+    fn compares_the_versions_as_semver() {
+        assert_eq!(
+            find(
+                vec![stable("1.9.0"), stable("1.10.0")],
+                "1.2.0",
+                model::ApprepoReleaseChannel::Stable,
+                None,
+                "install"
+            ),
+            Some(("1.10.0".to_string(), model::ApprepoReleaseArch::Universal))
+        );
+        assert_eq!(
+            find(
+                vec![stable("1.9.0"), stable("1.10.0")],
+                "1.10.0",
+                model::ApprepoReleaseChannel::Stable,
+                None,
+                "install"
+            ),
+            None
+        );
+    }
+}
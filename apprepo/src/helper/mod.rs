@@ -0,0 +1,7 @@
+pub mod apprepo;
+pub mod apprepo_download;
+pub mod apprepo_file;
+pub mod apprepo_manifest;
+pub mod apprepo_release;
+pub mod apprepo_search;
+pub mod apprepo_update;
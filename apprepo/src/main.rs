@@ -2,14 +2,22 @@ use std::error::Error;
 
 use diesel_migrations::{embed_migrations, EmbeddedMigrations};
 use tonic::transport::Server;
+use tools_apprepo::manifest;
 
-use crate::controller::ApprepoController;
+use crate::{
+    controller::ApprepoController,
+    service::storage::{local::LocalStorage, s3, Storage},
+};
 
 mod controller;
 mod env;
+mod helper;
 mod model;
 mod proto;
 mod schema;
+mod service;
+#[cfg(test)]
+mod test_server;
 
 const MIGRATIONS: EmbeddedMigrations = embed_migrations!("./migrations");
 
@@ -20,16 +28,54 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
     let service_name = env::Env::service_name();
     let service_addrs = env::Env::service_addrs();
     let database_url = env::Env::database_url();
+    let use_storage = env::Env::use_storage();
+    let storage_public_url = env::Env::storage_public_url();
 
     let db_pool = tools_lib_db::pg::connection::create_connection_pool(&database_url);
     let db_conn = &mut tools_lib_db::pg::connection::get_connection(&app_mode, &db_pool).unwrap();
     tools_lib_db::pg::migration::run_migrations(db_conn, MIGRATIONS)?;
 
+    let storage = if use_storage.is_s3() {
+        Storage::S3(Box::new(s3::S3Storage::new(s3::Config {
+            endpoint: env::Env::s3_endpoint(),
+            region: env::Env::s3_region(),
+            bucket: env::Env::s3_bucket(),
+            access_key: env::Env::s3_access_key(),
+            secret_key: env::Env::s3_secret_key(),
+            public_url: storage_public_url,
+        })?))
+    } else if use_storage.is_local() {
+        Storage::Local(LocalStorage {
+            dir: env::Env::storage_local_dir(),
+            public_url: storage_public_url,
+        })
+    } else {
+        return Err("USE_STORAGE must be LOCAL or S3.".into());
+    };
+
+    let signing_key = manifest::parse_signing_key(&env::Env::manifest_signing_key())
+        .map_err(|e| format!("MANIFEST_SIGNING_KEY: {e}"))?;
+    let trusted_keys = env::Env::manifest_trusted_keys()
+        .iter()
+        .map(|key| manifest::parse_public_key(key))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("MANIFEST_TRUSTED_KEYS: {e}"))?;
+
     println!("{app_name} {service_name} is running on {service_addrs} in {app_mode}.");
 
     Server::builder()
         .add_service(proto::apprepo::ApprepoServiceServer::new(
-            ApprepoController { app_mode, db_pool },
+            ApprepoController {
+                app_mode,
+                db_pool,
+                storage,
+                icon_size: env::Env::icon_size(),
+                icon_max_size: env::Env::icon_max_size(),
+                artifact_max_size: env::Env::artifact_max_size(),
+                download_redirect_url: env::Env::download_redirect_url(),
+                signing_key,
+                trusted_keys,
+            },
         ))
         .serve(service_addrs.parse()?)
         .await?;
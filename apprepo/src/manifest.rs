@@ -0,0 +1,313 @@
+//! The manifests of the releases and their detached signatures. The apprepo service signs
+//! the manifest of every version with Ed25519, the apps verify it with the keys they trust
+//! before they download an artifact.
+
+use std::fmt;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub const SIGNATURE_ALGORITHM: &str = "ed25519";
+const KEY_ID_LEN: usize = 16;
+
+/// A version of an app with the artifacts that can be downloaded for it.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub app_id: String,
+    pub name: String,
+    pub version: String,
+    pub artifacts: Vec<ManifestArtifact>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestArtifact {
+    pub platform: String,
+    pub arch: String,
+    pub channel: String,
+    pub url: String,
+    pub sha256: String,
+    pub released_at: String,
+}
+
+/// The detached signature of the bytes of a manifest, the signature is in base64.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestSignature {
+    pub key_id: String,
+    pub algorithm: String,
+    pub signature: String,
+}
+
+#[derive(Debug)]
+pub enum ManifestError {
+    InvalidKey(String),
+    UnknownKey(String),
+    InvalidSignature,
+    InvalidManifest(String),
+    ChecksumMismatch,
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifestError::InvalidKey(e) => write!(f, "The key is not valid: {e}."),
+            ManifestError::UnknownKey(key_id) => {
+                write!(f, "The manifest is signed with the untrusted key {key_id}.")
+            }
+            ManifestError::InvalidSignature => {
+                write!(f, "The signature of the manifest is not valid.")
+            }
+            ManifestError::InvalidManifest(e) => write!(f, "The manifest is not valid: {e}."),
+            ManifestError::ChecksumMismatch => {
+                write!(
+                    f,
+                    "The artifact doesn't match the checksum of the manifest."
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+/// Reads a signing key from the base64 of its 32 byte seed.
+pub fn parse_signing_key(signing_key: &str) -> Result<SigningKey, ManifestError> {
+    let bytes = STANDARD
+        .decode(signing_key.trim())
+        .map_err(|e| ManifestError::InvalidKey(e.to_string()))?;
+    let seed = <[u8; 32]>::try_from(bytes.as_slice())
+        .map_err(|_| ManifestError::InvalidKey("a signing key has 32 bytes".to_string()))?;
+
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+/// Reads a public key from the base64 of its 32 bytes.
+pub fn parse_public_key(public_key: &str) -> Result<VerifyingKey, ManifestError> {
+    let bytes = STANDARD
+        .decode(public_key.trim())
+        .map_err(|e| ManifestError::InvalidKey(e.to_string()))?;
+    let bytes = <[u8; 32]>::try_from(bytes.as_slice())
+        .map_err(|_| ManifestError::InvalidKey("a public key has 32 bytes".to_string()))?;
+
+    VerifyingKey::from_bytes(&bytes).map_err(|e| ManifestError::InvalidKey(e.to_string()))
+}
+
+pub fn encode_public_key(public_key: &VerifyingKey) -> String {
+    STANDARD.encode(public_key.as_bytes())
+}
+
+/// The id of a key is the start of the SHA-256 of the public key in hex, so the apps can
+/// tell which of the keys they trust signed a manifest.
+pub fn key_id(public_key: &VerifyingKey) -> String {
+    let hash = Sha256::digest(public_key.as_bytes());
+    let mut key_id = hash
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    key_id.truncate(KEY_ID_LEN);
+
+    key_id
+}
+
+pub fn sign(manifest: &[u8], signing_key: &SigningKey) -> ManifestSignature {
+    ManifestSignature {
+        key_id: key_id(&signing_key.verifying_key()),
+        algorithm: SIGNATURE_ALGORITHM.to_string(),
+        signature: STANDARD.encode(signing_key.sign(manifest).to_bytes()),
+    }
+}
+
+/// Checks the signature of the bytes of a manifest with the trusted key it names, then
+/// reads the manifest. Trusting the old keys with the new one lets the signing key be
+/// rotated without breaking the installed apps.
+pub fn verify(
+    manifest: &[u8],
+    signature: &ManifestSignature,
+    trusted_keys: &[VerifyingKey],
+) -> Result<Manifest, ManifestError> {
+    if signature.algorithm != SIGNATURE_ALGORITHM {
+        return Err(ManifestError::InvalidSignature);
+    }
+    let trusted_key = trusted_keys
+        .iter()
+        .find(|trusted_key| key_id(trusted_key) == signature.key_id)
+        .ok_or_else(|| ManifestError::UnknownKey(signature.key_id.to_owned()))?;
+
+    let signature_bytes = STANDARD
+        .decode(&signature.signature)
+        .map_err(|_| ManifestError::InvalidSignature)?;
+    let signature_bytes = <[u8; 64]>::try_from(signature_bytes.as_slice())
+        .map_err(|_| ManifestError::InvalidSignature)?;
+    trusted_key
+        .verify(manifest, &Signature::from_bytes(&signature_bytes))
+        .map_err(|_| ManifestError::InvalidSignature)?;
+
+    serde_json::from_slice(manifest).map_err(|e| ManifestError::InvalidManifest(e.to_string()))
+}
+
+/// Checks a downloaded artifact against the checksum of a verified manifest.
+pub fn verify_artifact(artifact: &ManifestArtifact, content: &[u8]) -> Result<(), ManifestError> {
+    let checksum = Sha256::digest(content)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    if !checksum.eq_ignore_ascii_case(&artifact.sha256) {
+        return Err(ManifestError::ChecksumMismatch);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signing_key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    fn artifact() -> ManifestArtifact {
+        ManifestArtifact {
+            platform: "linux".to_string(),
+            arch: "x86_64".to_string(),
+            channel: "stable".to_string(),
+            url: "https://downloads.example.com/1".to_string(),
+            sha256: "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824".to_string(),
+            released_at: "2026-10-19".to_string(),
+        }
+    }
+
+    fn manifest() -> Vec<u8> {
+        serde_json::to_vec(&Manifest {
+            app_id: "app".to_string(),
+            name: "App".to_string(),
+            version: "1.2.0".to_string(),
+            artifacts: vec![artifact()],
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn verifies_a_signed_manifest() {
+        let signing_key = signing_key(1);
+        let manifest = manifest();
+
+        let signature = sign(&manifest, &signing_key);
+        assert_eq!(signature.algorithm, SIGNATURE_ALGORITHM);
+        assert_eq!(signature.key_id.len(), KEY_ID_LEN);
+        let verified = verify(&manifest, &signature, &[signing_key.verifying_key()]).unwrap();
+        assert_eq!(verified.version, "1.2.0");
+        assert_eq!(verified.artifacts, [artifact()]);
+    }
+
+    #[test]
+    fn rejects_a_tampered_manifest() {
+        let signing_key = signing_key(1);
+        let mut manifest = manifest();
+        let signature = sign(&manifest, &signing_key);
+
+        let index = manifest.len() / 2;
+        manifest[index] ^= 1;
+        assert!(matches!(
+            verify(&manifest, &signature, &[signing_key.verifying_key()]),
+            Err(ManifestError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn rejects_a_manifest_signed_with_an_untrusted_key() {
+        let manifest = manifest();
+        let signature = sign(&manifest, &signing_key(1));
+
+        match verify(&manifest, &signature, &[signing_key(2).verifying_key()]) {
+            Err(ManifestError::UnknownKey(key_id)) => assert_eq!(key_id, signature.key_id),
+            result => panic!("unexpected result {result:?}"),
+        }
+    }
+
+    #[test]
+    fn rejects_another_algorithm() {
+        let signing_key = signing_key(1);
+        let manifest = manifest();
+        let signature = ManifestSignature {
+            algorithm: "rsa".to_string(),
+            ..sign(&manifest, &signing_key)
+        };
+
+        assert!(matches!(
+            verify(&manifest, &signature, &[signing_key.verifying_key()]),
+            Err(ManifestError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn rejects_a_signature_of_the_wrong_length() {
+        let signing_key = signing_key(1);
+        let manifest = manifest();
+        let signature = sign(&manifest, &signing_key);
+        let mut signature_bytes = STANDARD.decode(&signature.signature).unwrap();
+        signature_bytes.pop();
+        let signature = ManifestSignature {
+            signature: STANDARD.encode(signature_bytes),
+            ..signature
+        };
+
+        assert!(matches!(
+            verify(&manifest, &signature, &[signing_key.verifying_key()]),
+            Err(ManifestError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn verifies_a_manifest_signed_with_an_old_key_after_a_rotation() {
+        let old_key = signing_key(1);
+        let new_key = signing_key(2);
+        let trusted_keys = [new_key.verifying_key(), old_key.verifying_key()];
+        let manifest = manifest();
+
+        assert!(verify(&manifest, &sign(&manifest, &old_key), &trusted_keys).is_ok());
+        assert!(verify(&manifest, &sign(&manifest, &new_key), &trusted_keys).is_ok());
+    }
+
+    #[test]
+    fn checks_the_checksum_of_an_artifact() {
+        assert!(verify_artifact(&artifact(), b"hello").is_ok());
+        assert!(verify_artifact(
+            &ManifestArtifact {
+                sha256: artifact().sha256.to_ascii_uppercase(),
+                ..artifact()
+            },
+            b"hello"
+        )
+        .is_ok());
+        assert!(matches!(
+            verify_artifact(&artifact(), b"hello!"),
+            Err(ManifestError::ChecksumMismatch)
+        ));
+    }
+
+    #[test]
+    fn reads_the_keys_from_base64() {
+        let signing_key = signing_key(1);
+
+        let parsed_key = parse_signing_key(&STANDARD.encode([1; 32])).unwrap();
+        assert_eq!(parsed_key.to_bytes(), signing_key.to_bytes());
+        let public_key = encode_public_key(&signing_key.verifying_key());
+        assert_eq!(
+            parse_public_key(&public_key).unwrap(),
+            signing_key.verifying_key()
+        );
+        assert!(matches!(
+            parse_signing_key(&STANDARD.encode([1; 31])),
+            Err(ManifestError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            parse_public_key("not base64"),
+            Err(ManifestError::InvalidKey(_))
+        ));
+    }
+}
@@ -1,10 +1,12 @@
 use chrono::NaiveDateTime;
-use diesel::{AsChangeset, Insertable, Queryable};
+use diesel::{AsChangeset, Insertable, Queryable, QueryableByName};
 use uuid::Uuid;
 
-use crate::schema;
+use crate::{proto, schema};
 
-#[derive(Queryable, Insertable)]
+use super::ApprepoStatus;
+
+#[derive(Queryable, QueryableByName, Insertable)]
 #[diesel(table_name = schema::apprepo)]
 pub struct Apprepo {
     pub id: Uuid,
@@ -13,6 +15,31 @@ pub struct Apprepo {
     pub link: String,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub category_id: Option<Uuid>,
+    pub position: i32,
+    pub status: ApprepoStatus,
+    pub is_featured: bool,
+    pub description: String,
+    pub tags: Vec<String>,
+}
+
+impl Apprepo {
+    pub fn to_grpc(&self) -> proto::apprepo::Apprepo {
+        proto::apprepo::Apprepo {
+            id: self.id.to_string(),
+            name: self.name.to_owned(),
+            icon: self.icon.to_owned(),
+            link: self.link.to_owned(),
+            created_at: self.created_at.to_string(),
+            updated_at: self.updated_at.to_string(),
+            category_id: self.category_id.map(|category_id| category_id.to_string()),
+            position: self.position,
+            status: self.status.to_grpc_enum(),
+            is_featured: self.is_featured,
+            description: self.description.to_owned(),
+            tags: self.tags.to_owned(),
+        }
+    }
 }
 
 #[derive(AsChangeset)]
@@ -21,4 +48,9 @@ pub struct ApprepoChangeSet {
     pub name: Option<String>,
     pub icon: Option<String>,
     pub link: Option<String>,
+    pub category_id: Option<Option<Uuid>>,
+    pub status: Option<ApprepoStatus>,
+    pub is_featured: Option<bool>,
+    pub description: Option<String>,
+    pub tags: Option<Vec<String>>,
 }
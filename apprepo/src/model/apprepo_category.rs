@@ -0,0 +1,24 @@
+use chrono::NaiveDateTime;
+use diesel::Queryable;
+use uuid::Uuid;
+
+use crate::proto;
+
+#[derive(Queryable)]
+pub struct ApprepoCategory {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl ApprepoCategory {
+    pub fn to_grpc(&self) -> proto::apprepo::ApprepoCategory {
+        proto::apprepo::ApprepoCategory {
+            id: self.id.to_string(),
+            name: self.name.to_owned(),
+            created_at: self.created_at.to_string(),
+            updated_at: self.updated_at.to_string(),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use chrono::{NaiveDateTime, TimeZone, Utc};
+use diesel::Queryable;
+use uuid::Uuid;
+
+use crate::proto;
+
+use super::{ApprepoReleaseArch, ApprepoReleaseChannel, ApprepoReleasePlatform};
+
+#[derive(Queryable)]
+pub struct ApprepoRelease {
+    pub id: Uuid,
+    pub apprepo_id: Uuid,
+    pub version: String,
+    pub platform: ApprepoReleasePlatform,
+    pub arch: ApprepoReleaseArch,
+    pub download_url: String,
+    pub checksum: String,
+    pub changelog: String,
+    pub released_at: NaiveDateTime,
+    pub yanked_at: Option<NaiveDateTime>,
+    pub yanked_reason: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub channel: ApprepoReleaseChannel,
+    pub rollout_percentage: i32,
+    pub signature: Option<String>,
+}
+
+impl ApprepoRelease {
+    pub fn to_grpc(&self) -> proto::apprepo::ApprepoRelease {
+        proto::apprepo::ApprepoRelease {
+            id: self.id.to_string(),
+            apprepo_id: self.apprepo_id.to_string(),
+            version: self.version.to_owned(),
+            platform: self.platform.to_grpc_enum(),
+            arch: self.arch.to_grpc_enum(),
+            download_url: self.download_url.to_owned(),
+            checksum: self.checksum.to_owned(),
+            changelog: self.changelog.to_owned(),
+            released_at: Utc.from_utc_datetime(&self.released_at).to_rfc3339(),
+            yanked_at: self
+                .yanked_at
+                .map(|yanked_at| Utc.from_utc_datetime(&yanked_at).to_rfc3339()),
+            yanked_reason: self.yanked_reason.to_owned(),
+            created_at: self.created_at.to_string(),
+            updated_at: self.updated_at.to_string(),
+            channel: self.channel.to_grpc_enum(),
+            rollout_percentage: self.rollout_percentage,
+            signature: self.signature.to_owned(),
+        }
+    }
+}
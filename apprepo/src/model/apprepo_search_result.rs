@@ -0,0 +1,31 @@
+use diesel::{
+    sql_types::{Float, Text},
+    QueryableByName,
+};
+
+use crate::proto;
+
+use super::Apprepo;
+
+#[derive(QueryableByName)]
+pub struct ApprepoSearchResult {
+    #[diesel(embed)]
+    pub apprepo: Apprepo,
+    #[diesel(sql_type = Float)]
+    pub rank: f32,
+    #[diesel(sql_type = Text)]
+    pub name_highlight: String,
+    #[diesel(sql_type = Text)]
+    pub description_highlight: String,
+}
+
+impl ApprepoSearchResult {
+    pub fn to_grpc(&self) -> proto::apprepo::ApprepoSearchResult {
+        proto::apprepo::ApprepoSearchResult {
+            apprepo: Some(self.apprepo.to_grpc()),
+            rank: self.rank,
+            name_highlight: self.name_highlight.to_owned(),
+            description_highlight: self.description_highlight.to_owned(),
+        }
+    }
+}
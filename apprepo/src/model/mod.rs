@@ -1,4 +1,15 @@
 mod apprepo;
+mod apprepo_category;
+mod apprepo_release;
+mod apprepo_search_result;
+mod model_type;
 
 pub use apprepo::Apprepo;
 pub use apprepo::ApprepoChangeSet;
+pub use apprepo_category::ApprepoCategory;
+pub use apprepo_release::ApprepoRelease;
+pub use apprepo_search_result::ApprepoSearchResult;
+pub use model_type::enum_apprepo_release_arch::ApprepoReleaseArch;
+pub use model_type::enum_apprepo_release_channel::ApprepoReleaseChannel;
+pub use model_type::enum_apprepo_release_platform::ApprepoReleasePlatform;
+pub use model_type::enum_apprepo_status::ApprepoStatus;
@@ -0,0 +1,78 @@
+use std::io::Write;
+
+use diesel::{
+    deserialize::{self, FromSql},
+    pg::{Pg, PgValue},
+    serialize::{self, IsNull, Output, ToSql},
+};
+
+use crate::{proto, schema};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, diesel::AsExpression, diesel::FromSqlRow)]
+#[diesel(sql_type = schema::sql_types::ApprepoReleaseArch)]
+pub enum ApprepoReleaseArch {
+    X86_64,
+    Aarch64,
+    X86,
+    Arm,
+    Universal,
+}
+
+impl ToSql<schema::sql_types::ApprepoReleaseArch, Pg> for ApprepoReleaseArch {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            ApprepoReleaseArch::X86_64 => out.write_all(b"x86_64")?,
+            ApprepoReleaseArch::Aarch64 => out.write_all(b"aarch64")?,
+            ApprepoReleaseArch::X86 => out.write_all(b"x86")?,
+            ApprepoReleaseArch::Arm => out.write_all(b"arm")?,
+            ApprepoReleaseArch::Universal => out.write_all(b"universal")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<schema::sql_types::ApprepoReleaseArch, Pg> for ApprepoReleaseArch {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"x86_64" => Ok(ApprepoReleaseArch::X86_64),
+            b"aarch64" => Ok(ApprepoReleaseArch::Aarch64),
+            b"x86" => Ok(ApprepoReleaseArch::X86),
+            b"arm" => Ok(ApprepoReleaseArch::Arm),
+            b"universal" => Ok(ApprepoReleaseArch::Universal),
+            _ => Err("Unrecognized enum variant".into()),
+        }
+    }
+}
+
+impl ApprepoReleaseArch {
+    /// The name the enum has in the database and in the release manifests.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ApprepoReleaseArch::X86_64 => "x86_64",
+            ApprepoReleaseArch::Aarch64 => "aarch64",
+            ApprepoReleaseArch::X86 => "x86",
+            ApprepoReleaseArch::Arm => "arm",
+            ApprepoReleaseArch::Universal => "universal",
+        }
+    }
+
+    pub fn to_grpc_enum(self) -> i32 {
+        match self {
+            ApprepoReleaseArch::X86_64 => proto::apprepo::ApprepoReleaseArch::X8664.into(),
+            ApprepoReleaseArch::Aarch64 => proto::apprepo::ApprepoReleaseArch::Aarch64.into(),
+            ApprepoReleaseArch::X86 => proto::apprepo::ApprepoReleaseArch::X86.into(),
+            ApprepoReleaseArch::Arm => proto::apprepo::ApprepoReleaseArch::Arm.into(),
+            ApprepoReleaseArch::Universal => proto::apprepo::ApprepoReleaseArch::Universal.into(),
+        }
+    }
+
+    pub fn from_grpc_enum(value: i32) -> Option<Self> {
+        match proto::apprepo::ApprepoReleaseArch::from_i32(value)? {
+            proto::apprepo::ApprepoReleaseArch::X8664 => Some(ApprepoReleaseArch::X86_64),
+            proto::apprepo::ApprepoReleaseArch::Aarch64 => Some(ApprepoReleaseArch::Aarch64),
+            proto::apprepo::ApprepoReleaseArch::X86 => Some(ApprepoReleaseArch::X86),
+            proto::apprepo::ApprepoReleaseArch::Arm => Some(ApprepoReleaseArch::Arm),
+            proto::apprepo::ApprepoReleaseArch::Universal => Some(ApprepoReleaseArch::Universal),
+        }
+    }
+}
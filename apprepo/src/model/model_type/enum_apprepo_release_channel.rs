@@ -0,0 +1,60 @@
+use std::io::Write;
+
+use diesel::{
+    deserialize::{self, FromSql},
+    pg::{Pg, PgValue},
+    serialize::{self, IsNull, Output, ToSql},
+};
+
+use crate::{proto, schema};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, diesel::AsExpression, diesel::FromSqlRow)]
+#[diesel(sql_type = schema::sql_types::ApprepoReleaseChannel)]
+pub enum ApprepoReleaseChannel {
+    Stable,
+    Beta,
+}
+
+impl ToSql<schema::sql_types::ApprepoReleaseChannel, Pg> for ApprepoReleaseChannel {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            ApprepoReleaseChannel::Stable => out.write_all(b"stable")?,
+            ApprepoReleaseChannel::Beta => out.write_all(b"beta")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<schema::sql_types::ApprepoReleaseChannel, Pg> for ApprepoReleaseChannel {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"stable" => Ok(ApprepoReleaseChannel::Stable),
+            b"beta" => Ok(ApprepoReleaseChannel::Beta),
+            _ => Err("Unrecognized enum variant".into()),
+        }
+    }
+}
+
+impl ApprepoReleaseChannel {
+    /// The name the enum has in the database and in the release manifests.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ApprepoReleaseChannel::Stable => "stable",
+            ApprepoReleaseChannel::Beta => "beta",
+        }
+    }
+
+    pub fn to_grpc_enum(self) -> i32 {
+        match self {
+            ApprepoReleaseChannel::Stable => proto::apprepo::ApprepoReleaseChannel::Stable.into(),
+            ApprepoReleaseChannel::Beta => proto::apprepo::ApprepoReleaseChannel::Beta.into(),
+        }
+    }
+
+    pub fn from_grpc_enum(value: i32) -> Option<Self> {
+        match proto::apprepo::ApprepoReleaseChannel::from_i32(value)? {
+            proto::apprepo::ApprepoReleaseChannel::Stable => Some(ApprepoReleaseChannel::Stable),
+            proto::apprepo::ApprepoReleaseChannel::Beta => Some(ApprepoReleaseChannel::Beta),
+        }
+    }
+}
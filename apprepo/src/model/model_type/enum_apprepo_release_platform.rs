@@ -0,0 +1,86 @@
+use std::io::Write;
+
+use diesel::{
+    deserialize::{self, FromSql},
+    pg::{Pg, PgValue},
+    serialize::{self, IsNull, Output, ToSql},
+};
+
+use crate::{proto, schema};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, diesel::AsExpression, diesel::FromSqlRow)]
+#[diesel(sql_type = schema::sql_types::ApprepoReleasePlatform)]
+pub enum ApprepoReleasePlatform {
+    Windows,
+    Macos,
+    Linux,
+    Android,
+    Ios,
+}
+
+impl ToSql<schema::sql_types::ApprepoReleasePlatform, Pg> for ApprepoReleasePlatform {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            ApprepoReleasePlatform::Windows => out.write_all(b"windows")?,
+            ApprepoReleasePlatform::Macos => out.write_all(b"macos")?,
+            ApprepoReleasePlatform::Linux => out.write_all(b"linux")?,
+            ApprepoReleasePlatform::Android => out.write_all(b"android")?,
+            ApprepoReleasePlatform::Ios => out.write_all(b"ios")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<schema::sql_types::ApprepoReleasePlatform, Pg> for ApprepoReleasePlatform {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"windows" => Ok(ApprepoReleasePlatform::Windows),
+            b"macos" => Ok(ApprepoReleasePlatform::Macos),
+            b"linux" => Ok(ApprepoReleasePlatform::Linux),
+            b"android" => Ok(ApprepoReleasePlatform::Android),
+            b"ios" => Ok(ApprepoReleasePlatform::Ios),
+            _ => Err("Unrecognized enum variant".into()),
+        }
+    }
+}
+
+impl ApprepoReleasePlatform {
+    /// The name the enum has in the database and in the release manifests.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ApprepoReleasePlatform::Windows => "windows",
+            ApprepoReleasePlatform::Macos => "macos",
+            ApprepoReleasePlatform::Linux => "linux",
+            ApprepoReleasePlatform::Android => "android",
+            ApprepoReleasePlatform::Ios => "ios",
+        }
+    }
+
+    pub fn to_grpc_enum(self) -> i32 {
+        match self {
+            ApprepoReleasePlatform::Windows => {
+                proto::apprepo::ApprepoReleasePlatform::Windows.into()
+            }
+            ApprepoReleasePlatform::Macos => proto::apprepo::ApprepoReleasePlatform::Macos.into(),
+            ApprepoReleasePlatform::Linux => proto::apprepo::ApprepoReleasePlatform::Linux.into(),
+            ApprepoReleasePlatform::Android => {
+                proto::apprepo::ApprepoReleasePlatform::Android.into()
+            }
+            ApprepoReleasePlatform::Ios => proto::apprepo::ApprepoReleasePlatform::Ios.into(),
+        }
+    }
+
+    pub fn from_grpc_enum(value: i32) -> Option<Self> {
+        match proto::apprepo::ApprepoReleasePlatform::from_i32(value)? {
+            proto::apprepo::ApprepoReleasePlatform::Windows => {
+                Some(ApprepoReleasePlatform::Windows)
+            }
+            proto::apprepo::ApprepoReleasePlatform::Macos => Some(ApprepoReleasePlatform::Macos),
+            proto::apprepo::ApprepoReleasePlatform::Linux => Some(ApprepoReleasePlatform::Linux),
+            proto::apprepo::ApprepoReleasePlatform::Android => {
+                Some(ApprepoReleasePlatform::Android)
+            }
+            proto::apprepo::ApprepoReleasePlatform::Ios => Some(ApprepoReleasePlatform::Ios),
+        }
+    }
+}
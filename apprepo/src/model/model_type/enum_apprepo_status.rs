@@ -0,0 +1,57 @@
+use std::io::Write;
+
+use diesel::{
+    deserialize::{self, FromSql},
+    pg::{Pg, PgValue},
+    serialize::{self, IsNull, Output, ToSql},
+};
+
+use crate::{proto, schema};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, diesel::AsExpression, diesel::FromSqlRow)]
+#[diesel(sql_type = schema::sql_types::ApprepoStatus)]
+pub enum ApprepoStatus {
+    Draft,
+    Published,
+    Archived,
+}
+
+impl ToSql<schema::sql_types::ApprepoStatus, Pg> for ApprepoStatus {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            ApprepoStatus::Draft => out.write_all(b"draft")?,
+            ApprepoStatus::Published => out.write_all(b"published")?,
+            ApprepoStatus::Archived => out.write_all(b"archived")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<schema::sql_types::ApprepoStatus, Pg> for ApprepoStatus {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"draft" => Ok(ApprepoStatus::Draft),
+            b"published" => Ok(ApprepoStatus::Published),
+            b"archived" => Ok(ApprepoStatus::Archived),
+            _ => Err("Unrecognized enum variant".into()),
+        }
+    }
+}
+
+impl ApprepoStatus {
+    pub fn to_grpc_enum(self) -> i32 {
+        match self {
+            ApprepoStatus::Draft => proto::apprepo::ApprepoStatus::Draft.into(),
+            ApprepoStatus::Published => proto::apprepo::ApprepoStatus::Published.into(),
+            ApprepoStatus::Archived => proto::apprepo::ApprepoStatus::Archived.into(),
+        }
+    }
+
+    pub fn from_grpc_enum(value: i32) -> Option<Self> {
+        match proto::apprepo::ApprepoStatus::from_i32(value)? {
+            proto::apprepo::ApprepoStatus::Draft => Some(ApprepoStatus::Draft),
+            proto::apprepo::ApprepoStatus::Published => Some(ApprepoStatus::Published),
+            proto::apprepo::ApprepoStatus::Archived => Some(ApprepoStatus::Archived),
+        }
+    }
+}
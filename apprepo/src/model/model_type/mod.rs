@@ -0,0 +1,4 @@
+pub mod enum_apprepo_release_arch;
+pub mod enum_apprepo_release_channel;
+pub mod enum_apprepo_release_platform;
+pub mod enum_apprepo_status;
@@ -1,6 +1,27 @@
 // @generated automatically by Diesel CLI.
 
+pub mod sql_types {
+    #[derive(diesel::sql_types::SqlType, diesel::query_builder::QueryId)]
+    #[diesel(postgres_type(name = "apprepo_release_arch"))]
+    pub struct ApprepoReleaseArch;
+
+    #[derive(diesel::sql_types::SqlType, diesel::query_builder::QueryId)]
+    #[diesel(postgres_type(name = "apprepo_release_channel"))]
+    pub struct ApprepoReleaseChannel;
+
+    #[derive(diesel::sql_types::SqlType, diesel::query_builder::QueryId)]
+    #[diesel(postgres_type(name = "apprepo_release_platform"))]
+    pub struct ApprepoReleasePlatform;
+
+    #[derive(diesel::sql_types::SqlType, diesel::query_builder::QueryId)]
+    #[diesel(postgres_type(name = "apprepo_status"))]
+    pub struct ApprepoStatus;
+}
+
 diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ApprepoStatus;
+
     apprepo (id) {
         id -> Uuid,
         name -> Text,
@@ -8,5 +29,74 @@ diesel::table! {
         link -> Text,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        category_id -> Nullable<Uuid>,
+        position -> Int4,
+        status -> ApprepoStatus,
+        is_featured -> Bool,
+        description -> Text,
+        tags -> Array<Text>,
+    }
+}
+
+diesel::table! {
+    apprepo_category (id) {
+        id -> Uuid,
+        name -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ApprepoReleasePlatform;
+
+    apprepo_download (release_id, date, referrer) {
+        release_id -> Uuid,
+        apprepo_id -> Uuid,
+        date -> Date,
+        platform -> ApprepoReleasePlatform,
+        referrer -> Text,
+        count -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ApprepoReleasePlatform;
+    use super::sql_types::ApprepoReleaseArch;
+    use super::sql_types::ApprepoReleaseChannel;
+
+    apprepo_release (id) {
+        id -> Uuid,
+        apprepo_id -> Uuid,
+        version -> Text,
+        platform -> ApprepoReleasePlatform,
+        arch -> ApprepoReleaseArch,
+        download_url -> Text,
+        checksum -> Text,
+        changelog -> Text,
+        released_at -> Timestamp,
+        yanked_at -> Nullable<Timestamp>,
+        yanked_reason -> Nullable<Text>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        channel -> ApprepoReleaseChannel,
+        rollout_percentage -> Int4,
+        signature -> Nullable<Text>,
+    }
+}
+
+diesel::joinable!(apprepo -> apprepo_category (category_id));
+diesel::joinable!(apprepo_download -> apprepo (apprepo_id));
+diesel::joinable!(apprepo_download -> apprepo_release (release_id));
+diesel::joinable!(apprepo_release -> apprepo (apprepo_id));
+
+diesel::allow_tables_to_appear_in_same_query!(
+    apprepo,
+    apprepo_category,
+    apprepo_download,
+    apprepo_release,
+);
@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+
+use tokio::fs;
+
+/// Keeps the files in a directory, they are served from the public url by a web server.
+pub struct LocalStorage {
+    pub dir: PathBuf,
+    pub public_url: String,
+}
+
+impl LocalStorage {
+    pub async fn put(&self, key: &str, content: &[u8]) -> Result<String, String> {
+        let path = self.dir.join(key);
+
+        // A file with the same key has the same content
+        if fs::try_exists(&path).await.map_err(|e| e.to_string())? {
+            return Ok(super::public_url(&self.public_url, key));
+        }
+
+        // Write to a temporary file first so that a failed upload never leaves half a file
+        // under the key
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        let tmp_path = path.with_extension(format!("{}.tmp", uuid::Uuid::new_v4()));
+        fs::write(&tmp_path, content)
+            .await
+            .map_err(|e| e.to_string())?;
+        if let Err(e) = fs::rename(&tmp_path, &path).await {
+            let _ = fs::remove_file(&tmp_path).await;
+            return Err(e.to_string());
+        }
+
+        Ok(super::public_url(&self.public_url, key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Gives a storage in a new directory under the temporary directory.
+    fn storage() -> LocalStorage {
+        LocalStorage {
+            dir: std::env::temp_dir().join(format!("apprepo-{}", uuid::Uuid::new_v4())),
+            public_url: "https://files.example.com/".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn writes_a_file_under_its_key() {
+        let storage = storage();
+
+        let url = storage.put("icons/abc.png", b"icon").await.unwrap();
+        assert_eq!(url, "https://files.example.com/icons/abc.png");
+        assert_eq!(
+            fs::read(storage.dir.join("icons/abc.png")).await.unwrap(),
+            b"icon"
+        );
+
+        fs::remove_dir_all(&storage.dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn keeps_the_first_file_of_a_key() {
+        let storage = storage();
+
+        let first_url = storage.put("artifacts/abc.zip", b"first").await.unwrap();
+        let second_url = storage.put("artifacts/abc.zip", b"second").await.unwrap();
+        assert_eq!(first_url, second_url);
+        assert_eq!(
+            fs::read(storage.dir.join("artifacts/abc.zip"))
+                .await
+                .unwrap(),
+            b"first"
+        );
+
+        // No temporary file is left next to it
+        let mut entries = fs::read_dir(storage.dir.join("artifacts")).await.unwrap();
+        let mut names = vec![];
+        while let Some(entry) = entries.next_entry().await.unwrap() {
+            names.push(entry.file_name());
+        }
+        assert_eq!(names, ["abc.zip"]);
+
+        fs::remove_dir_all(&storage.dir).await.unwrap();
+    }
+}
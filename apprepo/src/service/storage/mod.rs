@@ -0,0 +1,30 @@
+use self::{local::LocalStorage, s3::S3Storage};
+
+pub mod local;
+pub mod s3;
+
+/// Where the uploaded icons and release artifacts are kept. The keys are content addressed,
+/// so a key always holds the same file.
+pub enum Storage {
+    Local(LocalStorage),
+    S3(Box<S3Storage>),
+}
+
+impl Storage {
+    /// Saves a file under a key and gives the url it is served from.
+    pub async fn put(
+        &self,
+        key: &str,
+        content: &[u8],
+        content_type: &str,
+    ) -> Result<String, String> {
+        match self {
+            Storage::Local(storage) => storage.put(key, content).await,
+            Storage::S3(storage) => storage.put(key, content, content_type).await,
+        }
+    }
+}
+
+fn public_url(base_url: &str, key: &str) -> String {
+    format!("{}/{key}", base_url.trim_end_matches('/'))
+}
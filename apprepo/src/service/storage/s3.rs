@@ -0,0 +1,112 @@
+use s3::{creds::Credentials, Bucket, Region};
+
+/// Keeps the files in an S3 bucket, any S3-compatible server like MinIO works with a custom
+/// endpoint.
+pub struct S3Storage {
+    pub bucket: Bucket,
+    pub public_url: String,
+}
+
+pub struct Config {
+    pub endpoint: String,
+    pub region: String,
+    pub bucket: String,
+    pub access_key: String,
+    pub secret_key: String,
+    pub public_url: String,
+}
+
+impl S3Storage {
+    pub fn new(config: Config) -> Result<Self, String> {
+        let region = Region::Custom {
+            region: config.region,
+            endpoint: config.endpoint,
+        };
+        let credentials = Credentials::new(
+            Some(&config.access_key),
+            Some(&config.secret_key),
+            None,
+            None,
+            None,
+        )
+        .map_err(|e| e.to_string())?;
+
+        // Path style urls work with the S3-compatible servers that don't have a domain per
+        // bucket
+        let bucket = Bucket::new(&config.bucket, region, credentials)
+            .map_err(|e| e.to_string())?
+            .with_path_style();
+
+        Ok(S3Storage {
+            bucket,
+            public_url: config.public_url,
+        })
+    }
+
+    pub async fn put(
+        &self,
+        key: &str,
+        content: &[u8],
+        content_type: &str,
+    ) -> Result<String, String> {
+        self.bucket
+            .put_object_with_content_type(key, content, content_type)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(super::public_url(&self.public_url, key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::{Response, TestServer};
+
+    fn storage(endpoint: String) -> S3Storage {
+        S3Storage::new(Config {
+            endpoint,
+            region: "us-east-1".to_string(),
+            bucket: "apprepo".to_string(),
+            access_key: "access".to_string(),
+            secret_key: "secret".to_string(),
+            public_url: "https://files.example.com".to_string(),
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn puts_a_file_in_the_bucket() {
+        let server = TestServer::start(Response::default()).await;
+
+        let url = storage(server.url.to_owned())
+            .put("icons/abc.png", b"icon", "image/png")
+            .await
+            .unwrap();
+        assert_eq!(url, "https://files.example.com/icons/abc.png");
+
+        let reqs = server.requests();
+        assert_eq!(reqs.len(), 1);
+        let head = reqs[0].head.to_ascii_lowercase();
+        assert!(
+            head.starts_with("put /apprepo/icons/abc.png http/1.1\r\n"),
+            "{head}"
+        );
+        assert!(head.contains("\r\ncontent-type: image/png\r\n"), "{head}");
+        assert!(
+            head.contains("\r\nauthorization: aws4-hmac-sha256 "),
+            "{head}"
+        );
+        assert_eq!(reqs[0].body, b"icon");
+    }
+
+    #[tokio::test]
+    async fn fails_when_the_bucket_rejects_the_file() {
+        let server = TestServer::start(Response { status: 403 }).await;
+
+        let result = storage(server.url.to_owned())
+            .put("icons/abc.png", b"icon", "image/png")
+            .await;
+        assert!(result.is_err());
+    }
+}
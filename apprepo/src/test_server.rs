@@ -0,0 +1,96 @@
+//! A local HTTP server for the tests of the outgoing requests, like the uploads to the bucket.
+
+use std::sync::{Arc, Mutex};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+/// The response to every request.
+pub struct Response {
+    pub status: u16,
+}
+
+impl Default for Response {
+    fn default() -> Self {
+        Response { status: 200 }
+    }
+}
+
+#[derive(Clone)]
+pub struct Request {
+    pub head: String,
+    pub body: Vec<u8>,
+}
+
+pub struct TestServer {
+    pub url: String,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl TestServer {
+    /// Answers every request on a local port with the same response and keeps the requests.
+    pub async fn start(res: Response) -> TestServer {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let status = res.status;
+        let requests = Arc::new(Mutex::new(vec![]));
+
+        let server_requests = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let requests = server_requests.clone();
+                tokio::spawn(async move {
+                    let mut req = vec![];
+                    let mut buf = [0; 4096];
+                    let head_len = loop {
+                        let len = stream.read(&mut buf).await.unwrap_or_default();
+                        if len == 0 {
+                            break None;
+                        }
+                        req.extend_from_slice(&buf[..len]);
+                        if let Some(index) = req.windows(4).position(|w| w == b"\r\n\r\n") {
+                            break Some(index + 4);
+                        }
+                    };
+                    let Some(head_len) = head_len else {
+                        return;
+                    };
+                    let head = String::from_utf8_lossy(&req[..head_len]).to_string();
+                    let content_length = head
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().ok())?
+                        })
+                        .unwrap_or_default();
+                    while req.len() < head_len + content_length {
+                        let len = stream.read(&mut buf).await.unwrap_or_default();
+                        if len == 0 {
+                            break;
+                        }
+                        req.extend_from_slice(&buf[..len]);
+                    }
+                    requests.lock().unwrap().push(Request {
+                        head,
+                        body: req[head_len..].to_vec(),
+                    });
+
+                    let head = format!(
+                        "HTTP/1.1 {status} Status\r\nETag: \"etag\"\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    );
+                    let _ = stream.write_all(head.as_bytes()).await;
+                });
+            }
+        });
+
+        TestServer { url, requests }
+    }
+
+    /// The requests the server answered so far.
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+}
@@ -1,15 +1,162 @@
-use async_graphql::Object;
+use std::str::FromStr;
+
+use async_graphql::{Context, Error, Object, Result};
+use serde::{Deserialize, Serialize};
+use tonic::Request;
+use tools_apprepo::proto::{self, apprepo::ApprepoServiceClient};
+use tools_lib_db::pg::connection::DbPool;
 use uuid::Uuid;
 
+use crate::{
+    dto::{service_name::ServiceName, token::Token},
+    env::{AppMode, GrpcConnectTimeout},
+    helper, service,
+};
+
+/// A page of the apprepos, the total counts every apprepo that matches the filters.
+pub struct ApprepoList {
+    pub apprepos: Vec<Apprepo>,
+    pub total: i64,
+}
+
+impl ApprepoList {
+    pub fn from_grpc(apprepos: &proto::apprepo::Apprepos) -> Result<Self, uuid::Error> {
+        Ok(ApprepoList {
+            apprepos: apprepos
+                .apprepos
+                .iter()
+                .map(Apprepo::from_grpc)
+                .collect::<Result<Vec<Apprepo>, uuid::Error>>()?,
+            total: apprepos.total,
+        })
+    }
+}
+
+#[Object]
+impl ApprepoList {
+    async fn apprepos(&self) -> &Vec<Apprepo> {
+        &self.apprepos
+    }
+
+    async fn total(&self) -> i64 {
+        self.total
+    }
+}
+
+/// A page of the search results, the total counts every apprepo that matches the query.
+pub struct ApprepoSearchResults {
+    pub results: Vec<ApprepoSearchResult>,
+    pub total: i64,
+}
+
+impl ApprepoSearchResults {
+    pub fn from_grpc(results: &proto::apprepo::ApprepoSearchResults) -> Result<Self, Error> {
+        Ok(ApprepoSearchResults {
+            results: results
+                .results
+                .iter()
+                .map(ApprepoSearchResult::from_grpc)
+                .collect::<Result<Vec<ApprepoSearchResult>, Error>>()?,
+            total: results.total,
+        })
+    }
+}
+
+#[Object]
+impl ApprepoSearchResults {
+    async fn results(&self) -> &Vec<ApprepoSearchResult> {
+        &self.results
+    }
+
+    async fn total(&self) -> i64 {
+        self.total
+    }
+}
+
+pub struct ApprepoSearchResult {
+    pub apprepo: Apprepo,
+    pub rank: f32,
+    pub name_highlight: String,
+    pub description_highlight: String,
+}
+
+impl ApprepoSearchResult {
+    pub fn from_grpc(result: &proto::apprepo::ApprepoSearchResult) -> Result<Self, Error> {
+        Ok(ApprepoSearchResult {
+            apprepo: Apprepo::from_grpc(
+                result
+                    .apprepo
+                    .as_ref()
+                    .ok_or("The search result has no apprepo.")?,
+            )?,
+            rank: result.rank,
+            name_highlight: result.name_highlight.to_owned(),
+            description_highlight: result.description_highlight.to_owned(),
+        })
+    }
+}
+
+#[Object]
+impl ApprepoSearchResult {
+    async fn apprepo(&self) -> &Apprepo {
+        &self.apprepo
+    }
+
+    /// How well the apprepo matches the query, the higher the better.
+    async fn rank(&self) -> f32 {
+        self.rank
+    }
+
+    /// The name as HTML, with the matched words wrapped in <mark> and </mark> and the rest of
+    /// the name escaped.
+    async fn name_highlight(&self) -> &str {
+        &self.name_highlight
+    }
+
+    /// The parts of the description that match best, highlighted like the name.
+    async fn description_highlight(&self) -> &str {
+        &self.description_highlight
+    }
+}
+
 pub struct Apprepo {
     pub id: Uuid,
     pub name: String,
     pub icon: String,
     pub link: String,
+    pub category_id: Option<Uuid>,
+    pub position: i32,
+    pub status: ApprepoStatus,
+    pub is_featured: bool,
+    pub description: String,
+    pub tags: Vec<String>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+impl Apprepo {
+    pub fn from_grpc(apprepo: &proto::apprepo::Apprepo) -> Result<Self, uuid::Error> {
+        Ok(Apprepo {
+            id: Uuid::from_str(&apprepo.id)?,
+            name: apprepo.name.to_owned(),
+            icon: apprepo.icon.to_owned(),
+            link: apprepo.link.to_owned(),
+            category_id: apprepo
+                .category_id
+                .as_ref()
+                .map(|category_id| Uuid::from_str(category_id))
+                .transpose()?,
+            position: apprepo.position,
+            status: ApprepoStatus::from_grpc_enum(apprepo.status),
+            is_featured: apprepo.is_featured,
+            description: apprepo.description.to_owned(),
+            tags: apprepo.tags.to_owned(),
+            created_at: apprepo.created_at.to_owned(),
+            updated_at: apprepo.updated_at.to_owned(),
+        })
+    }
+}
+
 #[Object]
 impl Apprepo {
     async fn id(&self) -> &Uuid {
@@ -28,6 +175,406 @@ impl Apprepo {
         &self.link
     }
 
+    async fn category_id(&self) -> &Option<Uuid> {
+        &self.category_id
+    }
+
+    /// The apprepos are listed by position, the lowest first.
+    async fn position(&self) -> i32 {
+        self.position
+    }
+
+    async fn status(&self) -> &ApprepoStatus {
+        &self.status
+    }
+
+    async fn is_featured(&self) -> bool {
+        self.is_featured
+    }
+
+    async fn description(&self) -> &str {
+        &self.description
+    }
+
+    async fn tags(&self) -> &Vec<String> {
+        &self.tags
+    }
+
+    async fn created_at(&self) -> &str {
+        &self.created_at
+    }
+
+    async fn updated_at(&self) -> &str {
+        &self.updated_at
+    }
+
+    /// The releases of the app, the newest version first.
+    async fn releases<'a>(
+        &self,
+        ctx: &Context<'a>,
+        include_yanked: Option<bool>,
+    ) -> Result<Vec<ApprepoRelease>> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+
+        let mut client = ApprepoServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::apprepo(), grpc_connect_timeout)
+                .await?,
+        );
+
+        let res = client
+            .get_apprepo_releases(Request::new(proto::apprepo::GetApprepoReleasesReq {
+                apprepo_id: self.id.to_string(),
+                include_yanked: include_yanked.unwrap_or(false),
+            }))
+            .await?;
+
+        Ok(res
+            .get_ref()
+            .releases
+            .iter()
+            .map(ApprepoRelease::from_grpc)
+            .collect::<Result<Vec<ApprepoRelease>, uuid::Error>>()?)
+    }
+
+    /// The newest release of every platform and architecture, for the download page. The
+    /// yanked releases are left out, and so are the pre-releases unless they are included.
+    async fn latest_releases<'a>(
+        &self,
+        ctx: &Context<'a>,
+        platform: Option<ApprepoReleasePlatform>,
+        include_prereleases: Option<bool>,
+    ) -> Result<Vec<ApprepoRelease>> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+
+        let mut client = ApprepoServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::apprepo(), grpc_connect_timeout)
+                .await?,
+        );
+
+        let res = client
+            .get_latest_apprepo_releases(Request::new(
+                proto::apprepo::GetLatestApprepoReleasesReq {
+                    apprepo_id: self.id.to_string(),
+                    platform: platform.map(ApprepoReleasePlatform::to_grpc_enum),
+                    include_prereleases: include_prereleases.unwrap_or(false),
+                },
+            ))
+            .await?;
+
+        Ok(res
+            .get_ref()
+            .releases
+            .iter()
+            .map(ApprepoRelease::from_grpc)
+            .collect::<Result<Vec<ApprepoRelease>, uuid::Error>>()?)
+    }
+
+    /// The downloads of the app through the download redirect, only for admins. The dates
+    /// are like 2023-03-23 in UTC, both are included and every day is counted without them.
+    async fn downloads<'a>(
+        &self,
+        ctx: &Context<'a>,
+        from: Option<String>,
+        to: Option<String>,
+        release_id: Option<Uuid>,
+    ) -> Result<ApprepoDownloadStats> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        if !helper::is_admin(db_conn, token, grpc_connect_timeout).await? {
+            return Err("Forbidden".into());
+        }
+
+        let mut client = ApprepoServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::apprepo(), grpc_connect_timeout)
+                .await?,
+        );
+
+        let res = client
+            .get_apprepo_download_stats(Request::new(proto::apprepo::GetApprepoDownloadStatsReq {
+                apprepo_id: self.id.to_string(),
+                from,
+                to,
+                release_id: release_id.map(|release_id| release_id.to_string()),
+            }))
+            .await?;
+
+        Ok(ApprepoDownloadStats::from_grpc(res.get_ref())?)
+    }
+}
+
+/// The downloads summed over the days, and split by day, platform, referrer and release.
+pub struct ApprepoDownloadStats {
+    pub total: i64,
+    pub by_date: Vec<ApprepoDownloadsByDate>,
+    pub by_platform: Vec<ApprepoDownloadsByPlatform>,
+    pub by_referrer: Vec<ApprepoDownloadsByReferrer>,
+    pub by_release: Vec<ApprepoDownloadsByRelease>,
+}
+
+impl ApprepoDownloadStats {
+    pub fn from_grpc(stats: &proto::apprepo::ApprepoDownloadStats) -> Result<Self, uuid::Error> {
+        Ok(ApprepoDownloadStats {
+            total: stats.total,
+            by_date: stats
+                .by_date
+                .iter()
+                .map(|downloads| ApprepoDownloadsByDate {
+                    date: downloads.date.to_owned(),
+                    count: downloads.count,
+                })
+                .collect(),
+            by_platform: stats
+                .by_platform
+                .iter()
+                .map(|downloads| ApprepoDownloadsByPlatform {
+                    platform: ApprepoReleasePlatform::from_grpc_enum(downloads.platform),
+                    count: downloads.count,
+                })
+                .collect(),
+            by_referrer: stats
+                .by_referrer
+                .iter()
+                .map(|downloads| ApprepoDownloadsByReferrer {
+                    referrer: downloads.referrer.to_owned(),
+                    count: downloads.count,
+                })
+                .collect(),
+            by_release: stats
+                .by_release
+                .iter()
+                .map(|downloads| {
+                    Ok(ApprepoDownloadsByRelease {
+                        release_id: Uuid::from_str(&downloads.release_id)?,
+                        version: downloads.version.to_owned(),
+                        count: downloads.count,
+                    })
+                })
+                .collect::<Result<Vec<ApprepoDownloadsByRelease>, uuid::Error>>()?,
+        })
+    }
+}
+
+#[Object]
+impl ApprepoDownloadStats {
+    async fn total(&self) -> i64 {
+        self.total
+    }
+
+    /// The days with downloads, the oldest first.
+    async fn by_date(&self) -> &Vec<ApprepoDownloadsByDate> {
+        &self.by_date
+    }
+
+    /// The platforms with the most downloads first.
+    async fn by_platform(&self) -> &Vec<ApprepoDownloadsByPlatform> {
+        &self.by_platform
+    }
+
+    /// The sites the downloads came from, the most downloads first.
+    async fn by_referrer(&self) -> &Vec<ApprepoDownloadsByReferrer> {
+        &self.by_referrer
+    }
+
+    /// The releases with the most downloads first.
+    async fn by_release(&self) -> &Vec<ApprepoDownloadsByRelease> {
+        &self.by_release
+    }
+}
+
+pub struct ApprepoDownloadsByDate {
+    pub date: String,
+    pub count: i64,
+}
+
+#[Object]
+impl ApprepoDownloadsByDate {
+    async fn date(&self) -> &str {
+        &self.date
+    }
+
+    async fn count(&self) -> i64 {
+        self.count
+    }
+}
+
+pub struct ApprepoDownloadsByPlatform {
+    pub platform: ApprepoReleasePlatform,
+    pub count: i64,
+}
+
+#[Object]
+impl ApprepoDownloadsByPlatform {
+    async fn platform(&self) -> &ApprepoReleasePlatform {
+        &self.platform
+    }
+
+    async fn count(&self) -> i64 {
+        self.count
+    }
+}
+
+pub struct ApprepoDownloadsByReferrer {
+    pub referrer: String,
+    pub count: i64,
+}
+
+#[Object]
+impl ApprepoDownloadsByReferrer {
+    /// The host of the site, empty for the direct downloads.
+    async fn referrer(&self) -> &str {
+        &self.referrer
+    }
+
+    async fn count(&self) -> i64 {
+        self.count
+    }
+}
+
+pub struct ApprepoDownloadsByRelease {
+    pub release_id: Uuid,
+    pub version: String,
+    pub count: i64,
+}
+
+#[Object]
+impl ApprepoDownloadsByRelease {
+    async fn release_id(&self) -> &Uuid {
+        &self.release_id
+    }
+
+    async fn version(&self) -> &str {
+        &self.version
+    }
+
+    async fn count(&self) -> i64 {
+        self.count
+    }
+}
+
+pub struct ApprepoRelease {
+    pub id: Uuid,
+    pub apprepo_id: Uuid,
+    pub version: String,
+    pub platform: ApprepoReleasePlatform,
+    pub arch: ApprepoReleaseArch,
+    pub download_url: String,
+    pub checksum: String,
+    pub changelog: String,
+    pub released_at: String,
+    pub yanked_at: Option<String>,
+    pub yanked_reason: Option<String>,
+    pub channel: ApprepoReleaseChannel,
+    pub rollout_percentage: i32,
+    pub signature: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl ApprepoRelease {
+    pub fn from_grpc(release: &proto::apprepo::ApprepoRelease) -> Result<Self, uuid::Error> {
+        Ok(ApprepoRelease {
+            id: Uuid::from_str(&release.id)?,
+            apprepo_id: Uuid::from_str(&release.apprepo_id)?,
+            version: release.version.to_owned(),
+            platform: ApprepoReleasePlatform::from_grpc_enum(release.platform),
+            arch: ApprepoReleaseArch::from_grpc_enum(release.arch),
+            download_url: release.download_url.to_owned(),
+            checksum: release.checksum.to_owned(),
+            changelog: release.changelog.to_owned(),
+            released_at: release.released_at.to_owned(),
+            yanked_at: release.yanked_at.to_owned(),
+            yanked_reason: release.yanked_reason.to_owned(),
+            channel: ApprepoReleaseChannel::from_grpc_enum(release.channel),
+            rollout_percentage: release.rollout_percentage,
+            signature: release.signature.to_owned(),
+            created_at: release.created_at.to_owned(),
+            updated_at: release.updated_at.to_owned(),
+        })
+    }
+}
+
+#[Object]
+impl ApprepoRelease {
+    async fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    async fn apprepo_id(&self) -> &Uuid {
+        &self.apprepo_id
+    }
+
+    /// The semantic version of the release.
+    async fn version(&self) -> &str {
+        &self.version
+    }
+
+    async fn platform(&self) -> &ApprepoReleasePlatform {
+        &self.platform
+    }
+
+    async fn arch(&self) -> &ApprepoReleaseArch {
+        &self.arch
+    }
+
+    /// The url of the redirect that counts the download, the releases given back by the admin
+    /// mutations have the url of the file.
+    async fn download_url(&self) -> &str {
+        &self.download_url
+    }
+
+    /// The SHA-256 of the download in hex.
+    async fn checksum(&self) -> &str {
+        &self.checksum
+    }
+
+    async fn changelog(&self) -> &str {
+        &self.changelog
+    }
+
+    async fn released_at(&self) -> &str {
+        &self.released_at
+    }
+
+    /// When the release was pulled, it isn't offered anymore.
+    async fn yanked_at(&self) -> &Option<String> {
+        &self.yanked_at
+    }
+
+    async fn yanked_reason(&self) -> &Option<String> {
+        &self.yanked_reason
+    }
+
+    async fn channel(&self) -> &ApprepoReleaseChannel {
+        &self.channel
+    }
+
+    /// The percentage of the installs the release is offered to as an update.
+    async fn rollout_percentage(&self) -> i32 {
+        self.rollout_percentage
+    }
+
+    /// The detached signature of the download made by the publisher.
+    async fn signature(&self) -> &Option<String> {
+        &self.signature
+    }
+
     async fn created_at(&self) -> &str {
         &self.created_at
     }
@@ -36,3 +583,205 @@ impl Apprepo {
         &self.updated_at
     }
 }
+
+/// Only the published apprepos are listed to everyone, the drafts and the archived ones are
+/// only listed to admins.
+#[derive(Clone, Copy, PartialEq, Eq, async_graphql::Enum)]
+pub enum ApprepoStatus {
+    Draft,
+    Published,
+    Archived,
+}
+
+impl ApprepoStatus {
+    pub fn to_grpc_enum(self) -> i32 {
+        match self {
+            ApprepoStatus::Draft => 0,
+            ApprepoStatus::Published => 1,
+            ApprepoStatus::Archived => 2,
+        }
+    }
+
+    pub fn from_grpc_enum(value: i32) -> Self {
+        match value {
+            0 => ApprepoStatus::Draft,
+            1 => ApprepoStatus::Published,
+            _ => ApprepoStatus::Archived,
+        }
+    }
+}
+
+pub struct ApprepoCategory {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl ApprepoCategory {
+    pub fn from_grpc(category: &proto::apprepo::ApprepoCategory) -> Result<Self, uuid::Error> {
+        Ok(ApprepoCategory {
+            id: Uuid::from_str(&category.id)?,
+            name: category.name.to_owned(),
+            created_at: category.created_at.to_owned(),
+            updated_at: category.updated_at.to_owned(),
+        })
+    }
+}
+
+#[Object]
+impl ApprepoCategory {
+    async fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn created_at(&self) -> &str {
+        &self.created_at
+    }
+
+    async fn updated_at(&self) -> &str {
+        &self.updated_at
+    }
+}
+
+/// The stable channel only gets the stable releases, the beta channel gets both.
+#[derive(Clone, Copy, PartialEq, Eq, Default, async_graphql::Enum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApprepoReleaseChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+impl ApprepoReleaseChannel {
+    pub fn to_grpc_enum(self) -> i32 {
+        match self {
+            ApprepoReleaseChannel::Stable => 0,
+            ApprepoReleaseChannel::Beta => 1,
+        }
+    }
+
+    pub fn from_grpc_enum(value: i32) -> Self {
+        match value {
+            0 => ApprepoReleaseChannel::Stable,
+            _ => ApprepoReleaseChannel::Beta,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, async_graphql::Enum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApprepoReleasePlatform {
+    Windows,
+    Macos,
+    Linux,
+    Android,
+    Ios,
+}
+
+impl ApprepoReleasePlatform {
+    pub fn to_grpc_enum(self) -> i32 {
+        match self {
+            ApprepoReleasePlatform::Windows => 0,
+            ApprepoReleasePlatform::Macos => 1,
+            ApprepoReleasePlatform::Linux => 2,
+            ApprepoReleasePlatform::Android => 3,
+            ApprepoReleasePlatform::Ios => 4,
+        }
+    }
+
+    pub fn from_grpc_enum(value: i32) -> Self {
+        match value {
+            0 => ApprepoReleasePlatform::Windows,
+            1 => ApprepoReleasePlatform::Macos,
+            2 => ApprepoReleasePlatform::Linux,
+            3 => ApprepoReleasePlatform::Android,
+            _ => ApprepoReleasePlatform::Ios,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, async_graphql::Enum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApprepoReleaseArch {
+    #[graphql(name = "X86_64")]
+    #[serde(rename = "x86_64")]
+    X86_64,
+    #[graphql(name = "AARCH64")]
+    Aarch64,
+    X86,
+    Arm,
+    /// One build for every architecture of the platform.
+    Universal,
+}
+
+impl ApprepoReleaseArch {
+    pub fn to_grpc_enum(self) -> i32 {
+        match self {
+            ApprepoReleaseArch::X86_64 => 0,
+            ApprepoReleaseArch::Aarch64 => 1,
+            ApprepoReleaseArch::X86 => 2,
+            ApprepoReleaseArch::Arm => 3,
+            ApprepoReleaseArch::Universal => 4,
+        }
+    }
+
+    pub fn from_grpc_enum(value: i32) -> Self {
+        match value {
+            0 => ApprepoReleaseArch::X86_64,
+            1 => ApprepoReleaseArch::Aarch64,
+            2 => ApprepoReleaseArch::X86,
+            3 => ApprepoReleaseArch::Arm,
+            _ => ApprepoReleaseArch::Universal,
+        }
+    }
+}
+
+/// An uploaded icon or release artifact, named after the SHA-256 of its content.
+pub struct ApprepoFile {
+    pub url: String,
+    pub key: String,
+    pub content_type: String,
+    pub size: i64,
+    pub checksum: String,
+}
+
+impl ApprepoFile {
+    pub fn from_grpc(file: &proto::apprepo::ApprepoFile) -> Self {
+        ApprepoFile {
+            url: file.url.to_owned(),
+            key: file.key.to_owned(),
+            content_type: file.content_type.to_owned(),
+            size: file.size,
+            checksum: file.checksum.to_owned(),
+        }
+    }
+}
+
+#[Object]
+impl ApprepoFile {
+    async fn url(&self) -> &str {
+        &self.url
+    }
+
+    async fn key(&self) -> &str {
+        &self.key
+    }
+
+    async fn content_type(&self) -> &str {
+        &self.content_type
+    }
+
+    /// In bytes.
+    async fn size(&self) -> i64 {
+        self.size
+    }
+
+    async fn checksum(&self) -> &str {
+        &self.checksum
+    }
+}
@@ -1,6 +1,18 @@
-use async_graphql::Object;
+use std::str::FromStr;
+
+use async_graphql::{Context, InputObject, Object, Result};
+use tonic::Request;
+use tools_lib_db::pg::connection::DbPool;
+use tools_link::proto::link::LinkServiceClient;
 use uuid::Uuid;
 
+use crate::{
+    dto::{service_name::ServiceName, token::Token},
+    env::{AppMode, GrpcConnectTimeout},
+    helper::get_account_id,
+    service,
+};
+
 pub struct Link {
     pub id: Uuid,
     pub title: String,
@@ -9,6 +21,94 @@ pub struct Link {
     pub visits: i32,
     pub created_at: String,
     pub updated_at: String,
+    pub folder_id: Option<Uuid>,
+    pub tags: Vec<LinkTag>,
+    pub utm: LinkUtm,
+    pub forward_query: bool,
+    pub variants: Vec<LinkVariant>,
+    pub interstitial: bool,
+    pub og_metadata: LinkOgMetadata,
+    pub og_metadata_override: LinkOgMetadata,
+    pub redirect_status: LinkRedirectStatus,
+    pub referrer_policy: Option<String>,
+    pub no_index: bool,
+    pub expires_at: Option<String>,
+    pub disabled_at: Option<String>,
+    pub disabled_reason: Option<String>,
+    pub deep_link: LinkDeepLink,
+    pub created_by_id: Uuid,
+    pub workspace_id: Option<Uuid>,
+    pub deleted_at: Option<String>,
+    pub health: LinkHealth,
+}
+
+impl Link {
+    pub fn from_grpc(link: &tools_link::proto::link::Link) -> Result<Self, uuid::Error> {
+        Ok(Link {
+            id: Uuid::from_str(&link.id)?,
+            title: link.title.to_owned(),
+            short_url: link.short_url.to_owned(),
+            long_url: link.long_url.to_owned(),
+            visits: link.visits,
+            created_at: link.created_at.to_owned(),
+            updated_at: link.updated_at.to_owned(),
+            folder_id: link
+                .folder_id
+                .as_ref()
+                .map(|folder_id| Uuid::from_str(folder_id))
+                .transpose()?,
+            tags: link
+                .tags
+                .iter()
+                .map(LinkTag::from_grpc)
+                .collect::<Result<Vec<LinkTag>, uuid::Error>>()?,
+            utm: link
+                .utm
+                .as_ref()
+                .map(LinkUtm::from_grpc)
+                .unwrap_or_default(),
+            forward_query: link.forward_query,
+            variants: link
+                .variants
+                .iter()
+                .map(LinkVariant::from_grpc)
+                .collect::<Result<Vec<LinkVariant>, uuid::Error>>()?,
+            interstitial: link.interstitial,
+            og_metadata: link
+                .og_metadata
+                .as_ref()
+                .map(LinkOgMetadata::from_grpc)
+                .unwrap_or_default(),
+            og_metadata_override: link
+                .og_metadata_override
+                .as_ref()
+                .map(LinkOgMetadata::from_grpc)
+                .unwrap_or_default(),
+            redirect_status: LinkRedirectStatus::from_grpc_enum(link.redirect_status),
+            referrer_policy: link.referrer_policy.to_owned(),
+            no_index: link.no_index,
+            expires_at: link.expires_at.to_owned(),
+            disabled_at: link.disabled_at.to_owned(),
+            disabled_reason: link.disabled_reason.to_owned(),
+            deep_link: link
+                .deep_link
+                .as_ref()
+                .map(LinkDeepLink::from_grpc)
+                .unwrap_or_default(),
+            created_by_id: Uuid::from_str(&link.created_by_id)?,
+            workspace_id: link
+                .workspace_id
+                .as_ref()
+                .map(|workspace_id| Uuid::from_str(workspace_id))
+                .transpose()?,
+            deleted_at: link.deleted_at.to_owned(),
+            health: link
+                .health
+                .as_ref()
+                .map(LinkHealth::from_grpc)
+                .unwrap_or_default(),
+        })
+    }
 }
 
 #[Object]
@@ -40,6 +140,728 @@ impl Link {
     async fn updated_at(&self) -> &str {
         &self.updated_at
     }
+
+    async fn folder_id(&self) -> &Option<Uuid> {
+        &self.folder_id
+    }
+
+    async fn tags(&self) -> &Vec<LinkTag> {
+        &self.tags
+    }
+
+    async fn utm(&self) -> &LinkUtm {
+        &self.utm
+    }
+
+    async fn forward_query(&self) -> &bool {
+        &self.forward_query
+    }
+
+    /// The destinations visitors are split between by weight, when the link has none
+    /// every visitor goes to `longUrl`.
+    async fn variants(&self) -> &Vec<LinkVariant> {
+        &self.variants
+    }
+
+    /// Shows a page telling visitors where they are going before redirecting them.
+    async fn interstitial(&self) -> &bool {
+        &self.interstitial
+    }
+
+    /// The metadata fetched from the long url.
+    async fn og_metadata(&self) -> &LinkOgMetadata {
+        &self.og_metadata
+    }
+
+    /// The metadata shown to crawlers in place of the fetched one.
+    async fn og_metadata_override(&self) -> &LinkOgMetadata {
+        &self.og_metadata_override
+    }
+
+    async fn redirect_status(&self) -> &LinkRedirectStatus {
+        &self.redirect_status
+    }
+
+    /// The `Referrer-Policy` header sent with the redirect.
+    async fn referrer_policy(&self) -> &Option<String> {
+        &self.referrer_policy
+    }
+
+    /// Asks search engines not to index the link with an `X-Robots-Tag` header.
+    async fn no_index(&self) -> &bool {
+        &self.no_index
+    }
+
+    /// The RFC 3339 date and time after which the link stops redirecting.
+    async fn expires_at(&self) -> &Option<String> {
+        &self.expires_at
+    }
+
+    /// When the link was disabled by a moderator, it doesn't redirect while it is set.
+    async fn disabled_at(&self) -> &Option<String> {
+        &self.disabled_at
+    }
+
+    async fn disabled_reason(&self) -> &Option<String> {
+        &self.disabled_reason
+    }
+
+    /// Opens the link in an app when it is installed on a phone.
+    async fn deep_link(&self) -> &LinkDeepLink {
+        &self.deep_link
+    }
+
+    /// The account that created the link.
+    async fn created_by_id(&self) -> &Uuid {
+        &self.created_by_id
+    }
+
+    /// The workspace that owns the link, empty for personal links.
+    async fn workspace_id(&self) -> &Option<Uuid> {
+        &self.workspace_id
+    }
+
+    /// When the link was moved to the trash.
+    async fn deleted_at(&self) -> &Option<String> {
+        &self.deleted_at
+    }
+
+    /// The last check of the long url.
+    async fn health(&self) -> &LinkHealth {
+        &self.health
+    }
+
+    /// The title, the short url and the long url the link had after each change, the newest
+    /// first.
+    async fn revisions<'a>(&self, ctx: &Context<'a>) -> Result<Vec<LinkRevision>> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .get_link_revisions(Request::new(tools_link::proto::link::GetLinkRevisionsReq {
+                link_id: self.id.to_string(),
+                created_by_id: account_id,
+            }))
+            .await?;
+
+        Ok(res
+            .get_ref()
+            .revisions
+            .iter()
+            .map(LinkRevision::from_grpc)
+            .collect::<Result<Vec<LinkRevision>, uuid::Error>>()?)
+    }
+}
+
+pub struct LinkRevision {
+    pub id: Uuid,
+    pub title: String,
+    pub short_url: String,
+    pub long_url: String,
+    pub created_by_id: Uuid,
+    pub created_at: String,
+}
+
+impl LinkRevision {
+    pub fn from_grpc(
+        revision: &tools_link::proto::link::LinkRevision,
+    ) -> Result<Self, uuid::Error> {
+        Ok(LinkRevision {
+            id: Uuid::from_str(&revision.id)?,
+            title: revision.title.to_owned(),
+            short_url: revision.short_url.to_owned(),
+            long_url: revision.long_url.to_owned(),
+            created_by_id: Uuid::from_str(&revision.created_by_id)?,
+            created_at: revision.created_at.to_owned(),
+        })
+    }
+}
+
+#[Object]
+impl LinkRevision {
+    async fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    async fn title(&self) -> &str {
+        &self.title
+    }
+
+    async fn short_url(&self) -> &str {
+        &self.short_url
+    }
+
+    async fn long_url(&self) -> &str {
+        &self.long_url
+    }
+
+    /// The account that made the change.
+    async fn created_by_id(&self) -> &Uuid {
+        &self.created_by_id
+    }
+
+    async fn created_at(&self) -> &str {
+        &self.created_at
+    }
+}
+
+#[derive(Default)]
+pub struct LinkUtm {
+    pub source: Option<String>,
+    pub medium: Option<String>,
+    pub campaign: Option<String>,
+    pub term: Option<String>,
+    pub content: Option<String>,
+}
+
+impl LinkUtm {
+    pub fn from_grpc(utm: &tools_link::proto::link::LinkUtm) -> Self {
+        LinkUtm {
+            source: utm.source.to_owned(),
+            medium: utm.medium.to_owned(),
+            campaign: utm.campaign.to_owned(),
+            term: utm.term.to_owned(),
+            content: utm.content.to_owned(),
+        }
+    }
+}
+
+#[Object]
+impl LinkUtm {
+    async fn source(&self) -> &Option<String> {
+        &self.source
+    }
+
+    async fn medium(&self) -> &Option<String> {
+        &self.medium
+    }
+
+    async fn campaign(&self) -> &Option<String> {
+        &self.campaign
+    }
+
+    async fn term(&self) -> &Option<String> {
+        &self.term
+    }
+
+    async fn content(&self) -> &Option<String> {
+        &self.content
+    }
+}
+
+#[derive(InputObject)]
+pub struct LinkUtmInput {
+    pub source: Option<String>,
+    pub medium: Option<String>,
+    pub campaign: Option<String>,
+    pub term: Option<String>,
+    pub content: Option<String>,
+}
+
+impl LinkUtmInput {
+    pub fn into_grpc(self) -> tools_link::proto::link::LinkUtm {
+        tools_link::proto::link::LinkUtm {
+            source: self.source,
+            medium: self.medium,
+            campaign: self.campaign,
+            term: self.term,
+            content: self.content,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct LinkDeepLink {
+    pub app_uri: Option<String>,
+    pub android_package: Option<String>,
+    pub ios_fallback_url: Option<String>,
+}
+
+impl LinkDeepLink {
+    pub fn from_grpc(deep_link: &tools_link::proto::link::LinkDeepLink) -> Self {
+        LinkDeepLink {
+            app_uri: deep_link.app_uri.to_owned(),
+            android_package: deep_link.android_package.to_owned(),
+            ios_fallback_url: deep_link.ios_fallback_url.to_owned(),
+        }
+    }
+}
+
+#[Object]
+impl LinkDeepLink {
+    /// The uri with the custom scheme of the app, like `myapp://path`.
+    async fn app_uri(&self) -> &Option<String> {
+        &self.app_uri
+    }
+
+    /// The application id Android opens the app uri with.
+    async fn android_package(&self) -> &Option<String> {
+        &self.android_package
+    }
+
+    /// Where iPhones go when the app isn't installed, instead of the long url.
+    async fn ios_fallback_url(&self) -> &Option<String> {
+        &self.ios_fallback_url
+    }
+}
+
+#[derive(InputObject)]
+pub struct LinkDeepLinkInput {
+    pub app_uri: Option<String>,
+    pub android_package: Option<String>,
+    pub ios_fallback_url: Option<String>,
+}
+
+impl LinkDeepLinkInput {
+    pub fn into_grpc(self) -> tools_link::proto::link::LinkDeepLink {
+        tools_link::proto::link::LinkDeepLink {
+            app_uri: self.app_uri,
+            android_package: self.android_package,
+            ios_fallback_url: self.ios_fallback_url,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct LinkHealth {
+    pub status_code: Option<i32>,
+    pub latency_ms: Option<i32>,
+    pub error: Option<String>,
+    pub checked_at: Option<String>,
+    pub failures: i32,
+    pub broken_at: Option<String>,
+}
+
+impl LinkHealth {
+    pub fn from_grpc(health: &tools_link::proto::link::LinkHealth) -> Self {
+        LinkHealth {
+            status_code: health.status_code,
+            latency_ms: health.latency_ms,
+            error: health.error.to_owned(),
+            checked_at: health.checked_at.to_owned(),
+            failures: health.failures,
+            broken_at: health.broken_at.to_owned(),
+        }
+    }
+}
+
+#[Object]
+impl LinkHealth {
+    /// The status code the long url answered with, empty when the request failed.
+    async fn status_code(&self) -> &Option<i32> {
+        &self.status_code
+    }
+
+    async fn latency_ms(&self) -> &Option<i32> {
+        &self.latency_ms
+    }
+
+    /// Why the request failed, like a timeout or a host that doesn't resolve.
+    async fn error(&self) -> &Option<String> {
+        &self.error
+    }
+
+    async fn checked_at(&self) -> &Option<String> {
+        &self.checked_at
+    }
+
+    /// The failed checks in a row.
+    async fn failures(&self) -> i32 {
+        self.failures
+    }
+
+    /// When the link was flagged as broken, empty once a check succeeds again.
+    async fn broken_at(&self) -> &Option<String> {
+        &self.broken_at
+    }
+}
+
+#[derive(Default)]
+pub struct LinkOgMetadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+}
+
+impl LinkOgMetadata {
+    pub fn from_grpc(og_metadata: &tools_link::proto::link::LinkOgMetadata) -> Self {
+        LinkOgMetadata {
+            title: og_metadata.title.to_owned(),
+            description: og_metadata.description.to_owned(),
+            image: og_metadata.image.to_owned(),
+        }
+    }
+}
+
+#[Object]
+impl LinkOgMetadata {
+    async fn title(&self) -> &Option<String> {
+        &self.title
+    }
+
+    async fn description(&self) -> &Option<String> {
+        &self.description
+    }
+
+    async fn image(&self) -> &Option<String> {
+        &self.image
+    }
+}
+
+#[derive(InputObject)]
+pub struct LinkOgMetadataInput {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+}
+
+impl LinkOgMetadataInput {
+    pub fn into_grpc(self) -> tools_link::proto::link::LinkOgMetadata {
+        tools_link::proto::link::LinkOgMetadata {
+            title: self.title,
+            description: self.description,
+            image: self.image,
+        }
+    }
+}
+
+pub struct LinkTag {
+    pub id: Uuid,
+    pub name: String,
+    pub workspace_id: Option<Uuid>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl LinkTag {
+    pub fn from_grpc(tag: &tools_link::proto::link::LinkTag) -> Result<Self, uuid::Error> {
+        Ok(LinkTag {
+            id: Uuid::from_str(&tag.id)?,
+            name: tag.name.to_owned(),
+            workspace_id: tag
+                .workspace_id
+                .as_ref()
+                .map(|workspace_id| Uuid::from_str(workspace_id))
+                .transpose()?,
+            created_at: tag.created_at.to_owned(),
+            updated_at: tag.updated_at.to_owned(),
+        })
+    }
+}
+
+#[Object]
+impl LinkTag {
+    async fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn workspace_id(&self) -> &Option<Uuid> {
+        &self.workspace_id
+    }
+
+    async fn created_at(&self) -> &str {
+        &self.created_at
+    }
+
+    async fn updated_at(&self) -> &str {
+        &self.updated_at
+    }
+}
+
+pub struct LinkFolder {
+    pub id: Uuid,
+    pub name: String,
+    pub parent_id: Option<Uuid>,
+    pub workspace_id: Option<Uuid>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl LinkFolder {
+    pub fn from_grpc(folder: &tools_link::proto::link::LinkFolder) -> Result<Self, uuid::Error> {
+        Ok(LinkFolder {
+            id: Uuid::from_str(&folder.id)?,
+            name: folder.name.to_owned(),
+            parent_id: folder
+                .parent_id
+                .as_ref()
+                .map(|parent_id| Uuid::from_str(parent_id))
+                .transpose()?,
+            workspace_id: folder
+                .workspace_id
+                .as_ref()
+                .map(|workspace_id| Uuid::from_str(workspace_id))
+                .transpose()?,
+            created_at: folder.created_at.to_owned(),
+            updated_at: folder.updated_at.to_owned(),
+        })
+    }
+}
+
+#[Object]
+impl LinkFolder {
+    async fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn parent_id(&self) -> &Option<Uuid> {
+        &self.parent_id
+    }
+
+    async fn workspace_id(&self) -> &Option<Uuid> {
+        &self.workspace_id
+    }
+
+    async fn created_at(&self) -> &str {
+        &self.created_at
+    }
+
+    async fn updated_at(&self) -> &str {
+        &self.updated_at
+    }
+}
+
+pub struct LinkVariant {
+    pub id: Uuid,
+    pub destination_url: String,
+    pub weight: i32,
+    pub visits: i32,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl LinkVariant {
+    pub fn from_grpc(variant: &tools_link::proto::link::LinkVariant) -> Result<Self, uuid::Error> {
+        Ok(LinkVariant {
+            id: Uuid::from_str(&variant.id)?,
+            destination_url: variant.destination_url.to_owned(),
+            weight: variant.weight,
+            visits: variant.visits,
+            created_at: variant.created_at.to_owned(),
+            updated_at: variant.updated_at.to_owned(),
+        })
+    }
+}
+
+#[Object]
+impl LinkVariant {
+    async fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    async fn destination_url(&self) -> &str {
+        &self.destination_url
+    }
+
+    async fn weight(&self) -> &i32 {
+        &self.weight
+    }
+
+    /// The visits sent to this variant.
+    async fn visits(&self) -> &i32 {
+        &self.visits
+    }
+
+    async fn created_at(&self) -> &str {
+        &self.created_at
+    }
+
+    async fn updated_at(&self) -> &str {
+        &self.updated_at
+    }
+}
+
+pub struct LinkReport {
+    pub id: Uuid,
+    pub link_id: Uuid,
+    pub short_url: String,
+    pub long_url: String,
+    pub reason: LinkReportReason,
+    pub details: Option<String>,
+    pub status: LinkReportStatus,
+    pub resolved_by_id: Option<Uuid>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl LinkReport {
+    pub fn from_grpc(report: &tools_link::proto::link::LinkReport) -> Result<Self, uuid::Error> {
+        Ok(LinkReport {
+            id: Uuid::from_str(&report.id)?,
+            link_id: Uuid::from_str(&report.link_id)?,
+            short_url: report.short_url.to_owned(),
+            long_url: report.long_url.to_owned(),
+            reason: LinkReportReason::from_grpc_enum(report.reason),
+            details: report.details.to_owned(),
+            status: LinkReportStatus::from_grpc_enum(report.status),
+            resolved_by_id: report
+                .resolved_by_id
+                .as_deref()
+                .map(Uuid::from_str)
+                .transpose()?,
+            created_at: report.created_at.to_owned(),
+            updated_at: report.updated_at.to_owned(),
+        })
+    }
+}
+
+#[Object]
+impl LinkReport {
+    async fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    async fn link_id(&self) -> &Uuid {
+        &self.link_id
+    }
+
+    async fn short_url(&self) -> &str {
+        &self.short_url
+    }
+
+    async fn long_url(&self) -> &str {
+        &self.long_url
+    }
+
+    async fn reason(&self) -> &LinkReportReason {
+        &self.reason
+    }
+
+    async fn details(&self) -> &Option<String> {
+        &self.details
+    }
+
+    async fn status(&self) -> &LinkReportStatus {
+        &self.status
+    }
+
+    /// The moderator who dismissed the report or disabled its link.
+    async fn resolved_by_id(&self) -> &Option<Uuid> {
+        &self.resolved_by_id
+    }
+
+    async fn created_at(&self) -> &str {
+        &self.created_at
+    }
+
+    async fn updated_at(&self) -> &str {
+        &self.updated_at
+    }
+}
+
+pub struct LinkRule {
+    pub id: Uuid,
+    pub link_id: Uuid,
+    pub position: i32,
+    pub platform: Option<LinkRulePlatform>,
+    pub country: Option<String>,
+    pub language: Option<String>,
+    pub starts_at: Option<String>,
+    pub ends_at: Option<String>,
+    pub destination_url: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl LinkRule {
+    pub fn from_grpc(rule: &tools_link::proto::link::LinkRule) -> Result<Self, uuid::Error> {
+        Ok(LinkRule {
+            id: Uuid::from_str(&rule.id)?,
+            link_id: Uuid::from_str(&rule.link_id)?,
+            position: rule.position,
+            platform: rule.platform.map(LinkRulePlatform::from_grpc_enum),
+            country: rule.country.to_owned(),
+            language: rule.language.to_owned(),
+            starts_at: rule.starts_at.to_owned(),
+            ends_at: rule.ends_at.to_owned(),
+            destination_url: rule.destination_url.to_owned(),
+            created_at: rule.created_at.to_owned(),
+            updated_at: rule.updated_at.to_owned(),
+        })
+    }
+}
+
+#[Object]
+impl LinkRule {
+    async fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    async fn link_id(&self) -> &Uuid {
+        &self.link_id
+    }
+
+    async fn position(&self) -> &i32 {
+        &self.position
+    }
+
+    async fn platform(&self) -> &Option<LinkRulePlatform> {
+        &self.platform
+    }
+
+    /// ISO 3166-1 alpha-2 code of the visitor's country.
+    async fn country(&self) -> &Option<String> {
+        &self.country
+    }
+
+    /// Primary subtag of the visitor's preferred language.
+    async fn language(&self) -> &Option<String> {
+        &self.language
+    }
+
+    async fn starts_at(&self) -> &Option<String> {
+        &self.starts_at
+    }
+
+    async fn ends_at(&self) -> &Option<String> {
+        &self.ends_at
+    }
+
+    async fn destination_url(&self) -> &str {
+        &self.destination_url
+    }
+
+    async fn created_at(&self) -> &str {
+        &self.created_at
+    }
+
+    async fn updated_at(&self) -> &str {
+        &self.updated_at
+    }
+}
+
+/// The conditions of a rule, a condition that isn't set matches any visitor.
+/// The times are RFC 3339 date and times.
+#[derive(InputObject)]
+pub struct LinkRuleInput {
+    pub platform: Option<LinkRulePlatform>,
+    pub country: Option<String>,
+    pub language: Option<String>,
+    pub starts_at: Option<String>,
+    pub ends_at: Option<String>,
+    pub destination_url: String,
 }
 
 pub struct GetLinkByShortUrlRes {
@@ -58,9 +880,56 @@ impl GetLinkByShortUrlRes {
     }
 }
 
+/// A visit counted on a link, or the visits a link resolver served from its cache.
+pub struct LinkVisited {
+    pub link_id: Uuid,
+    pub short_url: String,
+    pub count: i32,
+    pub visits: i32,
+}
+
+impl LinkVisited {
+    pub fn from_contract(visited: &tools_link::contract::LinkVisited) -> Result<Self, uuid::Error> {
+        Ok(LinkVisited {
+            link_id: Uuid::from_str(&visited.link_id)?,
+            short_url: visited.short_url.to_owned(),
+            count: visited.count,
+            visits: visited.visits,
+        })
+    }
+}
+
+#[Object]
+impl LinkVisited {
+    async fn link_id(&self) -> &Uuid {
+        &self.link_id
+    }
+
+    async fn short_url(&self) -> &str {
+        &self.short_url
+    }
+
+    /// The visits counted at once.
+    async fn count(&self) -> i32 {
+        self.count
+    }
+
+    /// The visits of the link so far.
+    async fn visits(&self) -> i32 {
+        self.visits
+    }
+}
+
 pub struct VisitLinkRes {
     pub short_url: String,
     pub long_url: String,
+    pub title: String,
+    pub interstitial: bool,
+    pub cacheable: bool,
+    pub redirect_status: LinkRedirectStatus,
+    pub referrer_policy: Option<String>,
+    pub no_index: bool,
+    pub deep_link: LinkDeepLink,
 }
 
 #[Object]
@@ -72,4 +941,321 @@ impl VisitLinkRes {
     async fn long_url(&self) -> &str {
         &self.long_url
     }
+
+    async fn title(&self) -> &str {
+        &self.title
+    }
+
+    async fn interstitial(&self) -> &bool {
+        &self.interstitial
+    }
+
+    /// Whether every visitor is sent to the same place, so that the visit can be cached.
+    async fn cacheable(&self) -> &bool {
+        &self.cacheable
+    }
+
+    async fn redirect_status(&self) -> &LinkRedirectStatus {
+        &self.redirect_status
+    }
+
+    async fn referrer_policy(&self) -> &Option<String> {
+        &self.referrer_policy
+    }
+
+    async fn no_index(&self) -> &bool {
+        &self.no_index
+    }
+
+    async fn deep_link(&self) -> &LinkDeepLink {
+        &self.deep_link
+    }
+}
+
+pub struct LinkPreview {
+    pub short_url: String,
+    pub title: String,
+    pub long_url: String,
+    pub visits: i32,
+    pub og_metadata: LinkOgMetadata,
+}
+
+#[Object]
+impl LinkPreview {
+    async fn short_url(&self) -> &str {
+        &self.short_url
+    }
+
+    async fn title(&self) -> &str {
+        &self.title
+    }
+
+    async fn long_url(&self) -> &str {
+        &self.long_url
+    }
+
+    async fn visits(&self) -> &i32 {
+        &self.visits
+    }
+
+    /// The overridden metadata, or the fetched one where it isn't overridden.
+    async fn og_metadata(&self) -> &LinkOgMetadata {
+        &self.og_metadata
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, async_graphql::Enum)]
+pub enum LinkFileFormat {
+    Csv,
+    Json,
+}
+
+impl LinkFileFormat {
+    pub fn to_grpc_enum(self) -> i32 {
+        match self {
+            LinkFileFormat::Csv => 0,
+            LinkFileFormat::Json => 1,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, async_graphql::Enum)]
+pub enum ImportConflictStrategy {
+    Skip,
+    Overwrite,
+    Rename,
+}
+
+impl ImportConflictStrategy {
+    pub fn to_grpc_enum(self) -> i32 {
+        match self {
+            ImportConflictStrategy::Skip => 0,
+            ImportConflictStrategy::Overwrite => 1,
+            ImportConflictStrategy::Rename => 2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, async_graphql::Enum)]
+pub enum ImportRowStatus {
+    Created,
+    Updated,
+    Renamed,
+    Skipped,
+    Invalid,
+    Failed,
+}
+
+impl ImportRowStatus {
+    pub fn from_grpc_enum(value: i32) -> Self {
+        match value {
+            0 => ImportRowStatus::Created,
+            1 => ImportRowStatus::Updated,
+            2 => ImportRowStatus::Renamed,
+            3 => ImportRowStatus::Skipped,
+            4 => ImportRowStatus::Invalid,
+            _ => ImportRowStatus::Failed,
+        }
+    }
+}
+
+pub struct ImportLinksRowRes {
+    pub row: u32,
+    pub short_url: String,
+    pub status: ImportRowStatus,
+    pub message: String,
+}
+
+#[Object]
+impl ImportLinksRowRes {
+    async fn row(&self) -> &u32 {
+        &self.row
+    }
+
+    async fn short_url(&self) -> &str {
+        &self.short_url
+    }
+
+    async fn status(&self) -> &ImportRowStatus {
+        &self.status
+    }
+
+    async fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+pub struct ImportLinksRes {
+    pub dry_run: bool,
+    pub total: u32,
+    pub imported: u32,
+    pub skipped: u32,
+    pub failed: u32,
+    pub rows: Vec<ImportLinksRowRes>,
+}
+
+#[Object]
+impl ImportLinksRes {
+    async fn dry_run(&self) -> &bool {
+        &self.dry_run
+    }
+
+    async fn total(&self) -> &u32 {
+        &self.total
+    }
+
+    async fn imported(&self) -> &u32 {
+        &self.imported
+    }
+
+    async fn skipped(&self) -> &u32 {
+        &self.skipped
+    }
+
+    async fn failed(&self) -> &u32 {
+        &self.failed
+    }
+
+    async fn rows(&self) -> &Vec<ImportLinksRowRes> {
+        &self.rows
+    }
+}
+
+pub struct ExportLinksRes {
+    pub content_type: String,
+    pub content: String,
+}
+
+#[Object]
+impl ExportLinksRes {
+    async fn content_type(&self) -> &str {
+        &self.content_type
+    }
+
+    async fn content(&self) -> &str {
+        &self.content
+    }
+}
+
+/// The status of the redirect, permanent redirects are cached by browsers so their next
+/// visits aren't counted.
+#[derive(Clone, Copy, PartialEq, Eq, async_graphql::Enum)]
+pub enum LinkRedirectStatus {
+    /// 307
+    TemporaryRedirect,
+    /// 308
+    PermanentRedirect,
+    /// 302
+    Found,
+    /// 301
+    MovedPermanently,
+}
+
+impl LinkRedirectStatus {
+    pub fn to_grpc_enum(self) -> i32 {
+        match self {
+            LinkRedirectStatus::TemporaryRedirect => 0,
+            LinkRedirectStatus::PermanentRedirect => 1,
+            LinkRedirectStatus::Found => 2,
+            LinkRedirectStatus::MovedPermanently => 3,
+        }
+    }
+
+    pub fn from_grpc_enum(value: i32) -> Self {
+        match value {
+            1 => LinkRedirectStatus::PermanentRedirect,
+            2 => LinkRedirectStatus::Found,
+            3 => LinkRedirectStatus::MovedPermanently,
+            _ => LinkRedirectStatus::TemporaryRedirect,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, async_graphql::Enum)]
+pub enum LinkReportReason {
+    Spam,
+    Phishing,
+    Malware,
+    IllegalContent,
+    Other,
+}
+
+impl LinkReportReason {
+    pub fn to_grpc_enum(self) -> i32 {
+        match self {
+            LinkReportReason::Spam => 0,
+            LinkReportReason::Phishing => 1,
+            LinkReportReason::Malware => 2,
+            LinkReportReason::IllegalContent => 3,
+            LinkReportReason::Other => 4,
+        }
+    }
+
+    pub fn from_grpc_enum(value: i32) -> Self {
+        match value {
+            0 => LinkReportReason::Spam,
+            1 => LinkReportReason::Phishing,
+            2 => LinkReportReason::Malware,
+            3 => LinkReportReason::IllegalContent,
+            _ => LinkReportReason::Other,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, async_graphql::Enum)]
+pub enum LinkReportStatus {
+    /// Waiting in the moderation queue.
+    Pending,
+    Dismissed,
+    /// The link was disabled.
+    Actioned,
+}
+
+impl LinkReportStatus {
+    pub fn to_grpc_enum(self) -> i32 {
+        match self {
+            LinkReportStatus::Pending => 0,
+            LinkReportStatus::Dismissed => 1,
+            LinkReportStatus::Actioned => 2,
+        }
+    }
+
+    pub fn from_grpc_enum(value: i32) -> Self {
+        match value {
+            0 => LinkReportStatus::Pending,
+            1 => LinkReportStatus::Dismissed,
+            _ => LinkReportStatus::Actioned,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, async_graphql::Enum)]
+pub enum LinkRulePlatform {
+    Ios,
+    Android,
+    Windows,
+    Macos,
+    Linux,
+}
+
+impl LinkRulePlatform {
+    pub fn to_grpc_enum(self) -> i32 {
+        match self {
+            LinkRulePlatform::Ios => 0,
+            LinkRulePlatform::Android => 1,
+            LinkRulePlatform::Windows => 2,
+            LinkRulePlatform::Macos => 3,
+            LinkRulePlatform::Linux => 4,
+        }
+    }
+
+    pub fn from_grpc_enum(value: i32) -> Self {
+        match value {
+            0 => LinkRulePlatform::Ios,
+            1 => LinkRulePlatform::Android,
+            2 => LinkRulePlatform::Windows,
+            3 => LinkRulePlatform::Macos,
+            _ => LinkRulePlatform::Linux,
+        }
+    }
 }
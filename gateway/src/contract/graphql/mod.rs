@@ -4,3 +4,4 @@ pub mod link;
 pub mod op_res;
 pub mod service_address;
 pub mod service_info;
+pub mod workspace;
@@ -0,0 +1,130 @@
+use std::str::FromStr;
+
+use async_graphql::Object;
+use uuid::Uuid;
+
+pub struct Workspace {
+    pub id: Uuid,
+    pub name: String,
+    pub role: WorkspaceRole,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl Workspace {
+    pub fn from_grpc(workspace: &tools_link::proto::link::Workspace) -> Result<Self, uuid::Error> {
+        Ok(Workspace {
+            id: Uuid::from_str(&workspace.id)?,
+            name: workspace.name.to_owned(),
+            role: WorkspaceRole::from_grpc_enum(workspace.role),
+            created_at: workspace.created_at.to_owned(),
+            updated_at: workspace.updated_at.to_owned(),
+        })
+    }
+}
+
+#[Object]
+impl Workspace {
+    async fn id(&self) -> &Uuid {
+        &self.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The role of the signed-in account in the workspace.
+    async fn role(&self) -> &WorkspaceRole {
+        &self.role
+    }
+
+    async fn created_at(&self) -> &str {
+        &self.created_at
+    }
+
+    async fn updated_at(&self) -> &str {
+        &self.updated_at
+    }
+}
+
+pub struct WorkspaceMember {
+    pub workspace_id: Uuid,
+    pub account_id: Uuid,
+    pub role: WorkspaceRole,
+    pub invited_by_id: Option<Uuid>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl WorkspaceMember {
+    pub fn from_grpc(
+        member: &tools_link::proto::link::WorkspaceMember,
+    ) -> Result<Self, uuid::Error> {
+        Ok(WorkspaceMember {
+            workspace_id: Uuid::from_str(&member.workspace_id)?,
+            account_id: Uuid::from_str(&member.account_id)?,
+            role: WorkspaceRole::from_grpc_enum(member.role),
+            invited_by_id: member
+                .invited_by_id
+                .as_ref()
+                .map(|invited_by_id| Uuid::from_str(invited_by_id))
+                .transpose()?,
+            created_at: member.created_at.to_owned(),
+            updated_at: member.updated_at.to_owned(),
+        })
+    }
+}
+
+#[Object]
+impl WorkspaceMember {
+    async fn workspace_id(&self) -> &Uuid {
+        &self.workspace_id
+    }
+
+    async fn account_id(&self) -> &Uuid {
+        &self.account_id
+    }
+
+    async fn role(&self) -> &WorkspaceRole {
+        &self.role
+    }
+
+    async fn invited_by_id(&self) -> &Option<Uuid> {
+        &self.invited_by_id
+    }
+
+    async fn created_at(&self) -> &str {
+        &self.created_at
+    }
+
+    async fn updated_at(&self) -> &str {
+        &self.updated_at
+    }
+}
+
+/// Owners manage the workspace and its members, editors manage its links and viewers can
+/// only read them.
+#[derive(Clone, Copy, PartialEq, Eq, async_graphql::Enum)]
+pub enum WorkspaceRole {
+    Owner,
+    Editor,
+    Viewer,
+}
+
+impl WorkspaceRole {
+    pub fn to_grpc_enum(self) -> i32 {
+        match self {
+            WorkspaceRole::Owner => 0,
+            WorkspaceRole::Editor => 1,
+            WorkspaceRole::Viewer => 2,
+        }
+    }
+
+    pub fn from_grpc_enum(value: i32) -> Self {
+        match value {
+            0 => WorkspaceRole::Owner,
+            1 => WorkspaceRole::Editor,
+            _ => WorkspaceRole::Viewer,
+        }
+    }
+}
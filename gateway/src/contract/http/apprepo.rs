@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+use crate::contract::graphql::apprepo::{
+    ApprepoReleaseArch, ApprepoReleaseChannel, ApprepoReleasePlatform,
+};
+
+/// The query of an update check, like
+/// `?appId=<id>&version=1.2.0&platform=windows&arch=x86_64&channel=stable&installId=<id>`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCheckQuery {
+    pub app_id: String,
+    pub version: String,
+    pub platform: ApprepoReleasePlatform,
+    pub arch: Option<ApprepoReleaseArch>,
+    #[serde(default)]
+    pub channel: ApprepoReleaseChannel,
+    #[serde(default)]
+    pub install_id: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCheckRes {
+    pub id: String,
+    pub version: String,
+    pub platform: ApprepoReleasePlatform,
+    pub arch: ApprepoReleaseArch,
+    pub channel: ApprepoReleaseChannel,
+    pub download_url: String,
+    pub checksum: String,
+    pub signature: Option<String>,
+    pub changelog: String,
+    pub released_at: String,
+}
+
+impl UpdateCheckRes {
+    pub fn from_grpc(release: &tools_apprepo::proto::apprepo::ApprepoRelease) -> Self {
+        UpdateCheckRes {
+            id: release.id.to_owned(),
+            version: release.version.to_owned(),
+            platform: ApprepoReleasePlatform::from_grpc_enum(release.platform),
+            arch: ApprepoReleaseArch::from_grpc_enum(release.arch),
+            channel: ApprepoReleaseChannel::from_grpc_enum(release.channel),
+            download_url: release.download_url.to_owned(),
+            checksum: release.checksum.to_owned(),
+            signature: release.signature.to_owned(),
+            changelog: release.changelog.to_owned(),
+            released_at: release.released_at.to_owned(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ManifestKeysRes {
+    pub keys: Vec<ManifestKeyRes>,
+}
+
+impl ManifestKeysRes {
+    pub fn from_grpc(keys: &tools_apprepo::proto::apprepo::ApprepoManifestKeys) -> Self {
+        ManifestKeysRes {
+            keys: keys.keys.iter().map(ManifestKeyRes::from_grpc).collect(),
+        }
+    }
+}
+
+/// A key the manifests can be signed with, the public key is in base64.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestKeyRes {
+    pub key_id: String,
+    pub algorithm: String,
+    pub public_key: String,
+    pub is_current: bool,
+}
+
+impl ManifestKeyRes {
+    pub fn from_grpc(key: &tools_apprepo::proto::apprepo::ApprepoManifestKey) -> Self {
+        ManifestKeyRes {
+            key_id: key.key_id.to_owned(),
+            algorithm: key.algorithm.to_owned(),
+            public_key: key.public_key.to_owned(),
+            is_current: key.is_current,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ErrorRes {
+    pub error: String,
+}
@@ -0,0 +1,207 @@
+use std::time::UNIX_EPOCH;
+
+use actix_web::{
+    http::{
+        header::{CacheControl, CacheDirective, Expires},
+        StatusCode,
+    },
+    web, HttpRequest, HttpResponse, Result,
+};
+use tonic::{transport::Channel, Code, Request, Status};
+use tools_apprepo::{
+    manifest::ManifestSignature,
+    proto::{self, apprepo::ApprepoServiceClient},
+};
+use tools_lib_db::pg::connection::DbPool;
+
+use crate::{
+    contract::http::apprepo::{ErrorRes, ManifestKeysRes, UpdateCheckQuery, UpdateCheckRes},
+    dto::service_name::ServiceName,
+    env::{AppMode, GrpcConnectTimeout},
+    service,
+};
+
+/// Tells an install of an app if there is a newer release for it, no token is needed. It
+/// answers with the release, or with 204 No Content when the install is up to date.
+pub async fn update(
+    app_mode: web::Data<AppMode>,
+    db_pool: web::Data<DbPool>,
+    grpc_connect_timeout: web::Data<GrpcConnectTimeout>,
+    query: web::Query<UpdateCheckQuery>,
+) -> Result<HttpResponse> {
+    let mut client = match client(&app_mode, &db_pool, &grpc_connect_timeout).await {
+        Ok(client) => client,
+        Err(res) => return Ok(res),
+    };
+
+    let query = query.into_inner();
+    let res = client
+        .check_apprepo_update(Request::new(proto::apprepo::CheckApprepoUpdateReq {
+            apprepo_id: query.app_id,
+            current_version: query.version,
+            platform: query.platform.to_grpc_enum(),
+            arch: query.arch.map(|arch| arch.to_grpc_enum()),
+            channel: query.channel.to_grpc_enum(),
+            install_id: query.install_id,
+        }))
+        .await;
+
+    match res {
+        Ok(res) => match &res.get_ref().release {
+            Some(release) if res.get_ref().is_available => {
+                Ok(HttpResponse::Ok().json(UpdateCheckRes::from_grpc(release)))
+            }
+            _ => Ok(HttpResponse::NoContent().finish()),
+        },
+        Err(status) => Ok(status_res(status)),
+    }
+}
+
+/// Serves the manifest of a release as the exact JSON bytes that were signed, the apps
+/// verify them with the detached signature before reading them.
+pub async fn manifest(
+    app_mode: web::Data<AppMode>,
+    db_pool: web::Data<DbPool>,
+    grpc_connect_timeout: web::Data<GrpcConnectTimeout>,
+    path: web::Path<(String, String)>,
+) -> Result<HttpResponse> {
+    match get_manifest(
+        &app_mode,
+        &db_pool,
+        &grpc_connect_timeout,
+        path.into_inner(),
+    )
+    .await
+    {
+        Ok(manifest) => Ok(HttpResponse::Ok()
+            .content_type("application/json")
+            .body(manifest.manifest)),
+        Err(res) => Ok(res),
+    }
+}
+
+/// Serves the detached signature of the manifest of a release.
+pub async fn manifest_signature(
+    app_mode: web::Data<AppMode>,
+    db_pool: web::Data<DbPool>,
+    grpc_connect_timeout: web::Data<GrpcConnectTimeout>,
+    path: web::Path<(String, String)>,
+) -> Result<HttpResponse> {
+    match get_manifest(
+        &app_mode,
+        &db_pool,
+        &grpc_connect_timeout,
+        path.into_inner(),
+    )
+    .await
+    {
+        Ok(manifest) => Ok(HttpResponse::Ok().json(ManifestSignature {
+            key_id: manifest.key_id,
+            algorithm: manifest.algorithm,
+            signature: manifest.signature,
+        })),
+        Err(res) => Ok(res),
+    }
+}
+
+/// Lists the public keys the manifests can be signed with, so the apps can learn the new
+/// key before the old one is retired.
+pub async fn manifest_keys(
+    app_mode: web::Data<AppMode>,
+    db_pool: web::Data<DbPool>,
+    grpc_connect_timeout: web::Data<GrpcConnectTimeout>,
+) -> Result<HttpResponse> {
+    let mut client = match client(&app_mode, &db_pool, &grpc_connect_timeout).await {
+        Ok(client) => client,
+        Err(res) => return Ok(res),
+    };
+
+    let res = client
+        .get_apprepo_manifest_keys(Request::new(proto::apprepo::GetApprepoManifestKeysReq {}))
+        .await;
+
+    match res {
+        Ok(res) => Ok(HttpResponse::Ok().json(ManifestKeysRes::from_grpc(res.get_ref()))),
+        Err(status) => Ok(status_res(status)),
+    }
+}
+
+/// Counts a download of a release and redirects it to the download url, the way the link
+/// resolver redirects the short urls. Nothing is cached so that every download is counted.
+pub async fn download(
+    req: HttpRequest,
+    app_mode: web::Data<AppMode>,
+    db_pool: web::Data<DbPool>,
+    grpc_connect_timeout: web::Data<GrpcConnectTimeout>,
+    path: web::Path<String>,
+) -> Result<HttpResponse> {
+    let mut client = match client(&app_mode, &db_pool, &grpc_connect_timeout).await {
+        Ok(client) => client,
+        Err(res) => return Ok(res),
+    };
+
+    let referrer = req
+        .headers()
+        .get("referer")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned);
+    let res = client
+        .download_apprepo_release(Request::new(proto::apprepo::DownloadApprepoReleaseReq {
+            release_id: path.into_inner(),
+            referrer,
+        }))
+        .await;
+
+    match res {
+        Ok(res) => Ok(HttpResponse::Found()
+            .insert_header(CacheControl(vec![CacheDirective::NoStore]))
+            .insert_header(Expires(UNIX_EPOCH.into()))
+            .insert_header(("location", res.into_inner().download_url))
+            .finish()),
+        Err(status) => Ok(status_res(status)),
+    }
+}
+
+async fn get_manifest(
+    app_mode: &AppMode,
+    db_pool: &DbPool,
+    grpc_connect_timeout: &GrpcConnectTimeout,
+    (app_id, version): (String, String),
+) -> Result<proto::apprepo::ApprepoManifest, HttpResponse> {
+    let mut client = client(app_mode, db_pool, grpc_connect_timeout).await?;
+
+    client
+        .get_apprepo_manifest(Request::new(proto::apprepo::GetApprepoManifestReq {
+            apprepo_id: app_id,
+            version,
+        }))
+        .await
+        .map(|res| res.into_inner())
+        .map_err(status_res)
+}
+
+async fn client(
+    app_mode: &AppMode,
+    db_pool: &DbPool,
+    grpc_connect_timeout: &GrpcConnectTimeout,
+) -> Result<ApprepoServiceClient<Channel>, HttpResponse> {
+    let db_conn = &mut tools_lib_db::pg::connection::get_connection(app_mode.as_str(), db_pool)
+        .map_err(|e| error_res(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    service::grpc::client::get(db_conn, &ServiceName::apprepo(), grpc_connect_timeout)
+        .await
+        .map(ApprepoServiceClient::new)
+        .map_err(|e| error_res(StatusCode::SERVICE_UNAVAILABLE, e.to_string()))
+}
+
+fn status_res(status: Status) -> HttpResponse {
+    match status.code() {
+        // The apprepo service answers aborted when the request is wrong
+        Code::Aborted => error_res(StatusCode::BAD_REQUEST, status.message().to_owned()),
+        _ => error_res(StatusCode::BAD_GATEWAY, status.message().to_owned()),
+    }
+}
+
+fn error_res(status_code: StatusCode, error: String) -> HttpResponse {
+    HttpResponse::build(status_code).json(ErrorRes { error })
+}
@@ -1,24 +1,112 @@
-use std::str::FromStr;
+use std::io::Read;
 
-use async_graphql::{Context, Object, Result};
+use async_graphql::{Context, MaybeUndefined, Object, Result, Upload};
 use tonic::Request;
 use tools_apprepo::proto::{self, apprepo::ApprepoServiceClient};
-use tools_lib_db::pg::connection::DbPool;
+use tools_lib_db::pg::connection::{DbPool, DbPooled};
 use uuid::Uuid;
 
 use crate::{
-    contract::graphql::{apprepo::Apprepo, op_res::OpRes},
+    contract::graphql::{
+        apprepo::{
+            Apprepo, ApprepoCategory, ApprepoFile, ApprepoList, ApprepoRelease, ApprepoReleaseArch,
+            ApprepoReleaseChannel, ApprepoReleasePlatform, ApprepoSearchResults, ApprepoStatus,
+        },
+        op_res::OpRes,
+    },
     dto::{service_name::ServiceName, token::Token},
     env::{AppMode, GrpcConnectTimeout},
     helper, service,
 };
 
+const UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
+const DEFAULT_PAGE_SIZE: i64 = 20;
+const MAX_PAGE_SIZE: i64 = 100;
+
 #[derive(Default)]
 pub struct ApprepoQuery;
 
 #[Object]
 impl ApprepoQuery {
-    async fn apprepos<'a>(&self, ctx: &Context<'a>) -> Result<Vec<Apprepo>> {
+    /// The published apprepos by position. Admins get the apprepos of every status unless
+    /// they filter by one.
+    async fn apprepos<'a>(
+        &self,
+        ctx: &Context<'a>,
+        category_id: Option<Uuid>,
+        is_featured: Option<bool>,
+        status: Option<ApprepoStatus>,
+        offset: Option<i64>,
+        limit: Option<i64>,
+    ) -> Result<ApprepoList> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+
+        let status = visible_status(ctx, db_conn, grpc_connect_timeout, status).await?;
+        let (offset, limit) = page(offset, limit)?;
+
+        let mut client = ApprepoServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::apprepo(), grpc_connect_timeout)
+                .await?,
+        );
+
+        let res = client
+            .get_apprepos(Request::new(proto::apprepo::GetAppreposReq {
+                status: status.map(ApprepoStatus::to_grpc_enum),
+                category_id: category_id.map(|category_id| category_id.to_string()),
+                is_featured,
+                offset,
+                limit,
+            }))
+            .await?;
+
+        Ok(ApprepoList::from_grpc(res.get_ref())?)
+    }
+
+    /// Searches the names, the tags and the descriptions of the apprepos for the words that
+    /// start with the words of the query, the best match first. Only admins can search the
+    /// apprepos that aren't published.
+    #[allow(clippy::too_many_arguments)]
+    async fn search_apprepos<'a>(
+        &self,
+        ctx: &Context<'a>,
+        query: String,
+        category_id: Option<Uuid>,
+        status: Option<ApprepoStatus>,
+        offset: Option<i64>,
+        limit: Option<i64>,
+    ) -> Result<ApprepoSearchResults> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+
+        let status = visible_status(ctx, db_conn, grpc_connect_timeout, status).await?;
+        let (offset, limit) = page(offset, limit)?;
+
+        let mut client = ApprepoServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::apprepo(), grpc_connect_timeout)
+                .await?,
+        );
+
+        let res = client
+            .search_apprepos(Request::new(proto::apprepo::SearchAppreposReq {
+                query,
+                status: status.map(ApprepoStatus::to_grpc_enum),
+                category_id: category_id.map(|category_id| category_id.to_string()),
+                offset,
+                limit,
+            }))
+            .await?;
+
+        ApprepoSearchResults::from_grpc(res.get_ref())
+    }
+
+    async fn apprepo_categories<'a>(&self, ctx: &Context<'a>) -> Result<Vec<ApprepoCategory>> {
         let db_conn = &mut tools_lib_db::pg::connection::get_connection(
             ctx.data_unchecked::<AppMode>().as_str(),
             ctx.data_unchecked::<DbPool>(),
@@ -31,22 +119,15 @@ impl ApprepoQuery {
         );
 
         let res = client
-            .get_apprepos(Request::new(proto::apprepo::GetAppreposReq {}))
+            .get_apprepo_categories(Request::new(proto::apprepo::GetApprepoCategoriesReq {}))
             .await?;
 
         Ok(res
             .get_ref()
-            .apprepos
+            .categories
             .iter()
-            .map(|apprepo| Apprepo {
-                id: Uuid::from_str(&apprepo.id).unwrap(),
-                name: apprepo.name.to_owned(),
-                icon: apprepo.icon.to_owned(),
-                link: apprepo.link.to_owned(),
-                created_at: apprepo.created_at.to_owned(),
-                updated_at: apprepo.updated_at.to_owned(),
-            })
-            .collect())
+            .map(ApprepoCategory::from_grpc)
+            .collect::<Result<Vec<ApprepoCategory>, uuid::Error>>()?)
     }
 }
 
@@ -55,12 +136,19 @@ pub struct ApprepoMutation;
 
 #[Object]
 impl ApprepoMutation {
+    /// Adds an apprepo after the others, as a draft unless a status is given.
+    #[allow(clippy::too_many_arguments)]
     async fn create_apprepo<'a>(
         &self,
         ctx: &Context<'a>,
         name: String,
         icon: String,
         link: String,
+        category_id: Option<Uuid>,
+        status: Option<ApprepoStatus>,
+        is_featured: Option<bool>,
+        description: Option<String>,
+        tags: Option<Vec<String>>,
     ) -> Result<Apprepo> {
         let db_conn = &mut tools_lib_db::pg::connection::get_connection(
             ctx.data_unchecked::<AppMode>().as_str(),
@@ -87,19 +175,19 @@ impl ApprepoMutation {
                 name,
                 icon,
                 link,
+                category_id: category_id.map(|category_id| category_id.to_string()),
+                status: status.map(ApprepoStatus::to_grpc_enum),
+                is_featured: is_featured.unwrap_or(false),
+                description: description.unwrap_or_default(),
+                tags: tags.unwrap_or_default(),
             }))
             .await?;
 
-        Ok(Apprepo {
-            id: Uuid::from_str(&res.get_ref().id)?,
-            name: res.get_ref().name.to_owned(),
-            icon: res.get_ref().icon.to_owned(),
-            link: res.get_ref().link.to_owned(),
-            created_at: res.get_ref().created_at.to_owned(),
-            updated_at: res.get_ref().updated_at.to_owned(),
-        })
+        Ok(Apprepo::from_grpc(res.get_ref())?)
     }
 
+    /// A null category takes the apprepo out of its category.
+    #[allow(clippy::too_many_arguments)]
     async fn update_apprepo<'a>(
         &self,
         ctx: &Context<'a>,
@@ -107,6 +195,11 @@ impl ApprepoMutation {
         name: Option<String>,
         icon: Option<String>,
         link: Option<String>,
+        category_id: MaybeUndefined<Uuid>,
+        status: Option<ApprepoStatus>,
+        is_featured: Option<bool>,
+        description: Option<String>,
+        tags: Option<Vec<String>>,
     ) -> Result<Apprepo> {
         let db_conn = &mut tools_lib_db::pg::connection::get_connection(
             ctx.data_unchecked::<AppMode>().as_str(),
@@ -134,17 +227,19 @@ impl ApprepoMutation {
                 name,
                 icon,
                 link,
+                category_id: match category_id {
+                    MaybeUndefined::Undefined => None,
+                    MaybeUndefined::Null => Some(String::new()),
+                    MaybeUndefined::Value(category_id) => Some(category_id.to_string()),
+                },
+                status: status.map(ApprepoStatus::to_grpc_enum),
+                is_featured,
+                description,
+                tags: tags.map(|tags| proto::apprepo::ApprepoTags { tags }),
             }))
             .await?;
 
-        Ok(Apprepo {
-            id: Uuid::from_str(&res.get_ref().id)?,
-            name: res.get_ref().name.to_owned(),
-            icon: res.get_ref().icon.to_owned(),
-            link: res.get_ref().link.to_owned(),
-            created_at: res.get_ref().created_at.to_owned(),
-            updated_at: res.get_ref().updated_at.to_owned(),
-        })
+        Ok(Apprepo::from_grpc(res.get_ref())?)
     }
 
     async fn delete_apprepo<'a>(&self, ctx: &Context<'a>, id: Uuid) -> Result<OpRes> {
@@ -178,4 +273,412 @@ impl ApprepoMutation {
             is_success: res.get_ref().is_success,
         })
     }
+
+    /// Puts the apprepos in the order of the ids, the ones left out follow them in the order
+    /// they had.
+    async fn reorder_apprepos<'a>(&self, ctx: &Context<'a>, ids: Vec<Uuid>) -> Result<OpRes> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        if !helper::is_admin(db_conn, token, grpc_connect_timeout).await? {
+            return Err("Forbidden".into());
+        }
+
+        let mut client = ApprepoServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::apprepo(), grpc_connect_timeout)
+                .await?,
+        );
+
+        let res = client
+            .reorder_apprepos(Request::new(proto::apprepo::ReorderAppreposReq {
+                ids: ids.iter().map(|id| id.to_string()).collect(),
+            }))
+            .await?;
+
+        Ok(OpRes {
+            is_success: res.get_ref().is_success,
+        })
+    }
+
+    async fn create_apprepo_category<'a>(
+        &self,
+        ctx: &Context<'a>,
+        name: String,
+    ) -> Result<ApprepoCategory> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        if !helper::is_admin(db_conn, token, grpc_connect_timeout).await? {
+            return Err("Forbidden".into());
+        }
+
+        let mut client = ApprepoServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::apprepo(), grpc_connect_timeout)
+                .await?,
+        );
+
+        let res = client
+            .create_apprepo_category(Request::new(proto::apprepo::CreateApprepoCategoryReq {
+                name,
+            }))
+            .await?;
+
+        Ok(ApprepoCategory::from_grpc(res.get_ref())?)
+    }
+
+    async fn update_apprepo_category<'a>(
+        &self,
+        ctx: &Context<'a>,
+        id: Uuid,
+        name: String,
+    ) -> Result<ApprepoCategory> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        if !helper::is_admin(db_conn, token, grpc_connect_timeout).await? {
+            return Err("Forbidden".into());
+        }
+
+        let mut client = ApprepoServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::apprepo(), grpc_connect_timeout)
+                .await?,
+        );
+
+        let res = client
+            .update_apprepo_category(Request::new(proto::apprepo::UpdateApprepoCategoryReq {
+                id: id.to_string(),
+                name,
+            }))
+            .await?;
+
+        Ok(ApprepoCategory::from_grpc(res.get_ref())?)
+    }
+
+    /// Deletes a category, its apprepos are kept without a category.
+    async fn delete_apprepo_category<'a>(&self, ctx: &Context<'a>, id: Uuid) -> Result<OpRes> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        if !helper::is_admin(db_conn, token, grpc_connect_timeout).await? {
+            return Err("Forbidden".into());
+        }
+
+        let mut client = ApprepoServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::apprepo(), grpc_connect_timeout)
+                .await?,
+        );
+
+        let res = client
+            .delete_apprepo_category(Request::new(proto::apprepo::DeleteApprepoCategoryReq {
+                id: id.to_string(),
+            }))
+            .await?;
+
+        Ok(OpRes {
+            is_success: res.get_ref().is_success,
+        })
+    }
+
+    /// Publishes a release of an app, a version can only be published once for every
+    /// platform and architecture. The release goes to the stable channel and is offered to
+    /// every install unless a channel and a rollout percentage are given.
+    #[allow(clippy::too_many_arguments)]
+    async fn publish_apprepo_release<'a>(
+        &self,
+        ctx: &Context<'a>,
+        apprepo_id: Uuid,
+        version: String,
+        platform: ApprepoReleasePlatform,
+        arch: ApprepoReleaseArch,
+        download_url: String,
+        checksum: String,
+        changelog: Option<String>,
+        released_at: Option<String>,
+        channel: Option<ApprepoReleaseChannel>,
+        rollout_percentage: Option<i32>,
+        signature: Option<String>,
+    ) -> Result<ApprepoRelease> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        if !helper::is_admin(db_conn, token, grpc_connect_timeout).await? {
+            return Err("Forbidden".into());
+        }
+
+        let mut client = ApprepoServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::apprepo(), grpc_connect_timeout)
+                .await?,
+        );
+
+        let res = client
+            .publish_apprepo_release(Request::new(proto::apprepo::PublishApprepoReleaseReq {
+                apprepo_id: apprepo_id.to_string(),
+                version,
+                platform: platform.to_grpc_enum(),
+                arch: arch.to_grpc_enum(),
+                download_url,
+                checksum,
+                changelog: changelog.unwrap_or_default(),
+                released_at,
+                channel: channel
+                    .unwrap_or(ApprepoReleaseChannel::Stable)
+                    .to_grpc_enum(),
+                rollout_percentage,
+                signature,
+            }))
+            .await?;
+
+        Ok(ApprepoRelease::from_grpc(res.get_ref())?)
+    }
+
+    /// Pulls a release, it stays in the history but isn't offered for download anymore.
+    async fn yank_apprepo_release<'a>(
+        &self,
+        ctx: &Context<'a>,
+        id: Uuid,
+        reason: Option<String>,
+    ) -> Result<ApprepoRelease> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        if !helper::is_admin(db_conn, token, grpc_connect_timeout).await? {
+            return Err("Forbidden".into());
+        }
+
+        let mut client = ApprepoServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::apprepo(), grpc_connect_timeout)
+                .await?,
+        );
+
+        let res = client
+            .yank_apprepo_release(Request::new(proto::apprepo::YankApprepoReleaseReq {
+                id: id.to_string(),
+                reason,
+            }))
+            .await?;
+
+        Ok(ApprepoRelease::from_grpc(res.get_ref())?)
+    }
+
+    /// Offers a release to a percentage of the installs, the installs that got it keep
+    /// getting it as the percentage grows.
+    async fn set_apprepo_release_rollout<'a>(
+        &self,
+        ctx: &Context<'a>,
+        id: Uuid,
+        rollout_percentage: i32,
+    ) -> Result<ApprepoRelease> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        if !helper::is_admin(db_conn, token, grpc_connect_timeout).await? {
+            return Err("Forbidden".into());
+        }
+
+        let mut client = ApprepoServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::apprepo(), grpc_connect_timeout)
+                .await?,
+        );
+
+        let res = client
+            .set_apprepo_release_rollout(Request::new(
+                proto::apprepo::SetApprepoReleaseRolloutReq {
+                    id: id.to_string(),
+                    rollout_percentage,
+                },
+            ))
+            .await?;
+
+        Ok(ApprepoRelease::from_grpc(res.get_ref())?)
+    }
+
+    /// Uploads a PNG, JPEG or WebP icon, it is shrunk to fit the icon size of the service
+    /// and saved as PNG.
+    async fn upload_apprepo_icon<'a>(
+        &self,
+        ctx: &Context<'a>,
+        file: Upload,
+    ) -> Result<ApprepoFile> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        if !helper::is_admin(db_conn, token, grpc_connect_timeout).await? {
+            return Err("Forbidden".into());
+        }
+
+        let reqs = upload_reqs(ctx, file)?;
+
+        let mut client = ApprepoServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::apprepo(), grpc_connect_timeout)
+                .await?,
+        );
+
+        let res = client
+            .upload_apprepo_icon(Request::new(tokio_stream::iter(reqs)))
+            .await?;
+
+        Ok(ApprepoFile::from_grpc(res.get_ref()))
+    }
+
+    /// Uploads the artifact of a release, its url and checksum can then be published.
+    async fn upload_apprepo_artifact<'a>(
+        &self,
+        ctx: &Context<'a>,
+        file: Upload,
+    ) -> Result<ApprepoFile> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        if !helper::is_admin(db_conn, token, grpc_connect_timeout).await? {
+            return Err("Forbidden".into());
+        }
+
+        let reqs = upload_reqs(ctx, file)?;
+
+        let mut client = ApprepoServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::apprepo(), grpc_connect_timeout)
+                .await?,
+        );
+
+        let res = client
+            .upload_apprepo_artifact(Request::new(tokio_stream::iter(reqs)))
+            .await?;
+
+        Ok(ApprepoFile::from_grpc(res.get_ref()))
+    }
+}
+
+/// Anyone can list the published apprepos, admins can list them all or filter them by any
+/// status. An invalid token is treated as no token.
+async fn visible_status(
+    ctx: &Context<'_>,
+    db_conn: &mut DbPooled,
+    grpc_connect_timeout: &u64,
+    status: Option<ApprepoStatus>,
+) -> Result<Option<ApprepoStatus>> {
+    let is_admin = match ctx.data_opt::<Token>() {
+        Some(token) => helper::is_admin(db_conn, token.0.to_owned(), grpc_connect_timeout)
+            .await
+            .unwrap_or(false),
+        None => false,
+    };
+
+    match (is_admin, status) {
+        (true, status) => Ok(status),
+        (false, None | Some(ApprepoStatus::Published)) => Ok(Some(ApprepoStatus::Published)),
+        (false, Some(_)) => Err("Forbidden".into()),
+    }
+}
+
+fn page(offset: Option<i64>, limit: Option<i64>) -> Result<(i64, i64)> {
+    let offset = offset.unwrap_or(0);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE);
+    if offset < 0 || !(1..=MAX_PAGE_SIZE).contains(&limit) {
+        return Err(format!(
+            "The offset can't be negative and the limit must be between 1 and {MAX_PAGE_SIZE}."
+        )
+        .into());
+    }
+
+    Ok((offset, limit))
+}
+
+/// Splits an uploaded file into chunks, only the first message carries the content type.
+fn upload_reqs(
+    ctx: &Context<'_>,
+    file: Upload,
+) -> Result<Vec<proto::apprepo::UploadApprepoFileReq>> {
+    let file = file.value(ctx)?;
+    let content_type = file
+        .content_type
+        .to_owned()
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+    let mut content = Vec::new();
+    file.into_read().read_to_end(&mut content)?;
+
+    let mut reqs = content
+        .chunks(UPLOAD_CHUNK_SIZE)
+        .map(|chunk| proto::apprepo::UploadApprepoFileReq {
+            chunk: chunk.to_vec(),
+            ..Default::default()
+        })
+        .collect::<Vec<proto::apprepo::UploadApprepoFileReq>>();
+    if reqs.is_empty() {
+        reqs.push(Default::default());
+    }
+    reqs[0].content_type = content_type;
+
+    Ok(reqs)
 }
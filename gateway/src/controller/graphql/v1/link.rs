@@ -1,6 +1,9 @@
-use std::str::FromStr;
+use std::io::Read;
 
-use async_graphql::{Context, Object, Result};
+use async_graphql::{
+    Context, Error, ErrorExtensions, MaybeUndefined, Object, Result, Subscription, Upload,
+};
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
 use tonic::Request;
 use tools_lib_db::pg::connection::DbPool;
 use tools_link::proto::link::LinkServiceClient;
@@ -8,21 +11,37 @@ use uuid::Uuid;
 
 use crate::{
     contract::graphql::{
-        link::{GetLinkByShortUrlRes, Link, VisitLinkRes},
+        link::{
+            ExportLinksRes, GetLinkByShortUrlRes, ImportConflictStrategy, ImportLinksRes,
+            ImportLinksRowRes, ImportRowStatus, Link, LinkDeepLink, LinkDeepLinkInput,
+            LinkFileFormat, LinkFolder, LinkOgMetadata, LinkOgMetadataInput, LinkPreview,
+            LinkRedirectStatus, LinkReport, LinkReportReason, LinkReportStatus, LinkRule,
+            LinkRuleInput, LinkRulePlatform, LinkTag, LinkUtmInput, LinkVariant, LinkVisited,
+            VisitLinkRes,
+        },
         op_res::OpRes,
     },
-    dto::{service_name::ServiceName, token::Token},
+    dto::{client_ip::ClientIp, service_name::ServiceName, token::Token},
     env::{AppMode, GrpcConnectTimeout},
-    helper::get_account_id,
-    service,
+    helper::{self, get_account_id},
+    service::{self, link_visits::LinkVisits},
 };
 
+const IMPORT_CHUNK_SIZE: usize = 64 * 1024;
+
 #[derive(Default)]
 pub struct LinkQuery;
 
 #[Object]
 impl LinkQuery {
-    async fn links<'a>(&self, ctx: &Context<'a>) -> Result<Vec<Link>> {
+    async fn links<'a>(
+        &self,
+        ctx: &Context<'a>,
+        tag_id: Option<Uuid>,
+        folder_id: Option<Uuid>,
+        include_subfolders: Option<bool>,
+        workspace_id: Option<Uuid>,
+    ) -> Result<Vec<Link>> {
         let db_conn = &mut tools_lib_db::pg::connection::get_connection(
             ctx.data_unchecked::<AppMode>().as_str(),
             ctx.data_unchecked::<DbPool>(),
@@ -43,6 +62,10 @@ impl LinkQuery {
         let res = client
             .get_links(Request::new(tools_link::proto::link::GetLinksReq {
                 created_by_id: account_id,
+                tag_id: tag_id.map(|tag_id| tag_id.to_string()),
+                folder_id: folder_id.map(|folder_id| folder_id.to_string()),
+                include_subfolders: include_subfolders.unwrap_or(false),
+                workspace_id: workspace_id.map(|workspace_id| workspace_id.to_string()),
             }))
             .await?;
 
@@ -50,16 +73,46 @@ impl LinkQuery {
             .get_ref()
             .links
             .iter()
-            .map(|link| Link {
-                id: Uuid::from_str(&link.id).unwrap(),
-                title: link.title.to_owned(),
-                short_url: link.short_url.to_owned(),
-                long_url: link.long_url.to_owned(),
-                visits: link.visits,
-                created_at: link.created_at.to_owned(),
-                updated_at: link.updated_at.to_owned(),
-            })
-            .collect())
+            .map(Link::from_grpc)
+            .collect::<Result<Vec<Link>, uuid::Error>>()?)
+    }
+
+    /// The links in the trash, the last deleted first.
+    async fn deleted_links<'a>(
+        &self,
+        ctx: &Context<'a>,
+        workspace_id: Option<Uuid>,
+    ) -> Result<Vec<Link>> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .get_deleted_links(Request::new(tools_link::proto::link::GetDeletedLinksReq {
+                created_by_id: account_id,
+                workspace_id: workspace_id.map(|workspace_id| workspace_id.to_string()),
+            }))
+            .await?;
+
+        Ok(res
+            .get_ref()
+            .links
+            .iter()
+            .map(Link::from_grpc)
+            .collect::<Result<Vec<Link>, uuid::Error>>()?)
     }
 
     async fn link<'a>(&self, ctx: &Context<'a>, id: Uuid) -> Result<Link> {
@@ -87,15 +140,7 @@ impl LinkQuery {
             }))
             .await?;
 
-        Ok(Link {
-            id: Uuid::from_str(&res.get_ref().id)?,
-            title: res.get_ref().title.to_owned(),
-            short_url: res.get_ref().short_url.to_owned(),
-            long_url: res.get_ref().long_url.to_owned(),
-            visits: res.get_ref().visits,
-            created_at: res.get_ref().created_at.to_owned(),
-            updated_at: res.get_ref().updated_at.to_owned(),
-        })
+        Ok(Link::from_grpc(res.get_ref())?)
     }
 
     async fn link_by_short_url<'a>(
@@ -125,7 +170,17 @@ impl LinkQuery {
         })
     }
 
-    async fn visit_link<'a>(&self, ctx: &Context<'a>, short_url: String) -> Result<VisitLinkRes> {
+    #[allow(clippy::too_many_arguments)]
+    async fn visit_link<'a>(
+        &self,
+        ctx: &Context<'a>,
+        short_url: String,
+        query: Option<String>,
+        user_agent: Option<String>,
+        ip_address: Option<String>,
+        accept_language: Option<String>,
+        visitor_id: Option<String>,
+    ) -> Result<VisitLinkRes> {
         let db_conn = &mut tools_lib_db::pg::connection::get_connection(
             ctx.data_unchecked::<AppMode>().as_str(),
             ctx.data_unchecked::<DbPool>(),
@@ -139,14 +194,249 @@ impl LinkQuery {
         let res = client
             .visit_link(Request::new(tools_link::proto::link::VisitLinkReq {
                 short_url,
+                query,
+                user_agent,
+                ip_address,
+                accept_language,
+                visitor_id,
             }))
-            .await?;
+            .await
+            .map_err(link_unavailable_error)?;
 
         Ok(VisitLinkRes {
             short_url: res.get_ref().short_url.to_owned(),
             long_url: res.get_ref().long_url.to_owned(),
+            title: res.get_ref().title.to_owned(),
+            interstitial: res.get_ref().interstitial,
+            cacheable: res.get_ref().cacheable,
+            redirect_status: LinkRedirectStatus::from_grpc_enum(res.get_ref().redirect_status),
+            referrer_policy: res.get_ref().referrer_policy.to_owned(),
+            no_index: res.get_ref().no_index,
+            deep_link: res
+                .get_ref()
+                .deep_link
+                .as_ref()
+                .map(LinkDeepLink::from_grpc)
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Gets what a link leads to without counting a visit.
+    async fn preview_link<'a>(&self, ctx: &Context<'a>, short_url: String) -> Result<LinkPreview> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .preview_link(Request::new(tools_link::proto::link::PreviewLinkReq {
+                short_url,
+            }))
+            .await
+            .map_err(link_unavailable_error)?;
+
+        Ok(LinkPreview {
+            short_url: res.get_ref().short_url.to_owned(),
+            title: res.get_ref().title.to_owned(),
+            long_url: res.get_ref().long_url.to_owned(),
+            visits: res.get_ref().visits,
+            og_metadata: res
+                .get_ref()
+                .og_metadata
+                .as_ref()
+                .map(LinkOgMetadata::from_grpc)
+                .unwrap_or_default(),
+        })
+    }
+
+    async fn export_links<'a>(
+        &self,
+        ctx: &Context<'a>,
+        format: LinkFileFormat,
+        workspace_id: Option<Uuid>,
+    ) -> Result<ExportLinksRes> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .export_links(Request::new(tools_link::proto::link::ExportLinksReq {
+                created_by_id: account_id,
+                format: format.to_grpc_enum(),
+                workspace_id: workspace_id.map(|workspace_id| workspace_id.to_string()),
+            }))
+            .await?;
+
+        Ok(ExportLinksRes {
+            content_type: res.get_ref().content_type.to_owned(),
+            content: String::from_utf8(res.into_inner().content)?,
         })
     }
+
+    async fn link_tags<'a>(
+        &self,
+        ctx: &Context<'a>,
+        workspace_id: Option<Uuid>,
+    ) -> Result<Vec<LinkTag>> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .get_link_tags(Request::new(tools_link::proto::link::GetLinkTagsReq {
+                created_by_id: account_id,
+                workspace_id: workspace_id.map(|workspace_id| workspace_id.to_string()),
+            }))
+            .await?;
+
+        Ok(res
+            .get_ref()
+            .tags
+            .iter()
+            .map(LinkTag::from_grpc)
+            .collect::<Result<Vec<LinkTag>, uuid::Error>>()?)
+    }
+
+    async fn link_folders<'a>(
+        &self,
+        ctx: &Context<'a>,
+        workspace_id: Option<Uuid>,
+    ) -> Result<Vec<LinkFolder>> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .get_link_folders(Request::new(tools_link::proto::link::GetLinkFoldersReq {
+                created_by_id: account_id,
+                workspace_id: workspace_id.map(|workspace_id| workspace_id.to_string()),
+            }))
+            .await?;
+
+        Ok(res
+            .get_ref()
+            .folders
+            .iter()
+            .map(LinkFolder::from_grpc)
+            .collect::<Result<Vec<LinkFolder>, uuid::Error>>()?)
+    }
+
+    /// The rules of a link in the order they are matched when the link is visited.
+    async fn link_rules<'a>(&self, ctx: &Context<'a>, link_id: Uuid) -> Result<Vec<LinkRule>> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .get_link_rules(Request::new(tools_link::proto::link::GetLinkRulesReq {
+                link_id: link_id.to_string(),
+                created_by_id: account_id,
+            }))
+            .await?;
+
+        Ok(res
+            .get_ref()
+            .rules
+            .iter()
+            .map(LinkRule::from_grpc)
+            .collect::<Result<Vec<LinkRule>, uuid::Error>>()?)
+    }
+
+    /// The moderation queue of reported links, the oldest report first.
+    async fn link_reports<'a>(
+        &self,
+        ctx: &Context<'a>,
+        status: Option<LinkReportStatus>,
+    ) -> Result<Vec<LinkReport>> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        if !helper::is_admin(db_conn, token, grpc_connect_timeout).await? {
+            return Err("Forbidden".into());
+        }
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .get_link_reports(Request::new(tools_link::proto::link::GetLinkReportsReq {
+                status: status.map(LinkReportStatus::to_grpc_enum),
+            }))
+            .await?;
+
+        Ok(res
+            .get_ref()
+            .reports
+            .iter()
+            .map(LinkReport::from_grpc)
+            .collect::<Result<Vec<LinkReport>, uuid::Error>>()?)
+    }
 }
 
 #[derive(Default)]
@@ -154,12 +444,24 @@ pub struct LinkMutation;
 
 #[Object]
 impl LinkMutation {
+    #[allow(clippy::too_many_arguments)]
     async fn create_link<'a>(
         &self,
         ctx: &Context<'a>,
         title: String,
         short_url: String,
         long_url: String,
+        folder_id: Option<Uuid>,
+        utm: Option<LinkUtmInput>,
+        forward_query: Option<bool>,
+        interstitial: Option<bool>,
+        og_metadata_override: Option<LinkOgMetadataInput>,
+        redirect_status: Option<LinkRedirectStatus>,
+        referrer_policy: Option<String>,
+        no_index: Option<bool>,
+        expires_at: Option<String>,
+        deep_link: Option<LinkDeepLinkInput>,
+        workspace_id: Option<Uuid>,
     ) -> Result<Link> {
         let db_conn = &mut tools_lib_db::pg::connection::get_connection(
             ctx.data_unchecked::<AppMode>().as_str(),
@@ -184,20 +486,26 @@ impl LinkMutation {
                 short_url,
                 long_url,
                 created_by_id: account_id,
+                folder_id: folder_id.map(|folder_id| folder_id.to_string()),
+                utm: utm.map(LinkUtmInput::into_grpc),
+                forward_query: forward_query.unwrap_or(false),
+                interstitial: interstitial.unwrap_or(false),
+                og_metadata_override: og_metadata_override.map(LinkOgMetadataInput::into_grpc),
+                redirect_status: redirect_status
+                    .unwrap_or(LinkRedirectStatus::TemporaryRedirect)
+                    .to_grpc_enum(),
+                referrer_policy,
+                no_index: no_index.unwrap_or(false),
+                expires_at,
+                deep_link: deep_link.map(LinkDeepLinkInput::into_grpc),
+                workspace_id: workspace_id.map(|workspace_id| workspace_id.to_string()),
             }))
             .await?;
 
-        Ok(Link {
-            id: Uuid::from_str(&res.get_ref().id)?,
-            title: res.get_ref().title.to_owned(),
-            short_url: res.get_ref().short_url.to_owned(),
-            long_url: res.get_ref().long_url.to_owned(),
-            visits: res.get_ref().visits,
-            created_at: res.get_ref().created_at.to_owned(),
-            updated_at: res.get_ref().updated_at.to_owned(),
-        })
+        Ok(Link::from_grpc(res.get_ref())?)
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn update_link<'a>(
         &self,
         ctx: &Context<'a>,
@@ -205,6 +513,19 @@ impl LinkMutation {
         title: Option<String>,
         short_url: Option<String>,
         long_url: Option<String>,
+        utm: Option<LinkUtmInput>,
+        forward_query: Option<bool>,
+        interstitial: Option<bool>,
+        og_metadata_override: Option<LinkOgMetadataInput>,
+        redirect_status: Option<LinkRedirectStatus>,
+        #[graphql(desc = "An empty referrer policy removes the policy of the link.")]
+        referrer_policy: Option<String>,
+        no_index: Option<bool>,
+        #[graphql(desc = "An empty expiry makes the link never expire.")] expires_at: Option<
+            String,
+        >,
+        #[graphql(desc = "Replaces all deep link settings of the link when set.")]
+        deep_link: Option<LinkDeepLinkInput>,
     ) -> Result<Link> {
         let db_conn = &mut tools_lib_db::pg::connection::get_connection(
             ctx.data_unchecked::<AppMode>().as_str(),
@@ -230,20 +551,22 @@ impl LinkMutation {
                 short_url,
                 long_url,
                 created_by_id: account_id,
+                utm: utm.map(LinkUtmInput::into_grpc),
+                forward_query,
+                interstitial,
+                og_metadata_override: og_metadata_override.map(LinkOgMetadataInput::into_grpc),
+                redirect_status: redirect_status.map(LinkRedirectStatus::to_grpc_enum),
+                referrer_policy,
+                no_index,
+                expires_at,
+                deep_link: deep_link.map(LinkDeepLinkInput::into_grpc),
             }))
             .await?;
 
-        Ok(Link {
-            id: Uuid::from_str(&res.get_ref().id)?,
-            title: res.get_ref().title.to_owned(),
-            short_url: res.get_ref().short_url.to_owned(),
-            long_url: res.get_ref().long_url.to_owned(),
-            visits: res.get_ref().visits,
-            created_at: res.get_ref().created_at.to_owned(),
-            updated_at: res.get_ref().updated_at.to_owned(),
-        })
+        Ok(Link::from_grpc(res.get_ref())?)
     }
 
+    /// Moves the link to the trash, it is purged after the retention period.
     async fn delete_link<'a>(&self, ctx: &Context<'a>, id: Uuid) -> Result<OpRes> {
         let db_conn = &mut tools_lib_db::pg::connection::get_connection(
             ctx.data_unchecked::<AppMode>().as_str(),
@@ -273,4 +596,979 @@ impl LinkMutation {
             is_success: res.get_ref().is_success,
         })
     }
+
+    async fn restore_link<'a>(&self, ctx: &Context<'a>, id: Uuid) -> Result<Link> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .restore_link(Request::new(tools_link::proto::link::RestoreLinkReq {
+                id: id.to_string(),
+                created_by_id: account_id,
+            }))
+            .await?;
+
+        Ok(Link::from_grpc(res.get_ref())?)
+    }
+
+    /// Deletes a link of the trash for good.
+    async fn purge_link<'a>(&self, ctx: &Context<'a>, id: Uuid) -> Result<OpRes> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .purge_link(Request::new(tools_link::proto::link::PurgeLinkReq {
+                id: id.to_string(),
+                created_by_id: account_id,
+            }))
+            .await?;
+
+        Ok(OpRes {
+            is_success: res.get_ref().is_success,
+        })
+    }
+
+    async fn import_links<'a>(
+        &self,
+        ctx: &Context<'a>,
+        file: Upload,
+        format: LinkFileFormat,
+        conflict_strategy: Option<ImportConflictStrategy>,
+        dry_run: Option<bool>,
+        workspace_id: Option<Uuid>,
+    ) -> Result<ImportLinksRes> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut content = Vec::new();
+        file.value(ctx)?.into_read().read_to_end(&mut content)?;
+
+        // Split the file into chunks, only the first message carries the options
+        let conflict_strategy = conflict_strategy
+            .unwrap_or(ImportConflictStrategy::Skip)
+            .to_grpc_enum();
+        let dry_run = dry_run.unwrap_or(false);
+        let mut reqs = content
+            .chunks(IMPORT_CHUNK_SIZE)
+            .map(|chunk| tools_link::proto::link::ImportLinksReq {
+                chunk: chunk.to_vec(),
+                ..Default::default()
+            })
+            .collect::<Vec<tools_link::proto::link::ImportLinksReq>>();
+        if reqs.is_empty() {
+            reqs.push(Default::default());
+        }
+        reqs[0].created_by_id = account_id;
+        reqs[0].format = format.to_grpc_enum();
+        reqs[0].conflict_strategy = conflict_strategy;
+        reqs[0].dry_run = dry_run;
+        reqs[0].workspace_id = workspace_id.map(|workspace_id| workspace_id.to_string());
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .import_links(Request::new(tokio_stream::iter(reqs)))
+            .await?;
+
+        Ok(ImportLinksRes {
+            dry_run: res.get_ref().dry_run,
+            total: res.get_ref().total,
+            imported: res.get_ref().imported,
+            skipped: res.get_ref().skipped,
+            failed: res.get_ref().failed,
+            rows: res
+                .get_ref()
+                .rows
+                .iter()
+                .map(|row| ImportLinksRowRes {
+                    row: row.row,
+                    short_url: row.short_url.to_owned(),
+                    status: ImportRowStatus::from_grpc_enum(row.status),
+                    message: row.message.to_owned(),
+                })
+                .collect(),
+        })
+    }
+
+    async fn move_links<'a>(
+        &self,
+        ctx: &Context<'a>,
+        ids: Vec<Uuid>,
+        folder_id: Option<Uuid>,
+    ) -> Result<OpRes> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .move_links(Request::new(tools_link::proto::link::MoveLinksReq {
+                ids: ids.iter().map(|id| id.to_string()).collect(),
+                folder_id: folder_id.map(|folder_id| folder_id.to_string()),
+                created_by_id: account_id,
+            }))
+            .await?;
+
+        Ok(OpRes {
+            is_success: res.get_ref().is_success,
+        })
+    }
+
+    async fn tag_links<'a>(
+        &self,
+        ctx: &Context<'a>,
+        ids: Vec<Uuid>,
+        tag_ids: Vec<Uuid>,
+    ) -> Result<OpRes> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .tag_links(Request::new(tools_link::proto::link::TagLinksReq {
+                ids: ids.iter().map(|id| id.to_string()).collect(),
+                tag_ids: tag_ids.iter().map(|id| id.to_string()).collect(),
+                created_by_id: account_id,
+            }))
+            .await?;
+
+        Ok(OpRes {
+            is_success: res.get_ref().is_success,
+        })
+    }
+
+    async fn untag_links<'a>(
+        &self,
+        ctx: &Context<'a>,
+        ids: Vec<Uuid>,
+        tag_ids: Vec<Uuid>,
+    ) -> Result<OpRes> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .untag_links(Request::new(tools_link::proto::link::TagLinksReq {
+                ids: ids.iter().map(|id| id.to_string()).collect(),
+                tag_ids: tag_ids.iter().map(|id| id.to_string()).collect(),
+                created_by_id: account_id,
+            }))
+            .await?;
+
+        Ok(OpRes {
+            is_success: res.get_ref().is_success,
+        })
+    }
+
+    /// Moves links to another account or to a workspace, set exactly one of them. The links
+    /// lose their folder and tags since those belong to the old owner.
+    async fn transfer_links<'a>(
+        &self,
+        ctx: &Context<'a>,
+        ids: Vec<Uuid>,
+        to_account_id: Option<Uuid>,
+        to_workspace_id: Option<Uuid>,
+    ) -> Result<OpRes> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .transfer_links(Request::new(tools_link::proto::link::TransferLinksReq {
+                ids: ids.iter().map(|id| id.to_string()).collect(),
+                created_by_id: account_id,
+                to_account_id: to_account_id.map(|to_account_id| to_account_id.to_string()),
+                to_workspace_id: to_workspace_id.map(|to_workspace_id| to_workspace_id.to_string()),
+            }))
+            .await?;
+
+        Ok(OpRes {
+            is_success: res.get_ref().is_success,
+        })
+    }
+
+    /// Puts back the title, the short url and the long url of a revision of the link.
+    async fn revert_link<'a>(
+        &self,
+        ctx: &Context<'a>,
+        id: Uuid,
+        revision_id: Uuid,
+    ) -> Result<Link> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .revert_link(Request::new(tools_link::proto::link::RevertLinkReq {
+                id: id.to_string(),
+                revision_id: revision_id.to_string(),
+                created_by_id: account_id,
+            }))
+            .await?;
+
+        Ok(Link::from_grpc(res.get_ref())?)
+    }
+
+    async fn create_link_tag<'a>(
+        &self,
+        ctx: &Context<'a>,
+        name: String,
+        workspace_id: Option<Uuid>,
+    ) -> Result<LinkTag> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .create_link_tag(Request::new(tools_link::proto::link::CreateLinkTagReq {
+                name,
+                created_by_id: account_id,
+                workspace_id: workspace_id.map(|workspace_id| workspace_id.to_string()),
+            }))
+            .await?;
+
+        Ok(LinkTag::from_grpc(res.get_ref())?)
+    }
+
+    async fn update_link_tag<'a>(
+        &self,
+        ctx: &Context<'a>,
+        id: Uuid,
+        name: String,
+    ) -> Result<LinkTag> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .update_link_tag(Request::new(tools_link::proto::link::UpdateLinkTagReq {
+                id: id.to_string(),
+                name,
+                created_by_id: account_id,
+            }))
+            .await?;
+
+        Ok(LinkTag::from_grpc(res.get_ref())?)
+    }
+
+    async fn delete_link_tag<'a>(&self, ctx: &Context<'a>, id: Uuid) -> Result<OpRes> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .delete_link_tag(Request::new(tools_link::proto::link::DeleteLinkTagReq {
+                id: id.to_string(),
+                created_by_id: account_id,
+            }))
+            .await?;
+
+        Ok(OpRes {
+            is_success: res.get_ref().is_success,
+        })
+    }
+
+    async fn create_link_folder<'a>(
+        &self,
+        ctx: &Context<'a>,
+        name: String,
+        parent_id: Option<Uuid>,
+        workspace_id: Option<Uuid>,
+    ) -> Result<LinkFolder> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .create_link_folder(Request::new(tools_link::proto::link::CreateLinkFolderReq {
+                name,
+                parent_id: parent_id.map(|parent_id| parent_id.to_string()),
+                created_by_id: account_id,
+                workspace_id: workspace_id.map(|workspace_id| workspace_id.to_string()),
+            }))
+            .await?;
+
+        Ok(LinkFolder::from_grpc(res.get_ref())?)
+    }
+
+    /// Set `parentId` to null to move the folder to the root.
+    async fn update_link_folder<'a>(
+        &self,
+        ctx: &Context<'a>,
+        id: Uuid,
+        name: Option<String>,
+        parent_id: MaybeUndefined<Uuid>,
+    ) -> Result<LinkFolder> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .update_link_folder(Request::new(tools_link::proto::link::UpdateLinkFolderReq {
+                id: id.to_string(),
+                name,
+                parent_id: match parent_id {
+                    MaybeUndefined::Undefined => None,
+                    MaybeUndefined::Null => Some(String::new()),
+                    MaybeUndefined::Value(parent_id) => Some(parent_id.to_string()),
+                },
+                created_by_id: account_id,
+            }))
+            .await?;
+
+        Ok(LinkFolder::from_grpc(res.get_ref())?)
+    }
+
+    async fn delete_link_folder<'a>(&self, ctx: &Context<'a>, id: Uuid) -> Result<OpRes> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .delete_link_folder(Request::new(tools_link::proto::link::DeleteLinkFolderReq {
+                id: id.to_string(),
+                created_by_id: account_id,
+            }))
+            .await?;
+
+        Ok(OpRes {
+            is_success: res.get_ref().is_success,
+        })
+    }
+    /// Adds a rule after the other rules of the link.
+    async fn create_link_rule<'a>(
+        &self,
+        ctx: &Context<'a>,
+        link_id: Uuid,
+        rule: LinkRuleInput,
+    ) -> Result<LinkRule> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .create_link_rule(Request::new(tools_link::proto::link::CreateLinkRuleReq {
+                link_id: link_id.to_string(),
+                platform: rule.platform.map(LinkRulePlatform::to_grpc_enum),
+                country: rule.country,
+                language: rule.language,
+                starts_at: rule.starts_at,
+                ends_at: rule.ends_at,
+                destination_url: rule.destination_url,
+                created_by_id: account_id,
+            }))
+            .await?;
+
+        Ok(LinkRule::from_grpc(res.get_ref())?)
+    }
+
+    /// Replaces the conditions and the destination of a rule.
+    async fn update_link_rule<'a>(
+        &self,
+        ctx: &Context<'a>,
+        id: Uuid,
+        rule: LinkRuleInput,
+    ) -> Result<LinkRule> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .update_link_rule(Request::new(tools_link::proto::link::UpdateLinkRuleReq {
+                id: id.to_string(),
+                platform: rule.platform.map(LinkRulePlatform::to_grpc_enum),
+                country: rule.country,
+                language: rule.language,
+                starts_at: rule.starts_at,
+                ends_at: rule.ends_at,
+                destination_url: rule.destination_url,
+                created_by_id: account_id,
+            }))
+            .await?;
+
+        Ok(LinkRule::from_grpc(res.get_ref())?)
+    }
+
+    async fn delete_link_rule<'a>(&self, ctx: &Context<'a>, id: Uuid) -> Result<OpRes> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .delete_link_rule(Request::new(tools_link::proto::link::DeleteLinkRuleReq {
+                id: id.to_string(),
+                created_by_id: account_id,
+            }))
+            .await?;
+
+        Ok(OpRes {
+            is_success: res.get_ref().is_success,
+        })
+    }
+
+    /// `ids` must contain every rule of the link, in the new order.
+    async fn reorder_link_rules<'a>(
+        &self,
+        ctx: &Context<'a>,
+        link_id: Uuid,
+        ids: Vec<Uuid>,
+    ) -> Result<Vec<LinkRule>> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .reorder_link_rules(Request::new(tools_link::proto::link::ReorderLinkRulesReq {
+                link_id: link_id.to_string(),
+                ids: ids.iter().map(|id| id.to_string()).collect(),
+                created_by_id: account_id,
+            }))
+            .await?;
+
+        Ok(res
+            .get_ref()
+            .rules
+            .iter()
+            .map(LinkRule::from_grpc)
+            .collect::<Result<Vec<LinkRule>, uuid::Error>>()?)
+    }
+
+    async fn create_link_variant<'a>(
+        &self,
+        ctx: &Context<'a>,
+        link_id: Uuid,
+        destination_url: String,
+        weight: i32,
+    ) -> Result<LinkVariant> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .create_link_variant(Request::new(
+                tools_link::proto::link::CreateLinkVariantReq {
+                    link_id: link_id.to_string(),
+                    destination_url,
+                    weight,
+                    created_by_id: account_id,
+                },
+            ))
+            .await?;
+
+        Ok(LinkVariant::from_grpc(res.get_ref())?)
+    }
+
+    async fn update_link_variant<'a>(
+        &self,
+        ctx: &Context<'a>,
+        id: Uuid,
+        destination_url: Option<String>,
+        weight: Option<i32>,
+    ) -> Result<LinkVariant> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .update_link_variant(Request::new(
+                tools_link::proto::link::UpdateLinkVariantReq {
+                    id: id.to_string(),
+                    destination_url,
+                    weight,
+                    created_by_id: account_id,
+                },
+            ))
+            .await?;
+
+        Ok(LinkVariant::from_grpc(res.get_ref())?)
+    }
+
+    async fn delete_link_variant<'a>(&self, ctx: &Context<'a>, id: Uuid) -> Result<OpRes> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .delete_link_variant(Request::new(
+                tools_link::proto::link::DeleteLinkVariantReq {
+                    id: id.to_string(),
+                    created_by_id: account_id,
+                },
+            ))
+            .await?;
+
+        Ok(OpRes {
+            is_success: res.get_ref().is_success,
+        })
+    }
+
+    /// Reports an abusive link to the moderators, anyone can report a link. The reporter is
+    /// told apart by the IP address of the request.
+    async fn report_link<'a>(
+        &self,
+        ctx: &Context<'a>,
+        short_url: String,
+        reason: LinkReportReason,
+        details: Option<String>,
+    ) -> Result<OpRes> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .report_link(Request::new(tools_link::proto::link::ReportLinkReq {
+                short_url,
+                reason: reason.to_grpc_enum(),
+                details,
+                reporter_ip: ctx
+                    .data_opt::<ClientIp>()
+                    .map(|client_ip| client_ip.0.to_owned()),
+            }))
+            .await
+            .map_err(link_unavailable_error)?;
+
+        Ok(OpRes {
+            is_success: res.get_ref().is_success,
+        })
+    }
+
+    async fn dismiss_link_report<'a>(&self, ctx: &Context<'a>, id: Uuid) -> Result<LinkReport> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        if !helper::is_admin(db_conn, token.to_owned(), grpc_connect_timeout).await? {
+            return Err("Forbidden".into());
+        }
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .dismiss_link_report(Request::new(
+                tools_link::proto::link::DismissLinkReportReq {
+                    id: id.to_string(),
+                    resolved_by_id: account_id,
+                },
+            ))
+            .await?;
+
+        Ok(LinkReport::from_grpc(res.get_ref())?)
+    }
+
+    /// Takes a link down, its pending reports are marked as actioned.
+    async fn disable_link<'a>(&self, ctx: &Context<'a>, id: Uuid, reason: String) -> Result<Link> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        if !helper::is_admin(db_conn, token.to_owned(), grpc_connect_timeout).await? {
+            return Err("Forbidden".into());
+        }
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .disable_link(Request::new(tools_link::proto::link::DisableLinkReq {
+                id: id.to_string(),
+                reason,
+                disabled_by_id: account_id,
+            }))
+            .await?;
+
+        Ok(Link::from_grpc(res.get_ref())?)
+    }
+
+    async fn enable_link<'a>(&self, ctx: &Context<'a>, id: Uuid) -> Result<Link> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        if !helper::is_admin(db_conn, token, grpc_connect_timeout).await? {
+            return Err("Forbidden".into());
+        }
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .enable_link(Request::new(tools_link::proto::link::EnableLinkReq {
+                id: id.to_string(),
+            }))
+            .await?;
+
+        Ok(Link::from_grpc(res.get_ref())?)
+    }
+}
+
+#[derive(Default)]
+pub struct LinkSubscription;
+
+#[Subscription]
+impl LinkSubscription {
+    /// The visits of a link as they are counted, for the accounts that can see the link.
+    async fn link_visited<'a>(
+        &self,
+        ctx: &Context<'a>,
+        link_id: Uuid,
+    ) -> Result<impl Stream<Item = LinkVisited>> {
+        let link_visits = ctx
+            .data_opt::<LinkVisits>()
+            .ok_or("Live visits need a message broker")?;
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        // The link service checks that the account can see the link
+        client
+            .get_link(Request::new(tools_link::proto::link::GetLinkReq {
+                id: link_id.to_string(),
+                created_by_id: account_id,
+            }))
+            .await?;
+
+        // The visits a slow subscriber skipped are left out
+        let link_id = link_id.to_string();
+        Ok(
+            BroadcastStream::new(link_visits.subscribe()).filter_map(move |visited| {
+                visited
+                    .ok()
+                    .filter(|visited| visited.link_id == link_id)
+                    .and_then(|visited| LinkVisited::from_contract(&visited).ok())
+            }),
+        )
+    }
+}
+
+/// Adds a code to the errors of a link that can't be visited, so that the link resolvers can
+/// tell them from a failure.
+fn link_unavailable_error(e: tonic::Status) -> Error {
+    let code = match e.code() {
+        tonic::Code::NotFound => "NOT_FOUND",
+        tonic::Code::FailedPrecondition => "EXPIRED",
+        tonic::Code::PermissionDenied => "DISABLED",
+        tonic::Code::ResourceExhausted => "RATE_LIMITED",
+        _ => return e.into(),
+    };
+
+    Error::new(e.message()).extend_with(|_, extensions| extensions.set("code", code))
 }
@@ -3,3 +3,4 @@ pub mod apprepo;
 pub mod link;
 pub mod service_address;
 pub mod service_info;
+pub mod workspace;
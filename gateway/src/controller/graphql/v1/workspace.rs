@@ -0,0 +1,279 @@
+use async_graphql::{Context, Object, Result};
+use tonic::Request;
+use tools_lib_db::pg::connection::DbPool;
+use tools_link::proto::link::LinkServiceClient;
+use uuid::Uuid;
+
+use crate::{
+    contract::graphql::{
+        op_res::OpRes,
+        workspace::{Workspace, WorkspaceMember, WorkspaceRole},
+    },
+    dto::{service_name::ServiceName, token::Token},
+    env::{AppMode, GrpcConnectTimeout},
+    helper::get_account_id,
+    service,
+};
+
+#[derive(Default)]
+pub struct WorkspaceQuery;
+
+#[Object]
+impl WorkspaceQuery {
+    /// The workspaces the signed-in account is a member of.
+    async fn workspaces<'a>(&self, ctx: &Context<'a>) -> Result<Vec<Workspace>> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .get_workspaces(Request::new(tools_link::proto::link::GetWorkspacesReq {
+                account_id,
+            }))
+            .await?;
+
+        Ok(res
+            .get_ref()
+            .workspaces
+            .iter()
+            .map(Workspace::from_grpc)
+            .collect::<Result<Vec<Workspace>, uuid::Error>>()?)
+    }
+
+    async fn workspace_members<'a>(
+        &self,
+        ctx: &Context<'a>,
+        workspace_id: Uuid,
+    ) -> Result<Vec<WorkspaceMember>> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .get_workspace_members(Request::new(
+                tools_link::proto::link::GetWorkspaceMembersReq {
+                    workspace_id: workspace_id.to_string(),
+                    account_id,
+                },
+            ))
+            .await?;
+
+        Ok(res
+            .get_ref()
+            .members
+            .iter()
+            .map(WorkspaceMember::from_grpc)
+            .collect::<Result<Vec<WorkspaceMember>, uuid::Error>>()?)
+    }
+}
+
+#[derive(Default)]
+pub struct WorkspaceMutation;
+
+#[Object]
+impl WorkspaceMutation {
+    /// Creates a workspace with the signed-in account as its owner.
+    async fn create_workspace<'a>(&self, ctx: &Context<'a>, name: String) -> Result<Workspace> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .create_workspace(Request::new(tools_link::proto::link::CreateWorkspaceReq {
+                name,
+                account_id,
+            }))
+            .await?;
+
+        Ok(Workspace::from_grpc(res.get_ref())?)
+    }
+
+    async fn update_workspace<'a>(
+        &self,
+        ctx: &Context<'a>,
+        id: Uuid,
+        name: String,
+    ) -> Result<Workspace> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .update_workspace(Request::new(tools_link::proto::link::UpdateWorkspaceReq {
+                id: id.to_string(),
+                name,
+                account_id,
+            }))
+            .await?;
+
+        Ok(Workspace::from_grpc(res.get_ref())?)
+    }
+
+    /// Adds an account to the workspace, only owners can invite.
+    async fn invite_workspace_member<'a>(
+        &self,
+        ctx: &Context<'a>,
+        workspace_id: Uuid,
+        member_id: Uuid,
+        role: WorkspaceRole,
+    ) -> Result<WorkspaceMember> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .invite_workspace_member(Request::new(
+                tools_link::proto::link::InviteWorkspaceMemberReq {
+                    workspace_id: workspace_id.to_string(),
+                    member_id: member_id.to_string(),
+                    role: role.to_grpc_enum(),
+                    account_id,
+                },
+            ))
+            .await?;
+
+        Ok(WorkspaceMember::from_grpc(res.get_ref())?)
+    }
+
+    async fn update_workspace_member<'a>(
+        &self,
+        ctx: &Context<'a>,
+        workspace_id: Uuid,
+        member_id: Uuid,
+        role: WorkspaceRole,
+    ) -> Result<WorkspaceMember> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .update_workspace_member(Request::new(
+                tools_link::proto::link::UpdateWorkspaceMemberReq {
+                    workspace_id: workspace_id.to_string(),
+                    member_id: member_id.to_string(),
+                    role: role.to_grpc_enum(),
+                    account_id,
+                },
+            ))
+            .await?;
+
+        Ok(WorkspaceMember::from_grpc(res.get_ref())?)
+    }
+
+    /// Owners can remove anyone, other members can only leave. The last owner can't leave.
+    async fn remove_workspace_member<'a>(
+        &self,
+        ctx: &Context<'a>,
+        workspace_id: Uuid,
+        member_id: Uuid,
+    ) -> Result<OpRes> {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(
+            ctx.data_unchecked::<AppMode>().as_str(),
+            ctx.data_unchecked::<DbPool>(),
+        )?;
+        let grpc_connect_timeout = ctx.data_unchecked::<GrpcConnectTimeout>();
+        let token = ctx
+            .data_opt::<Token>()
+            .ok_or("Token doesn't exist")?
+            .0
+            .to_owned();
+
+        let account_id = get_account_id(db_conn, token, grpc_connect_timeout).await?;
+
+        let mut client = LinkServiceClient::new(
+            service::grpc::client::get(db_conn, &ServiceName::link(), grpc_connect_timeout).await?,
+        );
+
+        let res = client
+            .remove_workspace_member(Request::new(
+                tools_link::proto::link::RemoveWorkspaceMemberReq {
+                    workspace_id: workspace_id.to_string(),
+                    member_id: member_id.to_string(),
+                    account_id,
+                },
+            ))
+            .await?;
+
+        Ok(OpRes {
+            is_success: res.get_ref().is_success,
+        })
+    }
+}
@@ -1,14 +1,21 @@
-use actix_web::{web, HttpResponse, Result};
+use actix_web::{guard, web, HttpRequest, HttpResponse, Result};
 use actix_web_httpauth::extractors::bearer::BearerAuth;
-use async_graphql::http::GraphiQLSource;
-use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse};
+use async_graphql::{
+    http::{GraphiQLSource, MultipartOptions},
+    Data,
+};
+use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse, GraphQLSubscription};
+
+use tools_lib_db::pg::connection::DbPool;
 
 use crate::{
-    dto::token::Token,
-    env::{AppMode, AppName, ServiceName},
+    dto::{client_ip::ClientIp, token::Token},
+    env::{AppMode, AppName, GrpcConnectTimeout, ServiceName, TrustedProxies},
     gql_schema::schema::GqlSchema,
+    helper,
 };
 
+pub mod apprepo;
 pub mod graphql;
 
 async fn root(
@@ -27,7 +34,9 @@ async fn root(
 
 async fn graphql_v1(
     schema: web::Data<GqlSchema>,
+    trusted_proxies: web::Data<TrustedProxies>,
     auth: Option<BearerAuth>,
+    http_req: HttpRequest,
     req: GraphQLRequest,
 ) -> GraphQLResponse {
     let mut req = req.into_inner();
@@ -35,14 +44,63 @@ async fn graphql_v1(
     if let Some(auth) = auth {
         req = req.data(Token(auth.token().to_owned()));
     }
+    if let Some(client_ip) = helper::get_client_ip(&http_req, &trusted_proxies) {
+        req = req.data(ClientIp(client_ip));
+    }
 
     schema.execute(req).await.into()
 }
 
+/// Serves the subscriptions over WebSocket, with the graphql-ws and graphql-transport-ws
+/// protocols.
+async fn graphql_v1_ws(
+    schema: web::Data<GqlSchema>,
+    trusted_proxies: web::Data<TrustedProxies>,
+    auth: Option<BearerAuth>,
+    req: HttpRequest,
+    payload: web::Payload,
+) -> Result<HttpResponse> {
+    let mut data = Data::default();
+
+    if let Some(auth) = auth {
+        data.insert(Token(auth.token().to_owned()));
+    }
+    if let Some(client_ip) = helper::get_client_ip(&req, &trusted_proxies) {
+        data.insert(ClientIp(client_ip));
+    }
+
+    GraphQLSubscription::new(GqlSchema::clone(&schema))
+        .with_data(data)
+        .on_connection_init(on_connection_init)
+        .start(&req, payload)
+}
+
+/// Browsers can't set the headers of a WebSocket, so the token can also be sent in the
+/// payload of the connection init message, as `{"Authorization": "Bearer <token>"}`.
+async fn on_connection_init(payload: serde_json::Value) -> async_graphql::Result<Data> {
+    let mut data = Data::default();
+
+    let token = payload
+        .get("Authorization")
+        .or_else(|| payload.get("authorization"))
+        .and_then(serde_json::Value::as_str)
+        .and_then(|authorization| authorization.strip_prefix("Bearer "));
+    if let Some(token) = token {
+        data.insert(Token(token.to_owned()));
+    }
+
+    Ok(data)
+}
+
 async fn graphiql_v1(app_mode: web::Data<AppMode>) -> Result<HttpResponse> {
     let app_mode = app_mode.as_str();
     if app_mode == "DEBUG" {
-        Ok(HttpResponse::Ok().body(GraphiQLSource::build().endpoint("/graphql/v1").finish()))
+        Ok(HttpResponse::Ok().body(
+            GraphiQLSource::build()
+                .endpoint("/graphql/v1")
+                .subscription_endpoint("/graphql/v1")
+                .finish(),
+        ))
     } else {
         Ok(HttpResponse::NotFound().finish())
     }
@@ -53,6 +111,10 @@ pub struct CtxData {
     pub app_mode: AppMode,
     pub service_name: ServiceName,
     pub gql_schema: GqlSchema,
+    pub upload_max_file_size: usize,
+    pub db_pool: DbPool,
+    pub grpc_connect_timeout: GrpcConnectTimeout,
+    pub trusted_proxies: TrustedProxies,
 }
 
 pub fn register(cfg: &mut web::ServiceConfig, data: CtxData) {
@@ -70,7 +132,37 @@ pub fn register(cfg: &mut web::ServiceConfig, data: CtxData) {
         web::scope("/graphql").service(
             web::scope("/v1")
                 .app_data(web::Data::new(data.gql_schema))
-                .route("", web::post().to(graphql_v1)),
+                .app_data(web::Data::new(data.trusted_proxies))
+                .app_data(MultipartOptions::default().max_file_size(data.upload_max_file_size))
+                .route("", web::post().to(graphql_v1))
+                .route(
+                    "",
+                    web::get()
+                        .guard(guard::Header("upgrade", "websocket"))
+                        .to(graphql_v1_ws),
+                ),
+        ),
+    );
+
+    // register /apprepo path, the apps check for updates, verify the releases and download
+    // them without a token
+    cfg.service(
+        web::scope("/apprepo").service(
+            web::scope("/v1")
+                .app_data(web::Data::new(data.app_mode.to_owned()))
+                .app_data(web::Data::new(data.db_pool))
+                .app_data(web::Data::new(data.grpc_connect_timeout))
+                .route("/update", web::get().to(apprepo::update))
+                .route("/keys", web::get().to(apprepo::manifest_keys))
+                .route("/downloads/{release_id}", web::get().to(apprepo::download))
+                .route(
+                    "/apps/{app_id}/releases/{version}/manifest",
+                    web::get().to(apprepo::manifest),
+                )
+                .route(
+                    "/apps/{app_id}/releases/{version}/manifest.sig",
+                    web::get().to(apprepo::manifest_signature),
+                ),
         ),
     );
 
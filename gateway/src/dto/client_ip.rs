@@ -0,0 +1 @@
+pub struct ClientIp(pub String);
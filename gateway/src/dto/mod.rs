@@ -1,2 +1,3 @@
+pub mod client_ip;
 pub mod service_name;
 pub mod token;
@@ -1,5 +1,5 @@
 use core::fmt;
-use std::env;
+use std::{env, net::IpAddr};
 
 pub struct Env;
 
@@ -27,6 +27,30 @@ impl Env {
     pub fn grpc_connect_timeout() -> String {
         env::var("GRPC_CONNECT_TIMEOUT").unwrap()
     }
+
+    pub fn upload_max_file_size() -> String {
+        env::var("UPLOAD_MAX_FILE_SIZE").unwrap()
+    }
+
+    pub fn trusted_proxies() -> TrustedProxies {
+        TrustedProxies::parse(&env::var("TRUSTED_PROXIES").unwrap_or_default())
+    }
+
+    pub fn use_msg_broker() -> UseMsgBroker {
+        UseMsgBroker(env::var("USE_MSG_BROKER").unwrap())
+    }
+
+    pub fn kafka_addrs() -> String {
+        env::var("KAFKA_ADDRS").unwrap()
+    }
+
+    pub fn kafka_group_id() -> String {
+        env::var("KAFKA_GROUP_ID").unwrap()
+    }
+
+    pub fn rabbitmq_addrs() -> String {
+        env::var("RABBITMQ_ADDRS").unwrap()
+    }
 }
 
 pub type GrpcConnectTimeout = u64;
@@ -86,3 +110,36 @@ impl AppMode {
         self.0 == "DEBUG"
     }
 }
+
+/// The IP addresses of the proxies, like the link resolver, whose forwarded headers tell the
+/// address of the client.
+#[derive(Clone, Default)]
+pub struct TrustedProxies(Vec<IpAddr>);
+
+impl TrustedProxies {
+    /// Reads a comma separated list of IP addresses, the ones that can't be read are left out.
+    pub fn parse(value: &str) -> Self {
+        TrustedProxies(
+            value
+                .split(',')
+                .filter_map(|addr| addr.trim().parse().ok())
+                .collect(),
+        )
+    }
+
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        self.0.contains(addr)
+    }
+}
+
+pub struct UseMsgBroker(String);
+
+impl UseMsgBroker {
+    pub fn is_kafka(&self) -> bool {
+        self.0 == "KAFKA"
+    }
+
+    pub fn is_rabbitmq(&self) -> bool {
+        self.0 == "RABBITMQ"
+    }
+}
@@ -1,15 +1,17 @@
-use async_graphql::{EmptySubscription, MergedObject, Schema};
+use async_graphql::{MergedObject, MergedSubscription, Schema};
 use tools_lib_db::pg::connection::DbPool;
 
 use crate::{
     controller::graphql::v1::{
         account::{AccountMutation, AccountQuery},
         apprepo::{ApprepoMutation, ApprepoQuery},
-        link::{LinkMutation, LinkQuery},
+        link::{LinkMutation, LinkQuery, LinkSubscription},
         service_address::{ServiceAddressMutation, ServiceAddressQuery},
         service_info::{ServiceInfoMutation, ServiceInfoQuery},
+        workspace::{WorkspaceMutation, WorkspaceQuery},
     },
     env::{AppMode, GrpcConnectTimeout},
+    service::link_visits::LinkVisits,
 };
 
 #[derive(MergedObject, Default)]
@@ -18,6 +20,7 @@ pub struct QueryRootV1(
     ServiceAddressQuery,
     AccountQuery,
     LinkQuery,
+    WorkspaceQuery,
     ApprepoQuery,
 );
 
@@ -27,28 +30,37 @@ pub struct MutationRootV1(
     ServiceAddressMutation,
     AccountMutation,
     LinkMutation,
+    WorkspaceMutation,
     ApprepoMutation,
 );
 
-pub type GqlSchema = Schema<QueryRootV1, MutationRootV1, EmptySubscription>;
+#[derive(MergedSubscription, Default)]
+pub struct SubscriptionRootV1(LinkSubscription);
+
+pub type GqlSchema = Schema<QueryRootV1, MutationRootV1, SubscriptionRootV1>;
 
 pub struct GqlData {
     pub app_mode: AppMode,
     pub db_pool: DbPool,
     pub grpc_connect_timeout: GrpcConnectTimeout,
+    pub link_visits: Option<LinkVisits>,
 }
 
 pub fn build_gql_schema(data: GqlData) -> GqlSchema {
     let mut schema = Schema::build(
         QueryRootV1::default(),
         MutationRootV1::default(),
-        EmptySubscription,
+        SubscriptionRootV1::default(),
     );
 
     if data.app_mode.is_release() {
         schema = schema.disable_introspection().disable_suggestions()
     }
 
+    if let Some(link_visits) = data.link_visits {
+        schema = schema.data(link_visits)
+    }
+
     schema
         .data(data.app_mode)
         .data(data.db_pool)
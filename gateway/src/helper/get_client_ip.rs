@@ -0,0 +1,74 @@
+use actix_web::HttpRequest;
+
+use crate::env::TrustedProxies;
+
+/// Gives the IP address of the client. The forwarded headers are only read when the request
+/// comes from a trusted proxy, anyone else could set them to any address.
+pub fn get_client_ip(req: &HttpRequest, trusted_proxies: &TrustedProxies) -> Option<String> {
+    let peer_addr = req.peer_addr()?.ip();
+    if !trusted_proxies.contains(&peer_addr) {
+        return Some(peer_addr.to_string());
+    }
+
+    req.connection_info()
+        .realip_remote_addr()
+        .map(str::to_owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+
+    use super::*;
+
+    fn trusted_proxies() -> TrustedProxies {
+        TrustedProxies::parse("10.0.0.2, ::1")
+    }
+
+    #[test]
+    fn ignores_the_forwarded_headers_of_an_untrusted_peer() {
+        let req = TestRequest::default()
+            .peer_addr("203.0.113.7:41000".parse().unwrap())
+            .insert_header(("x-forwarded-for", "198.51.100.1"))
+            .insert_header(("forwarded", "for=198.51.100.2"))
+            .to_http_request();
+
+        assert_eq!(
+            get_client_ip(&req, &trusted_proxies()).as_deref(),
+            Some("203.0.113.7")
+        );
+    }
+
+    #[test]
+    fn reads_the_forwarded_headers_of_a_trusted_proxy() {
+        let req = TestRequest::default()
+            .peer_addr("10.0.0.2:41000".parse().unwrap())
+            .insert_header(("x-forwarded-for", "198.51.100.1"))
+            .to_http_request();
+        assert_eq!(
+            get_client_ip(&req, &trusted_proxies()).as_deref(),
+            Some("198.51.100.1")
+        );
+
+        let req = TestRequest::default()
+            .peer_addr("[::1]:41000".parse().unwrap())
+            .to_http_request();
+        assert_eq!(
+            get_client_ip(&req, &trusted_proxies()).as_deref(),
+            Some("::1")
+        );
+    }
+
+    #[test]
+    fn trusts_no_proxy_by_default() {
+        let req = TestRequest::default()
+            .peer_addr("10.0.0.2:41000".parse().unwrap())
+            .insert_header(("x-forwarded-for", "198.51.100.1"))
+            .to_http_request();
+
+        assert_eq!(
+            get_client_ip(&req, &TrustedProxies::default()).as_deref(),
+            Some("10.0.0.2")
+        );
+    }
+}
@@ -1,5 +1,7 @@
 mod get_account_id;
+mod get_client_ip;
 mod is_admin;
 
 pub use get_account_id::get_account_id;
+pub use get_client_ip::get_client_ip;
 pub use is_admin::is_admin;
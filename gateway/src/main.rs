@@ -1,5 +1,6 @@
 use std::{
     fs,
+    future::Future,
     io::{Error, ErrorKind, Result},
 };
 
@@ -8,7 +9,10 @@ use actix_web::{middleware::Logger, web::ServiceConfig, App, HttpServer};
 use controller::register;
 use diesel_migrations::{embed_migrations, EmbeddedMigrations};
 
-use crate::controller::CtxData;
+use crate::{
+    controller::CtxData,
+    service::{kafka_consumer, link_visits::LinkVisits, rabbitmq_consumer},
+};
 
 mod contract;
 mod controller;
@@ -29,7 +33,10 @@ async fn main() -> Result<()> {
     let service_name = env::Env::service_name();
     let service_addrs = env::Env::service_addrs();
     let database_url = env::Env::database_url();
-    let grpc_connect_timeout = env::Env::grpc_connect_timeout();
+    let grpc_connect_timeout = env::Env::grpc_connect_timeout().parse().unwrap();
+    let upload_max_file_size = env::Env::upload_max_file_size().parse().unwrap();
+    let trusted_proxies = env::Env::trusted_proxies();
+    let use_msg_broker = env::Env::use_msg_broker();
 
     let db_pool = tools_lib_db::pg::connection::create_connection_pool(&database_url);
     let db_conn =
@@ -39,10 +46,45 @@ async fn main() -> Result<()> {
         return Err(Error::new(ErrorKind::Other, e));
     };
 
+    // The visits counted by the link service come through the message broker for the
+    // linkVisited subscription
+    let mut link_visits: Option<LinkVisits> = None;
+    if use_msg_broker.is_kafka() {
+        let visits = LinkVisits::default();
+        tokio::spawn(log_consumer_error(kafka_consumer::link_visited(
+            kafka_consumer::Config {
+                brokers: env::Env::kafka_addrs(),
+                group_id: env::Env::kafka_group_id(),
+            },
+            visits.to_owned(),
+        )));
+        link_visits = Some(visits);
+    } else if use_msg_broker.is_rabbitmq() {
+        let rabbitmq_connection = lapin::Connection::connect(
+            &env::Env::rabbitmq_addrs(),
+            lapin::ConnectionProperties::default()
+                .with_executor(tokio_executor_trait::Tokio::current())
+                .with_reactor(tokio_reactor_trait::Tokio),
+        )
+        .await
+        .map_err(|e| Error::new(ErrorKind::NotConnected, e))?;
+        let channel = rabbitmq_connection
+            .create_channel()
+            .await
+            .map_err(|e| Error::new(ErrorKind::NotConnected, e))?;
+        let visits = LinkVisits::default();
+        tokio::spawn(log_consumer_error(rabbitmq_consumer::link_visited(
+            channel,
+            visits.to_owned(),
+        )));
+        link_visits = Some(visits);
+    }
+
     let gql_schema = gql_schema::schema::build_gql_schema(gql_schema::schema::GqlData {
         app_mode: app_mode.to_owned(),
-        db_pool,
-        grpc_connect_timeout: grpc_connect_timeout.parse().unwrap(),
+        db_pool: db_pool.to_owned(),
+        grpc_connect_timeout,
+        link_visits,
     });
     if app_mode.is_debug() {
         fs::write(
@@ -71,6 +113,10 @@ async fn main() -> Result<()> {
                         app_mode: app_mode.to_owned(),
                         service_name: service_name.to_owned(),
                         gql_schema: gql_schema.to_owned(),
+                        upload_max_file_size,
+                        db_pool: db_pool.to_owned(),
+                        grpc_connect_timeout,
+                        trusted_proxies: trusted_proxies.to_owned(),
                     },
                 )
             })
@@ -79,3 +125,11 @@ async fn main() -> Result<()> {
     .run()
     .await
 }
+
+async fn log_consumer_error<E: std::fmt::Display>(
+    consumer: impl Future<Output = std::result::Result<(), E>>,
+) {
+    if let Err(e) = consumer.await {
+        eprintln!("Link visit consumer stopped: {e}");
+    }
+}
@@ -0,0 +1,46 @@
+use std::error::Error;
+
+use rdkafka::{
+    consumer::{Consumer, StreamConsumer},
+    ClientConfig, Message,
+};
+use tools_link::contract::LINK_VISITED_TOPIC;
+use uuid::Uuid;
+
+use crate::service::link_visits::LinkVisits;
+
+#[derive(Clone)]
+pub struct Config {
+    pub brokers: String,
+    pub group_id: String,
+}
+
+pub async fn link_visited(
+    config: Config,
+    link_visits: LinkVisits,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    // Every gateway has its own subscribers, so every one of them reads all the visits
+    let consumer: StreamConsumer = ClientConfig::new()
+        .set(
+            "group.id",
+            format!("{}-{}", config.group_id, Uuid::new_v4()),
+        )
+        .set("bootstrap.servers", &config.brokers)
+        .set("session.timeout.ms", "6000")
+        .set("enable.auto.commit", "false")
+        .set("auto.offset.reset", "latest")
+        .create()?;
+
+    consumer.subscribe(&[LINK_VISITED_TOPIC])?;
+
+    loop {
+        match consumer.recv().await {
+            Ok(message) => {
+                if let Some(payload) = message.payload() {
+                    link_visits.send(payload);
+                }
+            }
+            Err(e) => eprintln!("Kafka receive stream error: {e}"),
+        }
+    }
+}
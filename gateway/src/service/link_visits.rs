@@ -0,0 +1,34 @@
+use tokio::sync::broadcast;
+use tools_link::contract::LinkVisited;
+
+/// The visits a slow subscriber can fall behind by before it skips the oldest ones.
+const CAPACITY: usize = 1024;
+
+/// Hands the visits counted by the link service to the `linkVisited` subscriptions of this
+/// gateway.
+#[derive(Clone)]
+pub struct LinkVisits(broadcast::Sender<LinkVisited>);
+
+impl Default for LinkVisits {
+    fn default() -> Self {
+        LinkVisits(broadcast::channel(CAPACITY).0)
+    }
+}
+
+impl LinkVisits {
+    pub fn subscribe(&self) -> broadcast::Receiver<LinkVisited> {
+        self.0.subscribe()
+    }
+
+    pub fn send(&self, payload: &[u8]) {
+        match serde_json::from_slice::<Vec<LinkVisited>>(payload) {
+            Ok(visits) => {
+                // Sending only fails when nobody is subscribed
+                for visit in visits {
+                    let _ = self.0.send(visit);
+                }
+            }
+            Err(e) => eprintln!("Failed to read link visits: {e}"),
+        }
+    }
+}
@@ -1 +1,4 @@
 pub mod grpc;
+pub mod kafka_consumer;
+pub mod link_visits;
+pub mod rabbitmq_consumer;
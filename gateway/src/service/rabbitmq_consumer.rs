@@ -0,0 +1,64 @@
+use lapin::{
+    options::{BasicConsumeOptions, ExchangeDeclareOptions, QueueBindOptions, QueueDeclareOptions},
+    types::FieldTable,
+    Channel, ExchangeKind, Result,
+};
+use tokio_stream::StreamExt;
+use tools_link::contract::LINK_VISITED_TOPIC;
+
+use crate::service::link_visits::LinkVisits;
+
+pub async fn link_visited(channel: Channel, link_visits: LinkVisits) -> Result<()> {
+    channel
+        .exchange_declare(
+            LINK_VISITED_TOPIC,
+            ExchangeKind::Fanout,
+            ExchangeDeclareOptions::default(),
+            FieldTable::default(),
+        )
+        .await?;
+
+    // Every gateway has its own subscribers, so every one of them gets a queue bound to the
+    // exchange of the visits
+    let queue = channel
+        .queue_declare(
+            "",
+            QueueDeclareOptions {
+                exclusive: true,
+                auto_delete: true,
+                ..QueueDeclareOptions::default()
+            },
+            FieldTable::default(),
+        )
+        .await?;
+    channel
+        .queue_bind(
+            queue.name().as_str(),
+            LINK_VISITED_TOPIC,
+            "",
+            QueueBindOptions::default(),
+            FieldTable::default(),
+        )
+        .await?;
+
+    let consumer = &mut channel
+        .basic_consume(
+            queue.name().as_str(),
+            "consumer_tag",
+            BasicConsumeOptions {
+                no_ack: true,
+                ..BasicConsumeOptions::default()
+            },
+            FieldTable::default(),
+        )
+        .await?;
+
+    while let Some(delivery) = consumer.next().await {
+        match delivery {
+            Ok(delivery) => link_visits.send(&delivery.data),
+            Err(e) => eprintln!("Failed to consume queue message: {e}"),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,10 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE "link"
+    DROP CONSTRAINT IF EXISTS "fk_link_folder",
+    DROP COLUMN IF EXISTS "folder_id";
+
+DROP TABLE IF EXISTS "link_tag_map";
+
+DROP TABLE IF EXISTS "link_tag";
+
+DROP TABLE IF EXISTS "link_folder";
@@ -0,0 +1,34 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS "link_folder" (
+    "id" uuid DEFAULT gen_random_uuid (),
+    "name" TEXT NOT NULL,
+    "parent_id" uuid,
+    "created_by_id" uuid NOT NULL,
+    "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "updated_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY ("id"),
+    CONSTRAINT "fk_link_folder" FOREIGN KEY ("parent_id") REFERENCES "link_folder" ("id") ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS "link_tag" (
+    "id" uuid DEFAULT gen_random_uuid (),
+    "name" TEXT NOT NULL,
+    "created_by_id" uuid NOT NULL,
+    "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "updated_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY ("id"),
+    UNIQUE ("created_by_id", "name")
+);
+
+CREATE TABLE IF NOT EXISTS "link_tag_map" (
+    "link_id" uuid NOT NULL,
+    "tag_id" uuid NOT NULL,
+    "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY ("link_id", "tag_id"),
+    CONSTRAINT "fk_link" FOREIGN KEY ("link_id") REFERENCES "link" ("id") ON DELETE CASCADE,
+    CONSTRAINT "fk_link_tag" FOREIGN KEY ("tag_id") REFERENCES "link_tag" ("id") ON DELETE CASCADE
+);
+
+ALTER TABLE "link"
+    ADD COLUMN IF NOT EXISTS "folder_id" uuid,
+    ADD CONSTRAINT "fk_link_folder" FOREIGN KEY ("folder_id") REFERENCES "link_folder" ("id") ON DELETE SET NULL;
@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE "link"
+    DROP COLUMN IF EXISTS "utm_source",
+    DROP COLUMN IF EXISTS "utm_medium",
+    DROP COLUMN IF EXISTS "utm_campaign",
+    DROP COLUMN IF EXISTS "utm_term",
+    DROP COLUMN IF EXISTS "utm_content",
+    DROP COLUMN IF EXISTS "forward_query";
@@ -0,0 +1,8 @@
+-- Your SQL goes here
+ALTER TABLE "link"
+    ADD COLUMN IF NOT EXISTS "utm_source" TEXT,
+    ADD COLUMN IF NOT EXISTS "utm_medium" TEXT,
+    ADD COLUMN IF NOT EXISTS "utm_campaign" TEXT,
+    ADD COLUMN IF NOT EXISTS "utm_term" TEXT,
+    ADD COLUMN IF NOT EXISTS "utm_content" TEXT,
+    ADD COLUMN IF NOT EXISTS "forward_query" BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS "link_rule";
+
+DROP TYPE IF EXISTS "link_rule_platform";
@@ -0,0 +1,20 @@
+-- Your SQL goes here
+CREATE TYPE "link_rule_platform" AS ENUM ('ios', 'android', 'windows', 'macos', 'linux');
+
+CREATE TABLE IF NOT EXISTS "link_rule" (
+    "id" uuid DEFAULT gen_random_uuid (),
+    "link_id" uuid NOT NULL,
+    "position" INTEGER NOT NULL,
+    "platform" link_rule_platform,
+    "country" TEXT,
+    "language" TEXT,
+    "starts_at" TIMESTAMP,
+    "ends_at" TIMESTAMP,
+    "destination_url" TEXT NOT NULL,
+    "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "updated_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY ("id"),
+    CONSTRAINT "fk_link" FOREIGN KEY ("link_id") REFERENCES "link" ("id") ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS "idx_link_rule_link_id_position" ON "link_rule" ("link_id", "position");
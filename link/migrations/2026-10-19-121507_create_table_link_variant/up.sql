@@ -0,0 +1,14 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS "link_variant" (
+    "id" uuid DEFAULT gen_random_uuid (),
+    "link_id" uuid NOT NULL,
+    "destination_url" TEXT NOT NULL,
+    "weight" INTEGER NOT NULL CHECK ("weight" > 0),
+    "visits" INTEGER NOT NULL DEFAULT 0,
+    "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "updated_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY ("id"),
+    CONSTRAINT "fk_link" FOREIGN KEY ("link_id") REFERENCES "link" ("id") ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS "idx_link_variant_link_id" ON "link_variant" ("link_id");
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE "link"
+    ADD COLUMN IF NOT EXISTS "interstitial" BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE "link"
+    DROP COLUMN IF EXISTS "og_title",
+    DROP COLUMN IF EXISTS "og_description",
+    DROP COLUMN IF EXISTS "og_image",
+    DROP COLUMN IF EXISTS "og_fetched_at",
+    DROP COLUMN IF EXISTS "og_title_override",
+    DROP COLUMN IF EXISTS "og_description_override",
+    DROP COLUMN IF EXISTS "og_image_override";
@@ -0,0 +1,9 @@
+-- Your SQL goes here
+ALTER TABLE "link"
+    ADD COLUMN IF NOT EXISTS "og_title" TEXT,
+    ADD COLUMN IF NOT EXISTS "og_description" TEXT,
+    ADD COLUMN IF NOT EXISTS "og_image" TEXT,
+    ADD COLUMN IF NOT EXISTS "og_fetched_at" TIMESTAMP,
+    ADD COLUMN IF NOT EXISTS "og_title_override" TEXT,
+    ADD COLUMN IF NOT EXISTS "og_description_override" TEXT,
+    ADD COLUMN IF NOT EXISTS "og_image_override" TEXT;
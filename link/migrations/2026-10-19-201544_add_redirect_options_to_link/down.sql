@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE "link"
+    DROP COLUMN IF EXISTS "redirect_status",
+    DROP COLUMN IF EXISTS "referrer_policy",
+    DROP COLUMN IF EXISTS "no_index";
+
+DROP TYPE IF EXISTS "link_redirect_status";
@@ -0,0 +1,7 @@
+-- Your SQL goes here
+CREATE TYPE "link_redirect_status" AS ENUM ('temporary_redirect', 'permanent_redirect', 'found', 'moved_permanently');
+
+ALTER TABLE "link"
+    ADD COLUMN IF NOT EXISTS "redirect_status" link_redirect_status NOT NULL DEFAULT 'temporary_redirect',
+    ADD COLUMN IF NOT EXISTS "referrer_policy" TEXT,
+    ADD COLUMN IF NOT EXISTS "no_index" BOOLEAN NOT NULL DEFAULT FALSE;
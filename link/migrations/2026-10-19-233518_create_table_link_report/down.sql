@@ -0,0 +1,10 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE "link"
+    DROP COLUMN IF EXISTS "disabled_at",
+    DROP COLUMN IF EXISTS "disabled_reason",
+    DROP COLUMN IF EXISTS "disabled_by_id";
+
+DROP TABLE IF EXISTS "link_report";
+
+DROP TYPE IF EXISTS "link_report_status";
+DROP TYPE IF EXISTS "link_report_reason";
@@ -0,0 +1,26 @@
+-- Your SQL goes here
+CREATE TYPE "link_report_reason" AS ENUM ('spam', 'phishing', 'malware', 'illegal_content', 'other');
+CREATE TYPE "link_report_status" AS ENUM ('pending', 'dismissed', 'actioned');
+
+CREATE TABLE IF NOT EXISTS "link_report" (
+    "id" uuid DEFAULT gen_random_uuid (),
+    "link_id" uuid NOT NULL,
+    "reason" link_report_reason NOT NULL,
+    "details" TEXT,
+    "reporter_ip" TEXT,
+    "status" link_report_status NOT NULL DEFAULT 'pending',
+    "resolved_by_id" uuid,
+    "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "updated_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY ("id"),
+    CONSTRAINT "fk_link" FOREIGN KEY ("link_id") REFERENCES "link" ("id") ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS "idx_link_report_link_id" ON "link_report" ("link_id");
+CREATE INDEX IF NOT EXISTS "idx_link_report_status" ON "link_report" ("status", "created_at");
+CREATE INDEX IF NOT EXISTS "idx_link_report_reporter_ip" ON "link_report" ("reporter_ip", "created_at");
+
+ALTER TABLE "link"
+    ADD COLUMN IF NOT EXISTS "disabled_at" TIMESTAMP,
+    ADD COLUMN IF NOT EXISTS "disabled_reason" TEXT,
+    ADD COLUMN IF NOT EXISTS "disabled_by_id" uuid;
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE "link"
+    DROP COLUMN IF EXISTS "app_uri",
+    DROP COLUMN IF EXISTS "android_package",
+    DROP COLUMN IF EXISTS "ios_fallback_url";
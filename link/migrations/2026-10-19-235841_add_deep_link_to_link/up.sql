@@ -0,0 +1,5 @@
+-- Your SQL goes here
+ALTER TABLE "link"
+    ADD COLUMN IF NOT EXISTS "app_uri" TEXT,
+    ADD COLUMN IF NOT EXISTS "android_package" TEXT,
+    ADD COLUMN IF NOT EXISTS "ios_fallback_url" TEXT;
@@ -0,0 +1,14 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS "link_tag_workspace_id_name_key";
+DROP INDEX IF EXISTS "link_tag_created_by_id_name_key";
+DELETE FROM "link_tag" WHERE "workspace_id" IS NOT NULL;
+ALTER TABLE "link_tag" ADD CONSTRAINT "link_tag_created_by_id_name_key" UNIQUE ("created_by_id", "name");
+
+ALTER TABLE "link" DROP COLUMN IF EXISTS "workspace_id";
+ALTER TABLE "link_folder" DROP COLUMN IF EXISTS "workspace_id";
+ALTER TABLE "link_tag" DROP COLUMN IF EXISTS "workspace_id";
+
+DROP TABLE IF EXISTS "workspace_member";
+DROP TABLE IF EXISTS "workspace";
+
+DROP TYPE IF EXISTS "workspace_role";
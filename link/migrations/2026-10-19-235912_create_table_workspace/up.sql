@@ -0,0 +1,44 @@
+-- Your SQL goes here
+CREATE TYPE "workspace_role" AS ENUM ('owner', 'editor', 'viewer');
+
+CREATE TABLE IF NOT EXISTS "workspace" (
+    "id" uuid DEFAULT gen_random_uuid (),
+    "name" TEXT NOT NULL,
+    "created_by_id" uuid NOT NULL,
+    "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "updated_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY ("id")
+);
+
+CREATE TABLE IF NOT EXISTS "workspace_member" (
+    "workspace_id" uuid NOT NULL,
+    "account_id" uuid NOT NULL,
+    "role" workspace_role NOT NULL,
+    "invited_by_id" uuid,
+    "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "updated_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY ("workspace_id", "account_id"),
+    CONSTRAINT "fk_workspace" FOREIGN KEY ("workspace_id") REFERENCES "workspace" ("id") ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS "idx_workspace_member_account_id" ON "workspace_member" ("account_id");
+
+-- Links, tags and folders without a workspace belong to the account that created them. A
+-- workspace can't be deleted while any of them refer to it
+ALTER TABLE "link"
+    ADD COLUMN IF NOT EXISTS "workspace_id" uuid,
+    ADD CONSTRAINT "fk_workspace" FOREIGN KEY ("workspace_id") REFERENCES "workspace" ("id") ON DELETE RESTRICT;
+ALTER TABLE "link_folder"
+    ADD COLUMN IF NOT EXISTS "workspace_id" uuid,
+    ADD CONSTRAINT "fk_workspace" FOREIGN KEY ("workspace_id") REFERENCES "workspace" ("id") ON DELETE RESTRICT;
+ALTER TABLE "link_tag"
+    ADD COLUMN IF NOT EXISTS "workspace_id" uuid,
+    ADD CONSTRAINT "fk_workspace" FOREIGN KEY ("workspace_id") REFERENCES "workspace" ("id") ON DELETE RESTRICT;
+
+CREATE INDEX IF NOT EXISTS "idx_link_workspace_id" ON "link" ("workspace_id");
+CREATE INDEX IF NOT EXISTS "idx_link_folder_workspace_id" ON "link_folder" ("workspace_id");
+
+-- Tag names are unique within the personal tags of an account or within a workspace
+ALTER TABLE "link_tag" DROP CONSTRAINT IF EXISTS "link_tag_created_by_id_name_key";
+CREATE UNIQUE INDEX IF NOT EXISTS "link_tag_created_by_id_name_key" ON "link_tag" ("created_by_id", "name") WHERE "workspace_id" IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS "link_tag_workspace_id_name_key" ON "link_tag" ("workspace_id", "name") WHERE "workspace_id" IS NOT NULL;
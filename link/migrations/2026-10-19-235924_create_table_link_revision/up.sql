@@ -0,0 +1,18 @@
+-- Your SQL goes here
+CREATE TABLE IF NOT EXISTS "link_revision" (
+    "id" uuid DEFAULT gen_random_uuid (),
+    "link_id" uuid NOT NULL,
+    "title" TEXT NOT NULL,
+    "short_url" TEXT NOT NULL,
+    "long_url" TEXT NOT NULL,
+    "created_by_id" uuid NOT NULL,
+    "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY ("id"),
+    CONSTRAINT "fk_link" FOREIGN KEY ("link_id") REFERENCES "link" ("id") ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS "idx_link_revision_link_id" ON "link_revision" ("link_id", "created_at");
+
+-- Start the history of the existing links with their current state
+INSERT INTO "link_revision" ("link_id", "title", "short_url", "long_url", "created_by_id", "created_at")
+SELECT "id", "title", "short_url", "long_url", "created_by_id", "updated_at" FROM "link";
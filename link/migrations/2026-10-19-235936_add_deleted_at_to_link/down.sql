@@ -0,0 +1,10 @@
+-- This file should undo anything in `up.sql`
+DELETE FROM "link" WHERE "deleted_at" IS NOT NULL;
+
+DROP INDEX IF EXISTS "idx_link_deleted_at";
+DROP INDEX IF EXISTS "idx_link_short_url";
+ALTER TABLE "link" ADD CONSTRAINT "link_short_url_key" UNIQUE ("short_url");
+
+ALTER TABLE "link"
+    DROP COLUMN IF EXISTS "deleted_at",
+    DROP COLUMN IF EXISTS "deleted_by_id";
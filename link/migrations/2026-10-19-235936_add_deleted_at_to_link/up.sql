@@ -0,0 +1,9 @@
+-- Your SQL goes here
+ALTER TABLE "link"
+    ADD COLUMN IF NOT EXISTS "deleted_at" TIMESTAMP,
+    ADD COLUMN IF NOT EXISTS "deleted_by_id" uuid;
+
+-- Links in the trash don't hold on to their short url
+ALTER TABLE "link" DROP CONSTRAINT IF EXISTS "link_short_url_key";
+CREATE UNIQUE INDEX IF NOT EXISTS "idx_link_short_url" ON "link" ("short_url") WHERE "deleted_at" IS NULL;
+CREATE INDEX IF NOT EXISTS "idx_link_deleted_at" ON "link" ("deleted_at") WHERE "deleted_at" IS NOT NULL;
@@ -0,0 +1,11 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS "idx_link_health_next_check_at";
+
+ALTER TABLE "link"
+    DROP COLUMN IF EXISTS "health_status_code",
+    DROP COLUMN IF EXISTS "health_latency_ms",
+    DROP COLUMN IF EXISTS "health_error",
+    DROP COLUMN IF EXISTS "health_checked_at",
+    DROP COLUMN IF EXISTS "health_failures",
+    DROP COLUMN IF EXISTS "health_broken_at",
+    DROP COLUMN IF EXISTS "health_next_check_at";
@@ -0,0 +1,11 @@
+-- Your SQL goes here
+ALTER TABLE "link"
+    ADD COLUMN IF NOT EXISTS "health_status_code" INTEGER,
+    ADD COLUMN IF NOT EXISTS "health_latency_ms" INTEGER,
+    ADD COLUMN IF NOT EXISTS "health_error" TEXT,
+    ADD COLUMN IF NOT EXISTS "health_checked_at" TIMESTAMP,
+    ADD COLUMN IF NOT EXISTS "health_failures" INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS "health_broken_at" TIMESTAMP,
+    ADD COLUMN IF NOT EXISTS "health_next_check_at" TIMESTAMP;
+
+CREATE INDEX IF NOT EXISTS "idx_link_health_next_check_at" ON "link" ("health_next_check_at" NULLS FIRST);
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+/// The topic, or the RabbitMQ fanout exchange, of the link events.
+pub const LINK_EVENT_TOPIC: &str = "link_event";
+/// The topic, or the RabbitMQ queue, of the visits served by the link resolvers.
+pub const LINK_VISIT_TOPIC: &str = "link_visit";
+/// The topic, or the RabbitMQ fanout exchange, of the visits counted by the link service.
+pub const LINK_VISITED_TOPIC: &str = "link_visited";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkEventKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+/// Published when what a short url resolves to changes.
+#[derive(Serialize, Deserialize)]
+pub struct LinkEvent {
+    pub kind: LinkEventKind,
+    pub short_url: String,
+}
+
+/// Published by the link resolvers for the visits they served without the link service.
+#[derive(Serialize, Deserialize)]
+pub struct LinkVisit {
+    pub short_url: String,
+    pub count: i32,
+}
+
+/// Published by the link service once it has counted the visits of a link.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LinkVisited {
+    pub link_id: String,
+    pub short_url: String,
+    pub count: i32,
+    /// The visits of the link so far, with the counted ones.
+    pub visits: i32,
+}
@@ -1,10 +1,21 @@
 use std::str::FromStr;
 
-use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
-use tonic::{Request, Response, Result, Status};
+use chrono::{Duration, NaiveDateTime, Utc};
+use diesel::{
+    Connection, ExpressionMethods, OptionalExtension, PgConnection, QueryDsl, RunQueryDsl,
+};
+use tonic::{transport, Request, Response, Result, Status, Streaming};
+use tools_account::proto::account::{AccountServiceClient, GetAccountEmailsReq};
+use tools_link::contract::{LinkEvent, LinkEventKind, LinkVisited};
 use uuid::Uuid;
 
 use crate::{
+    dto::link_record::LinkRecord,
+    helper::{
+        link, link_destination, link_event, link_file, link_folder, link_import, link_og_metadata,
+        link_report, link_revision, link_rule, link_tag, link_validation, link_variant,
+        workspace::{self, Space},
+    },
     model,
     proto::{self, link::LinkService},
     schema,
@@ -12,6 +23,9 @@ use crate::{
 
 use super::LinkController;
 
+const IMPORT_MAX_SIZE: usize = 16 * 1024 * 1024;
+const IMPORT_BATCH_SIZE: usize = 100;
+
 #[tonic::async_trait]
 impl LinkService for LinkController {
     async fn get_links(
@@ -22,25 +36,71 @@ impl LinkService for LinkController {
             &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
                 .map_err(|e| Status::internal(e.to_string()))?;
 
-        // Get all links created by user id
+        // Check if the account can see the links of the space
         let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
-        let links = schema::link::table
-            .filter(schema::link::created_by_id.eq(&created_by_id))
-            .order(schema::link::created_at.desc())
+        let space = Space::parse(&created_by_id, &req.get_ref().workspace_id)
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        check_role(
+            db_conn,
+            Some(space),
+            &created_by_id,
+            model::WorkspaceRole::Viewer,
+            "The workspace is not found.",
+        )?;
+
+        // Get all links of the space, except the ones in the trash
+        let mut query = link::filter_by_space(
+            schema::link::table
+                .filter(schema::link::deleted_at.is_null())
+                .order(schema::link::created_at.desc())
+                .into_boxed(),
+            &space,
+        );
+
+        // Filter the links by tag
+        if let Some(tag_id) = &req.get_ref().tag_id {
+            let tag_id = Uuid::from_str(tag_id).map_err(|e| Status::aborted(e.to_string()))?;
+            query = query.filter(
+                schema::link::id.eq_any(
+                    schema::link_tag_map::table
+                        .filter(schema::link_tag_map::tag_id.eq(tag_id))
+                        .select(schema::link_tag_map::link_id),
+                ),
+            );
+        }
+
+        // Filter the links by folder
+        if let Some(folder_id) = &req.get_ref().folder_id {
+            let folder_id =
+                Uuid::from_str(folder_id).map_err(|e| Status::aborted(e.to_string()))?;
+            if req.get_ref().include_subfolders {
+                let folder_ids = link_folder::get_folder_tree_ids(db_conn, &folder_id, &space)
+                    .map_err(|e| Status::internal(e.to_string()))?;
+                query = query.filter(schema::link::folder_id.eq_any(folder_ids));
+            } else {
+                query = query.filter(schema::link::folder_id.eq(folder_id));
+            }
+        }
+
+        let links = query
             .load::<model::Link>(db_conn)
             .map_err(|e| Status::internal(e.to_string()))?;
 
+        // Get the tags and the variants of the links
+        let link_ids = links.iter().map(|link| link.id).collect::<Vec<Uuid>>();
+        let mut tags_by_link_id = link_tag::get_tags_by_link_ids(db_conn, &link_ids)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let mut variants_by_link_id = link_variant::get_variants_by_link_ids(db_conn, &link_ids)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
         Ok(Response::new(proto::link::Links {
             links: links
                 .iter()
-                .map(|link| proto::link::Link {
-                    id: link.id.to_string(),
-                    title: link.title.to_owned(),
-                    short_url: link.short_url.to_owned(),
-                    long_url: link.long_url.to_owned(),
-                    visits: link.visits,
-                    created_at: link.created_at.to_string(),
-                    updated_at: link.updated_at.to_string(),
+                .map(|link| {
+                    link.to_grpc(
+                        tags_by_link_id.remove(&link.id).unwrap_or_default(),
+                        variants_by_link_id.remove(&link.id).unwrap_or_default(),
+                    )
                 })
                 .collect(),
         }))
@@ -59,18 +119,37 @@ impl LinkService for LinkController {
             Uuid::from_str(&req.get_ref().id).map_err(|e| Status::aborted(e.to_string()))?;
         let link = schema::link::table
             .find(&link_id)
+            .filter(schema::link::deleted_at.is_null())
             .first::<model::Link>(db_conn)
+            .optional()
             .map_err(|e| Status::internal(e.to_string()))?;
 
-        Ok(Response::new(proto::link::Link {
-            id: link.id.to_string(),
-            title: link.title.to_owned(),
-            short_url: link.short_url.to_owned(),
-            long_url: link.long_url.to_owned(),
-            visits: link.visits,
-            created_at: link.created_at.to_string(),
-            updated_at: link.updated_at.to_string(),
-        }))
+        // Check if the account can see the link
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id)
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        check_role(
+            db_conn,
+            link.as_ref()
+                .map(|link| Space::new(link.created_by_id, link.workspace_id)),
+            &created_by_id,
+            model::WorkspaceRole::Viewer,
+            "The link is not found.",
+        )?;
+        let Some(link) = link else {
+            return Err(Status::aborted("The link is not found."));
+        };
+
+        // Get the tags and the variants of the link
+        let tags = link_tag::get_tags_by_link_ids(db_conn, &[link.id])
+            .map_err(|e| Status::internal(e.to_string()))?
+            .remove(&link.id)
+            .unwrap_or_default();
+        let variants = link_variant::get_variants_by_link_ids(db_conn, &[link.id])
+            .map_err(|e| Status::internal(e.to_string()))?
+            .remove(&link.id)
+            .unwrap_or_default();
+
+        Ok(Response::new(link.to_grpc(tags, variants)))
     }
 
     async fn get_link_by_short_url(
@@ -84,6 +163,7 @@ impl LinkService for LinkController {
         // Get a link with a short url
         let (link_short_url, link_long_url) = schema::link::table
             .filter(schema::link::short_url.eq(&req.get_ref().short_url))
+            .filter(schema::link::deleted_at.is_null())
             .select((schema::link::short_url, schema::link::long_url))
             .first::<(String, String)>(db_conn)
             .map_err(|e| Status::internal(e.to_string()))?;
@@ -103,25 +183,153 @@ impl LinkService for LinkController {
                 .map_err(|e| Status::internal(e.to_string()))?;
 
         // Get a link with a short url
-        let (link_id, link_visits) = schema::link::table
+        let (link_id, link_visits, expires_at, disabled_at) = schema::link::table
             .filter(schema::link::short_url.eq(&req.get_ref().short_url))
-            .select((schema::link::id, schema::link::visits))
-            .first::<(Uuid, i32)>(db_conn)
-            .map_err(|e| Status::internal(e.to_string()))?;
+            .filter(schema::link::deleted_at.is_null())
+            .select((
+                schema::link::id,
+                schema::link::visits,
+                schema::link::expires_at,
+                schema::link::disabled_at,
+            ))
+            .first::<(Uuid, i32, Option<NaiveDateTime>, Option<NaiveDateTime>)>(db_conn)
+            .optional()
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| Status::not_found("The link is not found."))?;
+
+        // Check if the link has been disabled or has expired, its visits aren't counted
+        if disabled_at.is_some() {
+            return Err(Status::permission_denied("The link has been disabled."));
+        }
+        if matches!(expires_at, Some(expires_at) if expires_at <= Utc::now().naive_utc()) {
+            return Err(Status::failed_precondition("The link has expired."));
+        }
 
         // Increment visits by one
-        let (link_short_url, link_long_url) = diesel::update(schema::link::table.find(&link_id))
+        let link = diesel::update(schema::link::table.find(&link_id))
             .set((
                 schema::link::visits.eq(link_visits + 1),
                 schema::link::updated_at.eq(diesel::dsl::now),
             ))
-            .returning((schema::link::short_url, schema::link::long_url))
-            .get_result::<(String, String)>(db_conn)
+            .get_result::<model::Link>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Get the destination of the first rule matching the visitor
+        let rules = link_rule::get_rules_by_link_id(db_conn, &link.id)
             .map_err(|e| Status::internal(e.to_string()))?;
+        let mut cacheable = rules.is_empty() && !link.forward_query && link.expires_at.is_none();
+        let destination_url = match rules.is_empty() {
+            true => None,
+            false => {
+                let visitor = link_rule::Visitor::new(
+                    req.get_ref().user_agent.as_deref(),
+                    req.get_ref().ip_address.as_deref(),
+                    req.get_ref().accept_language.as_deref(),
+                    self.geoip_reader.as_ref(),
+                );
+                let now = Utc::now().naive_utc();
+                rules
+                    .iter()
+                    .find(|rule| link_rule::is_rule_matched(rule, &visitor, &now))
+                    .map(|rule| rule.destination_url.to_owned())
+            }
+        };
+
+        // Without a matching rule, split the visitors between the variants by weight
+        let destination_url = match destination_url {
+            Some(destination_url) => Some(destination_url),
+            None => {
+                let variants = link_variant::get_variants_by_link_id(db_conn, &link.id)
+                    .map_err(|e| Status::internal(e.to_string()))?;
+                cacheable &= variants.is_empty();
+                let visitor_id = req
+                    .get_ref()
+                    .visitor_id
+                    .to_owned()
+                    .unwrap_or_else(|| Uuid::new_v4().to_string());
+                match link_variant::pick_variant(&variants, &link.id, &visitor_id) {
+                    Some(variant) => {
+                        diesel::update(schema::link_variant::table.find(&variant.id))
+                            .set(schema::link_variant::visits.eq(schema::link_variant::visits + 1))
+                            .execute(db_conn)
+                            .map_err(|e| Status::internal(e.to_string()))?;
+                        Some(variant.destination_url.to_owned())
+                    }
+                    None => None,
+                }
+            }
+        };
+
+        // Add the UTM parameters and the forwarded query to the destination
+        let forwarded_query = match link.forward_query {
+            true => req.get_ref().query.as_deref(),
+            false => None,
+        };
+        let long_url = link_destination::build(
+            destination_url.as_deref().unwrap_or(&link.long_url),
+            &link.utm_params(),
+            forwarded_query,
+        );
+
+        // Publish the visit without holding up the redirect
+        let visited = LinkVisited {
+            link_id: link.id.to_string(),
+            short_url: link.short_url.to_owned(),
+            count: 1,
+            visits: link.visits,
+        };
+        let kafka_producer = self.kafka_producer.to_owned();
+        let rabbitmq_channel = self.rabbitmq_channel.to_owned();
+        tokio::spawn(async move {
+            link_event::publish_visited(&kafka_producer, &rabbitmq_channel, &[visited]).await;
+        });
+
+        let deep_link = link.deep_link();
 
         Ok(Response::new(proto::link::VisitLinkRes {
-            short_url: link_short_url,
-            long_url: link_long_url,
+            deep_link: Some(deep_link),
+            short_url: link.short_url,
+            long_url,
+            title: link.title,
+            interstitial: link.interstitial,
+            cacheable,
+            redirect_status: link.redirect_status.to_grpc_enum(),
+            referrer_policy: link.referrer_policy,
+            no_index: link.no_index,
+        }))
+    }
+
+    async fn preview_link(
+        &self,
+        req: Request<proto::link::PreviewLinkReq>,
+    ) -> Result<Response<proto::link::PreviewLinkRes>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Get a link with a short url without counting a visit
+        let link = schema::link::table
+            .filter(schema::link::short_url.eq(&req.get_ref().short_url))
+            .filter(schema::link::deleted_at.is_null())
+            .first::<model::Link>(db_conn)
+            .optional()
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| Status::not_found("The link is not found."))?;
+        if link.disabled_at.is_some() {
+            return Err(Status::permission_denied("The link has been disabled."));
+        }
+        if link.is_expired() {
+            return Err(Status::failed_precondition("The link has expired."));
+        }
+
+        let og_metadata = link.effective_og_metadata();
+
+        Ok(Response::new(proto::link::PreviewLinkRes {
+            short_url: link.short_url,
+            title: link.title,
+            og_metadata: Some(og_metadata),
+            long_url: link.long_url,
+            visits: link.visits,
         }))
     }
 
@@ -133,27 +341,121 @@ impl LinkService for LinkController {
             &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
                 .map_err(|e| Status::internal(e.to_string()))?;
 
-        // Add link to database
+        let redirect_status =
+            model::LinkRedirectStatus::from_grpc_enum(req.get_ref().redirect_status)
+                .ok_or_else(|| Status::aborted("The redirect status is not supported."))?;
+        if let Some(referrer_policy) = &req.get_ref().referrer_policy {
+            link_validation::validate_referrer_policy(referrer_policy).map_err(Status::aborted)?;
+        }
+        let expires_at = match req.get_ref().expires_at.as_deref() {
+            Some(expires_at) => {
+                Some(link_validation::parse_expires_at(expires_at).map_err(Status::aborted)?)
+            }
+            None => None,
+        };
+        let deep_link = link::deep_link_from_grpc(
+            req.get_ref()
+                .deep_link
+                .as_ref()
+                .unwrap_or(&Default::default()),
+        )
+        .map_err(Status::aborted)?;
+
+        // Check if the account can add links to the space
         let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
-        let link = diesel::insert_into(schema::link::table)
-            .values((
-                schema::link::title.eq(&req.get_ref().title),
-                schema::link::short_url.eq(&req.get_ref().short_url),
-                schema::link::long_url.eq(&req.get_ref().long_url),
-                schema::link::created_by_id.eq(&created_by_id),
-            ))
-            .get_result::<model::Link>(db_conn)
+        let space = Space::parse(&created_by_id, &req.get_ref().workspace_id)
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        check_role(
+            db_conn,
+            Some(space),
+            &created_by_id,
+            model::WorkspaceRole::Editor,
+            "The workspace is not found.",
+        )?;
+
+        // Check if the folder is in the same space
+        let folder_id = req
+            .get_ref()
+            .folder_id
+            .as_ref()
+            .map(|folder_id| Uuid::from_str(folder_id))
+            .transpose()
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        if let Some(folder_id) = &folder_id {
+            let folder_space = link_folder::get_folder_space(db_conn, folder_id)
+                .map_err(|e| Status::internal(e.to_string()))?;
+            if folder_space != Some(space) {
+                return Err(Status::aborted("The folder is not found."));
+            }
+        }
+
+        // Add link to database with its first revision
+        let link = db_conn
+            .transaction::<_, diesel::result::Error, _>(|db_conn| {
+                let link = diesel::insert_into(schema::link::table)
+                    .values((
+                        schema::link::title.eq(&req.get_ref().title),
+                        schema::link::short_url.eq(&req.get_ref().short_url),
+                        schema::link::long_url.eq(&req.get_ref().long_url),
+                        schema::link::created_by_id.eq(&created_by_id),
+                        schema::link::workspace_id.eq(space.workspace_id()),
+                        schema::link::folder_id.eq(&folder_id),
+                        link::utm_from_grpc(
+                            req.get_ref().utm.as_ref().unwrap_or(&Default::default()),
+                        ),
+                        schema::link::forward_query.eq(req.get_ref().forward_query),
+                        schema::link::interstitial.eq(req.get_ref().interstitial),
+                        schema::link::redirect_status.eq(redirect_status),
+                        schema::link::referrer_policy.eq(&req.get_ref().referrer_policy),
+                        schema::link::no_index.eq(req.get_ref().no_index),
+                        schema::link::expires_at.eq(expires_at),
+                        deep_link,
+                        link::og_metadata_override_from_grpc(
+                            req.get_ref()
+                                .og_metadata_override
+                                .as_ref()
+                                .unwrap_or(&Default::default()),
+                        ),
+                    ))
+                    .get_result::<model::Link>(db_conn)?;
+                link_revision::record(db_conn, &link, &created_by_id)?;
+
+                Ok(link)
+            })
             .map_err(|e| Status::internal(e.to_string()))?;
 
-        Ok(Response::new(proto::link::Link {
-            id: link.id.to_string(),
-            title: link.title.to_owned(),
-            short_url: link.short_url.to_owned(),
-            long_url: link.long_url.to_owned(),
-            visits: link.visits,
-            created_at: link.created_at.to_string(),
-            updated_at: link.updated_at.to_string(),
-        }))
+        // Fetch the Open Graph metadata of the long url
+        link_og_metadata::refresh(
+            self.app_mode.to_owned(),
+            self.db_pool.to_owned(),
+            self.http_client.to_owned(),
+            self.og_fetch_allow_private_hosts,
+            link.id,
+            link.long_url.to_owned(),
+        );
+
+        // Drop the cached not found of the short url
+        link_event::publish(
+            &self.kafka_producer,
+            &self.rabbitmq_channel,
+            &[LinkEvent {
+                kind: LinkEventKind::Created,
+                short_url: link.short_url.to_owned(),
+            }],
+        )
+        .await;
+
+        // Get the tags and the variants of the link
+        let tags = link_tag::get_tags_by_link_ids(db_conn, &[link.id])
+            .map_err(|e| Status::internal(e.to_string()))?
+            .remove(&link.id)
+            .unwrap_or_default();
+        let variants = link_variant::get_variants_by_link_ids(db_conn, &[link.id])
+            .map_err(|e| Status::internal(e.to_string()))?
+            .remove(&link.id)
+            .unwrap_or_default();
+
+        Ok(Response::new(link.to_grpc(tags, variants)))
     }
 
     async fn update_link(
@@ -164,43 +466,142 @@ impl LinkService for LinkController {
             &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
                 .map_err(|e| Status::internal(e.to_string()))?;
 
-        // Check if the link is created by the id
+        let redirect_status = match req.get_ref().redirect_status {
+            Some(redirect_status) => Some(
+                model::LinkRedirectStatus::from_grpc_enum(redirect_status)
+                    .ok_or_else(|| Status::aborted("The redirect status is not supported."))?,
+            ),
+            None => None,
+        };
+        let referrer_policy = match req.get_ref().referrer_policy.as_deref() {
+            Some("") => Some(None),
+            Some(referrer_policy) => {
+                link_validation::validate_referrer_policy(referrer_policy)
+                    .map_err(Status::aborted)?;
+                Some(Some(referrer_policy.to_owned()))
+            }
+            None => None,
+        };
+        let expires_at = match req.get_ref().expires_at.as_deref() {
+            Some(expires_at) if expires_at.trim().is_empty() => Some(None),
+            Some(expires_at) => Some(Some(
+                link_validation::parse_expires_at(expires_at).map_err(Status::aborted)?,
+            )),
+            None => None,
+        };
+        let deep_link = match req.get_ref().deep_link.as_ref() {
+            Some(deep_link) => Some(link::deep_link_from_grpc(deep_link).map_err(Status::aborted)?),
+            None => None,
+        };
+
+        // Check if the account can edit the link
         let link_id =
             Uuid::from_str(&req.get_ref().id).map_err(|e| Status::aborted(e.to_string()))?;
         let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
-        let is_link_exist_and_created_by_id = diesel::select(diesel::dsl::exists(
-            schema::link::table
-                .find(&link_id)
-                .filter(schema::link::created_by_id.eq(&created_by_id)),
-        ))
-        .get_result::<bool>(db_conn)
-        .map_err(|e| Status::internal(e.to_string()))?;
-        if !is_link_exist_and_created_by_id {
-            return Err(Status::aborted("The link is not found."));
+        let space = link::get_links_space(db_conn, &[link_id])
+            .map_err(|e| Status::internal(e.to_string()))?;
+        check_role(
+            db_conn,
+            space,
+            &created_by_id,
+            model::WorkspaceRole::Editor,
+            "The link is not found.",
+        )?;
+        let old_short_url =
+            link::get_short_url(db_conn, &link_id).map_err(|e| Status::internal(e.to_string()))?;
+
+        // Update the link and record its new revision
+        let link = db_conn
+            .transaction::<_, diesel::result::Error, _>(|db_conn| {
+                // A new long url starts over with a clean health, so that it's flagged and its
+                // owners are mailed when it's broken too
+                let old_long_url = schema::link::table
+                    .find(&link_id)
+                    .select(schema::link::long_url)
+                    .for_update()
+                    .first::<String>(db_conn)?;
+                let health_reset = matches!(
+                    &req.get_ref().long_url,
+                    Some(long_url) if *long_url != old_long_url
+                )
+                .then_some((
+                    schema::link::health_failures.eq(0),
+                    schema::link::health_broken_at.eq(None::<NaiveDateTime>),
+                    schema::link::health_checked_at.eq(None::<NaiveDateTime>),
+                    schema::link::health_next_check_at.eq(None::<NaiveDateTime>),
+                ));
+
+                let link = diesel::update(schema::link::table.find(&link_id))
+                    .set((
+                        model::LinkChangeSet {
+                            title: req.get_ref().title.to_owned(),
+                            long_url: req.get_ref().long_url.to_owned(),
+                            short_url: req.get_ref().short_url.to_owned(),
+                            forward_query: req.get_ref().forward_query,
+                            interstitial: req.get_ref().interstitial,
+                            redirect_status,
+                            referrer_policy,
+                            no_index: req.get_ref().no_index,
+                            expires_at,
+                        },
+                        req.get_ref().utm.as_ref().map(link::utm_from_grpc),
+                        req.get_ref()
+                            .og_metadata_override
+                            .as_ref()
+                            .map(link::og_metadata_override_from_grpc),
+                        deep_link,
+                        health_reset,
+                        schema::link::updated_at.eq(diesel::dsl::now),
+                    ))
+                    .get_result::<model::Link>(db_conn)?;
+                link_revision::record(db_conn, &link, &created_by_id)?;
+
+                Ok(link)
+            })
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Fetch the Open Graph metadata of the new long url
+        if req.get_ref().long_url.is_some() {
+            link_og_metadata::refresh(
+                self.app_mode.to_owned(),
+                self.db_pool.to_owned(),
+                self.http_client.to_owned(),
+                self.og_fetch_allow_private_hosts,
+                link.id,
+                link.long_url.to_owned(),
+            );
         }
 
-        // Update the link
-        let link = diesel::update(schema::link::table.find(&link_id))
-            .set((
-                model::LinkChangeSet {
-                    title: req.get_ref().title.to_owned(),
-                    long_url: req.get_ref().long_url.to_owned(),
-                    short_url: req.get_ref().short_url.to_owned(),
+        // Drop the cached links of the old and the new short url
+        let events = match link.short_url == old_short_url {
+            true => vec![LinkEvent {
+                kind: LinkEventKind::Updated,
+                short_url: old_short_url,
+            }],
+            false => vec![
+                LinkEvent {
+                    kind: LinkEventKind::Deleted,
+                    short_url: old_short_url,
                 },
-                schema::link::updated_at.eq(diesel::dsl::now),
-            ))
-            .get_result::<model::Link>(db_conn)
-            .map_err(|e| Status::internal(e.to_string()))?;
+                LinkEvent {
+                    kind: LinkEventKind::Created,
+                    short_url: link.short_url.to_owned(),
+                },
+            ],
+        };
+        link_event::publish(&self.kafka_producer, &self.rabbitmq_channel, &events).await;
 
-        Ok(Response::new(proto::link::Link {
-            id: link.id.to_string(),
-            title: link.title.to_owned(),
-            short_url: link.short_url.to_owned(),
-            long_url: link.long_url.to_owned(),
-            visits: link.visits,
-            created_at: link.created_at.to_string(),
-            updated_at: link.updated_at.to_string(),
-        }))
+        // Get the tags and the variants of the link
+        let tags = link_tag::get_tags_by_link_ids(db_conn, &[link.id])
+            .map_err(|e| Status::internal(e.to_string()))?
+            .remove(&link.id)
+            .unwrap_or_default();
+        let variants = link_variant::get_variants_by_link_ids(db_conn, &[link.id])
+            .map_err(|e| Status::internal(e.to_string()))?
+            .remove(&link.id)
+            .unwrap_or_default();
+
+        Ok(Response::new(link.to_grpc(tags, variants)))
     }
 
     async fn delete_link(
@@ -211,26 +612,2083 @@ impl LinkService for LinkController {
             &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
                 .map_err(|e| Status::internal(e.to_string()))?;
 
-        // Check if the link is created by the id
+        // Check if the account can edit the link
         let link_id =
             Uuid::from_str(&req.get_ref().id).map_err(|e| Status::aborted(e.to_string()))?;
         let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
-        let is_link_exist_and_created_by_id = diesel::select(diesel::dsl::exists(
+        let space = link::get_links_space(db_conn, &[link_id])
+            .map_err(|e| Status::internal(e.to_string()))?;
+        check_role(
+            db_conn,
+            space,
+            &created_by_id,
+            model::WorkspaceRole::Editor,
+            "The link is not found.",
+        )?;
+
+        // Move the link to the trash
+        let short_url = diesel::update(schema::link::table.find(&link_id))
+            .set((
+                schema::link::deleted_at.eq(diesel::dsl::now),
+                schema::link::deleted_by_id.eq(&created_by_id),
+                schema::link::updated_at.eq(diesel::dsl::now),
+            ))
+            .returning(schema::link::short_url)
+            .get_result::<String>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        link_event::publish(
+            &self.kafka_producer,
+            &self.rabbitmq_channel,
+            &[LinkEvent {
+                kind: LinkEventKind::Deleted,
+                short_url,
+            }],
+        )
+        .await;
+
+        Ok(Response::new(proto::link::OpRes { is_success: true }))
+    }
+
+    async fn import_links(
+        &self,
+        req: Request<Streaming<proto::link::ImportLinksReq>>,
+    ) -> Result<Response<proto::link::ImportLinksRes>> {
+        let mut stream = req.into_inner();
+
+        // Read the options from the first message and collect the file chunks
+        let first_req = stream
+            .message()
+            .await?
+            .ok_or_else(|| Status::aborted("The import file is empty."))?;
+        let created_by_id =
+            Uuid::from_str(&first_req.created_by_id).map_err(|e| Status::aborted(e.to_string()))?;
+        let space = Space::parse(&created_by_id, &first_req.workspace_id)
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        let format = first_req.format();
+        let conflict_strategy = first_req.conflict_strategy();
+        let dry_run = first_req.dry_run;
+        let mut content = first_req.chunk;
+        while let Some(req) = stream.message().await? {
+            if content.len() + req.chunk.len() > IMPORT_MAX_SIZE {
+                return Err(Status::aborted(format!(
+                    "The import file is larger than {IMPORT_MAX_SIZE} bytes."
+                )));
+            }
+            content.extend_from_slice(&req.chunk);
+        }
+
+        let records =
+            link_file::parse(format, &content).map_err(|e| Status::aborted(e.to_string()))?;
+
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Check if the account can add links to the space
+        check_role(
+            db_conn,
+            Some(space),
+            &created_by_id,
+            model::WorkspaceRole::Editor,
+            "The workspace is not found.",
+        )?;
+
+        // Import the rows in batches, each batch is committed or rolled back as a whole
+        let mut rows = Vec::with_capacity(records.len());
+        for (batch_index, batch) in records.chunks(IMPORT_BATCH_SIZE).enumerate() {
+            let first_row = batch_index * IMPORT_BATCH_SIZE + 1;
+            let mut batch_rows = Vec::with_capacity(batch.len());
+
+            let batch_res = db_conn.transaction::<_, diesel::result::Error, _>(|db_conn| {
+                for (index, record) in batch.iter().enumerate() {
+                    let row = (first_row + index) as u32;
+                    batch_rows.push(match record {
+                        Ok(record) => link_import::import_record(
+                            db_conn,
+                            row,
+                            record,
+                            &created_by_id,
+                            &space,
+                            conflict_strategy,
+                        )?,
+                        Err(e) => link_import::invalid_row(row, "", e),
+                    });
+                }
+
+                // Roll back to leave the database untouched while still reporting
+                // what would have happened
+                if dry_run {
+                    return Err(diesel::result::Error::RollbackTransaction);
+                }
+                Ok(())
+            });
+
+            match batch_res {
+                Ok(()) | Err(diesel::result::Error::RollbackTransaction) => {
+                    rows.append(&mut batch_rows)
+                }
+                Err(e) => rows.extend(batch.iter().enumerate().map(|(index, record)| {
+                    link_import::failed_row(
+                        (first_row + index) as u32,
+                        record
+                            .as_ref()
+                            .map(|record| record.short_url.as_str())
+                            .unwrap_or_default(),
+                        &e.to_string(),
+                    )
+                })),
+            }
+        }
+
+        // Drop the cached links of the imported short urls
+        if !dry_run {
+            let events = rows
+                .iter()
+                .filter_map(|row| {
+                    let kind = match row.status() {
+                        proto::link::ImportRowStatus::Created
+                        | proto::link::ImportRowStatus::Renamed => LinkEventKind::Created,
+                        proto::link::ImportRowStatus::Updated => LinkEventKind::Updated,
+                        _ => return None,
+                    };
+                    Some(LinkEvent {
+                        kind,
+                        short_url: row.short_url.to_owned(),
+                    })
+                })
+                .collect::<Vec<LinkEvent>>();
+            link_event::publish(&self.kafka_producer, &self.rabbitmq_channel, &events).await;
+        }
+
+        let count_rows = |statuses: &[proto::link::ImportRowStatus]| {
+            rows.iter()
+                .filter(|row| statuses.contains(&row.status()))
+                .count() as u32
+        };
+
+        Ok(Response::new(proto::link::ImportLinksRes {
+            dry_run,
+            total: rows.len() as u32,
+            imported: count_rows(&[
+                proto::link::ImportRowStatus::Created,
+                proto::link::ImportRowStatus::Updated,
+                proto::link::ImportRowStatus::Renamed,
+            ]),
+            skipped: count_rows(&[proto::link::ImportRowStatus::Skipped]),
+            failed: count_rows(&[
+                proto::link::ImportRowStatus::Invalid,
+                proto::link::ImportRowStatus::Failed,
+            ]),
+            rows,
+        }))
+    }
+
+    async fn export_links(
+        &self,
+        req: Request<proto::link::ExportLinksReq>,
+    ) -> Result<Response<proto::link::ExportLinksRes>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Check if the account can see the links of the space
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id)
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        let space = Space::parse(&created_by_id, &req.get_ref().workspace_id)
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        check_role(
+            db_conn,
+            Some(space),
+            &created_by_id,
+            model::WorkspaceRole::Viewer,
+            "The workspace is not found.",
+        )?;
+
+        // Get all links of the space
+        let links = link::filter_by_space(
             schema::link::table
-                .find(&link_id)
-                .filter(schema::link::created_by_id.eq(&created_by_id)),
-        ))
-        .get_result::<bool>(db_conn)
+                .filter(schema::link::deleted_at.is_null())
+                .order(schema::link::created_at.asc())
+                .into_boxed(),
+            &space,
+        )
+        .load::<model::Link>(db_conn)
         .map_err(|e| Status::internal(e.to_string()))?;
-        if !is_link_exist_and_created_by_id {
-            return Err(Status::aborted("You are not the one who created the link."));
+
+        let format = req.get_ref().format();
+        let content = link_file::serialize(
+            format,
+            &links
+                .into_iter()
+                .map(|link| LinkRecord {
+                    title: link.title,
+                    short_url: link.short_url,
+                    long_url: link.long_url,
+                })
+                .collect::<Vec<LinkRecord>>(),
+        )
+        .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(proto::link::ExportLinksRes {
+            content_type: link_file::content_type(format).to_string(),
+            content,
+        }))
+    }
+
+    async fn move_links(
+        &self,
+        req: Request<proto::link::MoveLinksReq>,
+    ) -> Result<Response<proto::link::OpRes>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        let link_ids =
+            link::parse_ids(&req.get_ref().ids).map_err(|e| Status::aborted(e.to_string()))?;
+        if link_ids.is_empty() {
+            return Ok(Response::new(proto::link::OpRes { is_success: true }));
         }
 
-        // Delete the link
-        diesel::delete(schema::link::table.find(&link_id))
+        // Check if the account can edit the links
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let space = link::get_links_space(db_conn, &link_ids)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let space = check_role(
+            db_conn,
+            space,
+            &created_by_id,
+            model::WorkspaceRole::Editor,
+            "Some of the links are not found.",
+        )?;
+
+        // Check if the folder is in the same space as the links
+        let folder_id = req
+            .get_ref()
+            .folder_id
+            .as_ref()
+            .map(|folder_id| Uuid::from_str(folder_id))
+            .transpose()
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        if let Some(folder_id) = &folder_id {
+            let folder_space = link_folder::get_folder_space(db_conn, folder_id)
+                .map_err(|e| Status::internal(e.to_string()))?;
+            if folder_space != Some(space) {
+                return Err(Status::aborted("The folder is not found."));
+            }
+        }
+
+        // Move the links
+        diesel::update(schema::link::table.filter(schema::link::id.eq_any(&link_ids)))
+            .set((
+                schema::link::folder_id.eq(&folder_id),
+                schema::link::updated_at.eq(diesel::dsl::now),
+            ))
+            .execute(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(proto::link::OpRes { is_success: true }))
+    }
+
+    async fn tag_links(
+        &self,
+        req: Request<proto::link::TagLinksReq>,
+    ) -> Result<Response<proto::link::OpRes>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        let link_ids =
+            link::parse_ids(&req.get_ref().ids).map_err(|e| Status::aborted(e.to_string()))?;
+        let tag_ids =
+            link::parse_ids(&req.get_ref().tag_ids).map_err(|e| Status::aborted(e.to_string()))?;
+        if link_ids.is_empty() || tag_ids.is_empty() {
+            return Ok(Response::new(proto::link::OpRes { is_success: true }));
+        }
+
+        // Check if the account can edit the links and the tags are in the same space
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let space = link::get_links_space(db_conn, &link_ids)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let space = check_role(
+            db_conn,
+            space,
+            &created_by_id,
+            model::WorkspaceRole::Editor,
+            "Some of the links are not found.",
+        )?;
+        let tags_space = link_tag::get_tags_space(db_conn, &tag_ids)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        if tags_space != Some(space) {
+            return Err(Status::aborted("Some of the tags are not found."));
+        }
+
+        // Tag the links, links that already have the tag are left as is
+        diesel::insert_into(schema::link_tag_map::table)
+            .values(
+                link_ids
+                    .iter()
+                    .flat_map(|link_id| {
+                        tag_ids.iter().map(|tag_id| {
+                            (
+                                schema::link_tag_map::link_id.eq(*link_id),
+                                schema::link_tag_map::tag_id.eq(*tag_id),
+                            )
+                        })
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .on_conflict_do_nothing()
+            .execute(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(proto::link::OpRes { is_success: true }))
+    }
+
+    async fn untag_links(
+        &self,
+        req: Request<proto::link::TagLinksReq>,
+    ) -> Result<Response<proto::link::OpRes>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        let link_ids =
+            link::parse_ids(&req.get_ref().ids).map_err(|e| Status::aborted(e.to_string()))?;
+        let tag_ids =
+            link::parse_ids(&req.get_ref().tag_ids).map_err(|e| Status::aborted(e.to_string()))?;
+        if link_ids.is_empty() || tag_ids.is_empty() {
+            return Ok(Response::new(proto::link::OpRes { is_success: true }));
+        }
+
+        // Check if the account can edit the links
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let space = link::get_links_space(db_conn, &link_ids)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        check_role(
+            db_conn,
+            space,
+            &created_by_id,
+            model::WorkspaceRole::Editor,
+            "Some of the links are not found.",
+        )?;
+
+        // Untag the links
+        diesel::delete(
+            schema::link_tag_map::table
+                .filter(schema::link_tag_map::link_id.eq_any(&link_ids))
+                .filter(schema::link_tag_map::tag_id.eq_any(&tag_ids)),
+        )
+        .execute(db_conn)
+        .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(proto::link::OpRes { is_success: true }))
+    }
+
+    async fn get_link_tags(
+        &self,
+        req: Request<proto::link::GetLinkTagsReq>,
+    ) -> Result<Response<proto::link::LinkTags>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Check if the account can see the tags of the space
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let space = Space::parse(&created_by_id, &req.get_ref().workspace_id)
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        check_role(
+            db_conn,
+            Some(space),
+            &created_by_id,
+            model::WorkspaceRole::Viewer,
+            "The workspace is not found.",
+        )?;
+
+        // Get all tags of the space
+        let tags = link_tag::filter_by_space(
+            schema::link_tag::table
+                .order(schema::link_tag::name.asc())
+                .into_boxed(),
+            &space,
+        )
+        .load::<model::LinkTag>(db_conn)
+        .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(proto::link::LinkTags {
+            tags: tags.iter().map(|tag| tag.to_grpc()).collect(),
+        }))
+    }
+
+    async fn create_link_tag(
+        &self,
+        req: Request<proto::link::CreateLinkTagReq>,
+    ) -> Result<Response<proto::link::LinkTag>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        link_validation::validate_name(&req.get_ref().name).map_err(Status::aborted)?;
+
+        // Check if the account can add tags to the space
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let space = Space::parse(&created_by_id, &req.get_ref().workspace_id)
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        check_role(
+            db_conn,
+            Some(space),
+            &created_by_id,
+            model::WorkspaceRole::Editor,
+            "The workspace is not found.",
+        )?;
+
+        // Add tag to database
+        let tag = diesel::insert_into(schema::link_tag::table)
+            .values((
+                schema::link_tag::name.eq(req.get_ref().name.trim()),
+                schema::link_tag::created_by_id.eq(&created_by_id),
+                schema::link_tag::workspace_id.eq(space.workspace_id()),
+            ))
+            .get_result::<model::LinkTag>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(tag.to_grpc()))
+    }
+
+    async fn update_link_tag(
+        &self,
+        req: Request<proto::link::UpdateLinkTagReq>,
+    ) -> Result<Response<proto::link::LinkTag>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        link_validation::validate_name(&req.get_ref().name).map_err(Status::aborted)?;
+
+        // Check if the account can edit the tag
+        let tag_id =
+            Uuid::from_str(&req.get_ref().id).map_err(|e| Status::aborted(e.to_string()))?;
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let space = link_tag::get_tags_space(db_conn, &[tag_id])
+            .map_err(|e| Status::internal(e.to_string()))?;
+        check_role(
+            db_conn,
+            space,
+            &created_by_id,
+            model::WorkspaceRole::Editor,
+            "The tag is not found.",
+        )?;
+
+        // Rename the tag
+        let tag = diesel::update(schema::link_tag::table.find(&tag_id))
+            .set((
+                schema::link_tag::name.eq(req.get_ref().name.trim()),
+                schema::link_tag::updated_at.eq(diesel::dsl::now),
+            ))
+            .get_result::<model::LinkTag>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(tag.to_grpc()))
+    }
+
+    async fn delete_link_tag(
+        &self,
+        req: Request<proto::link::DeleteLinkTagReq>,
+    ) -> Result<Response<proto::link::OpRes>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Check if the account can edit the tag
+        let tag_id =
+            Uuid::from_str(&req.get_ref().id).map_err(|e| Status::aborted(e.to_string()))?;
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let space = link_tag::get_tags_space(db_conn, &[tag_id])
+            .map_err(|e| Status::internal(e.to_string()))?;
+        check_role(
+            db_conn,
+            space,
+            &created_by_id,
+            model::WorkspaceRole::Editor,
+            "The tag is not found.",
+        )?;
+
+        // Delete the tag, it is removed from the links too
+        diesel::delete(schema::link_tag::table.find(&tag_id))
             .execute(db_conn)
             .map_err(|e| Status::internal(e.to_string()))?;
 
         Ok(Response::new(proto::link::OpRes { is_success: true }))
     }
+
+    async fn get_link_folders(
+        &self,
+        req: Request<proto::link::GetLinkFoldersReq>,
+    ) -> Result<Response<proto::link::LinkFolders>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Check if the account can see the folders of the space
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let space = Space::parse(&created_by_id, &req.get_ref().workspace_id)
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        check_role(
+            db_conn,
+            Some(space),
+            &created_by_id,
+            model::WorkspaceRole::Viewer,
+            "The workspace is not found.",
+        )?;
+
+        // Get all folders of the space
+        let folders = link_folder::filter_by_space(
+            schema::link_folder::table
+                .order(schema::link_folder::name.asc())
+                .into_boxed(),
+            &space,
+        )
+        .load::<model::LinkFolder>(db_conn)
+        .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(proto::link::LinkFolders {
+            folders: folders.iter().map(|folder| folder.to_grpc()).collect(),
+        }))
+    }
+
+    async fn create_link_folder(
+        &self,
+        req: Request<proto::link::CreateLinkFolderReq>,
+    ) -> Result<Response<proto::link::LinkFolder>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        link_validation::validate_name(&req.get_ref().name).map_err(Status::aborted)?;
+
+        // Check if the account can add folders to the space
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let space = Space::parse(&created_by_id, &req.get_ref().workspace_id)
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        check_role(
+            db_conn,
+            Some(space),
+            &created_by_id,
+            model::WorkspaceRole::Editor,
+            "The workspace is not found.",
+        )?;
+
+        // Check if the parent folder is in the same space
+        let parent_id = req
+            .get_ref()
+            .parent_id
+            .as_ref()
+            .map(|parent_id| Uuid::from_str(parent_id))
+            .transpose()
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        if let Some(parent_id) = &parent_id {
+            let parent_space = link_folder::get_folder_space(db_conn, parent_id)
+                .map_err(|e| Status::internal(e.to_string()))?;
+            if parent_space != Some(space) {
+                return Err(Status::aborted("The parent folder is not found."));
+            }
+        }
+
+        // Add folder to database
+        let folder = diesel::insert_into(schema::link_folder::table)
+            .values((
+                schema::link_folder::name.eq(req.get_ref().name.trim()),
+                schema::link_folder::parent_id.eq(&parent_id),
+                schema::link_folder::created_by_id.eq(&created_by_id),
+                schema::link_folder::workspace_id.eq(space.workspace_id()),
+            ))
+            .get_result::<model::LinkFolder>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(folder.to_grpc()))
+    }
+
+    async fn update_link_folder(
+        &self,
+        req: Request<proto::link::UpdateLinkFolderReq>,
+    ) -> Result<Response<proto::link::LinkFolder>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        if let Some(name) = &req.get_ref().name {
+            link_validation::validate_name(name).map_err(Status::aborted)?;
+        }
+
+        // Check if the account can edit the folder
+        let folder_id =
+            Uuid::from_str(&req.get_ref().id).map_err(|e| Status::aborted(e.to_string()))?;
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let space = link_folder::get_folder_space(db_conn, &folder_id)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let space = check_role(
+            db_conn,
+            space,
+            &created_by_id,
+            model::WorkspaceRole::Editor,
+            "The folder is not found.",
+        )?;
+
+        // Check if the new parent folder is in the same space and isn't the folder itself or
+        // one of its subfolders
+        let parent_id = match &req.get_ref().parent_id {
+            Some(parent_id) if parent_id.is_empty() => Some(None),
+            Some(parent_id) => {
+                let parent_id =
+                    Uuid::from_str(parent_id).map_err(|e| Status::aborted(e.to_string()))?;
+                let parent_space = link_folder::get_folder_space(db_conn, &parent_id)
+                    .map_err(|e| Status::internal(e.to_string()))?;
+                if parent_space != Some(space) {
+                    return Err(Status::aborted("The parent folder is not found."));
+                }
+                let folder_tree_ids = link_folder::get_folder_tree_ids(db_conn, &folder_id, &space)
+                    .map_err(|e| Status::internal(e.to_string()))?;
+                if folder_tree_ids.contains(&parent_id) {
+                    return Err(Status::aborted(
+                        "A folder can't be moved into itself or its subfolders.",
+                    ));
+                }
+                Some(Some(parent_id))
+            }
+            None => None,
+        };
+
+        // Update the folder
+        let folder = diesel::update(schema::link_folder::table.find(&folder_id))
+            .set((
+                model::LinkFolderChangeSet {
+                    name: req
+                        .get_ref()
+                        .name
+                        .as_ref()
+                        .map(|name| name.trim().to_string()),
+                    parent_id,
+                },
+                schema::link_folder::updated_at.eq(diesel::dsl::now),
+            ))
+            .get_result::<model::LinkFolder>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(folder.to_grpc()))
+    }
+
+    async fn delete_link_folder(
+        &self,
+        req: Request<proto::link::DeleteLinkFolderReq>,
+    ) -> Result<Response<proto::link::OpRes>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Check if the account can edit the folder
+        let folder_id =
+            Uuid::from_str(&req.get_ref().id).map_err(|e| Status::aborted(e.to_string()))?;
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let space = link_folder::get_folder_space(db_conn, &folder_id)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        check_role(
+            db_conn,
+            space,
+            &created_by_id,
+            model::WorkspaceRole::Editor,
+            "The folder is not found.",
+        )?;
+
+        // Delete the folder with its subfolders, their links are moved to the root
+        diesel::delete(schema::link_folder::table.find(&folder_id))
+            .execute(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(proto::link::OpRes { is_success: true }))
+    }
+
+    async fn get_link_rules(
+        &self,
+        req: Request<proto::link::GetLinkRulesReq>,
+    ) -> Result<Response<proto::link::LinkRules>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Check if the account can see the link
+        let link_id =
+            Uuid::from_str(&req.get_ref().link_id).map_err(|e| Status::aborted(e.to_string()))?;
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let space = link::get_links_space(db_conn, &[link_id])
+            .map_err(|e| Status::internal(e.to_string()))?;
+        check_role(
+            db_conn,
+            space,
+            &created_by_id,
+            model::WorkspaceRole::Viewer,
+            "The link is not found.",
+        )?;
+
+        // Get the rules of the link in the order they are matched
+        let rules = link_rule::get_rules_by_link_id(db_conn, &link_id)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(proto::link::LinkRules {
+            rules: rules.iter().map(|rule| rule.to_grpc()).collect(),
+        }))
+    }
+
+    async fn create_link_rule(
+        &self,
+        req: Request<proto::link::CreateLinkRuleReq>,
+    ) -> Result<Response<proto::link::LinkRule>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        let change_set = link_rule::parse_change_set(
+            req.get_ref().platform,
+            &req.get_ref().country,
+            &req.get_ref().language,
+            &req.get_ref().starts_at,
+            &req.get_ref().ends_at,
+            &req.get_ref().destination_url,
+        )
+        .map_err(Status::aborted)?;
+
+        // Check if the account can edit the link
+        let link_id =
+            Uuid::from_str(&req.get_ref().link_id).map_err(|e| Status::aborted(e.to_string()))?;
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let space = link::get_links_space(db_conn, &[link_id])
+            .map_err(|e| Status::internal(e.to_string()))?;
+        check_role(
+            db_conn,
+            space,
+            &created_by_id,
+            model::WorkspaceRole::Editor,
+            "The link is not found.",
+        )?;
+
+        // Add rule to database after the other rules of the link
+        let rule = db_conn
+            .transaction(|db_conn| {
+                let last_position = schema::link_rule::table
+                    .filter(schema::link_rule::link_id.eq(&link_id))
+                    .select(diesel::dsl::max(schema::link_rule::position))
+                    .first::<Option<i32>>(db_conn)?;
+
+                diesel::insert_into(schema::link_rule::table)
+                    .values((
+                        schema::link_rule::link_id.eq(&link_id),
+                        schema::link_rule::position.eq(last_position.map_or(0, |p| p + 1)),
+                        change_set,
+                    ))
+                    .get_result::<model::LinkRule>(db_conn)
+            })
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Drop the cached link, the rules change where it leads
+        let short_url =
+            link::get_short_url(db_conn, &link_id).map_err(|e| Status::internal(e.to_string()))?;
+        link_event::publish(
+            &self.kafka_producer,
+            &self.rabbitmq_channel,
+            &[LinkEvent {
+                kind: LinkEventKind::Updated,
+                short_url,
+            }],
+        )
+        .await;
+
+        Ok(Response::new(rule.to_grpc()))
+    }
+
+    async fn update_link_rule(
+        &self,
+        req: Request<proto::link::UpdateLinkRuleReq>,
+    ) -> Result<Response<proto::link::LinkRule>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        let change_set = link_rule::parse_change_set(
+            req.get_ref().platform,
+            &req.get_ref().country,
+            &req.get_ref().language,
+            &req.get_ref().starts_at,
+            &req.get_ref().ends_at,
+            &req.get_ref().destination_url,
+        )
+        .map_err(Status::aborted)?;
+
+        // Check if the account can edit the link of the rule
+        let rule_id =
+            Uuid::from_str(&req.get_ref().id).map_err(|e| Status::aborted(e.to_string()))?;
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let link = link_rule::get_rule_link(db_conn, &rule_id)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        check_role(
+            db_conn,
+            link.map(|(_, space)| space),
+            &created_by_id,
+            model::WorkspaceRole::Editor,
+            "The rule is not found.",
+        )?;
+        let Some((link_id, _)) = link else {
+            return Err(Status::aborted("The rule is not found."));
+        };
+
+        // Update the rule, the conditions that aren't set are cleared
+        let rule = diesel::update(schema::link_rule::table.find(&rule_id))
+            .set((
+                change_set,
+                schema::link_rule::updated_at.eq(diesel::dsl::now),
+            ))
+            .get_result::<model::LinkRule>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Drop the cached link, the rules change where it leads
+        let short_url =
+            link::get_short_url(db_conn, &link_id).map_err(|e| Status::internal(e.to_string()))?;
+        link_event::publish(
+            &self.kafka_producer,
+            &self.rabbitmq_channel,
+            &[LinkEvent {
+                kind: LinkEventKind::Updated,
+                short_url,
+            }],
+        )
+        .await;
+
+        Ok(Response::new(rule.to_grpc()))
+    }
+
+    async fn delete_link_rule(
+        &self,
+        req: Request<proto::link::DeleteLinkRuleReq>,
+    ) -> Result<Response<proto::link::OpRes>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Check if the account can edit the link of the rule
+        let rule_id =
+            Uuid::from_str(&req.get_ref().id).map_err(|e| Status::aborted(e.to_string()))?;
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let link = link_rule::get_rule_link(db_conn, &rule_id)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        check_role(
+            db_conn,
+            link.map(|(_, space)| space),
+            &created_by_id,
+            model::WorkspaceRole::Editor,
+            "The rule is not found.",
+        )?;
+        let Some((link_id, _)) = link else {
+            return Err(Status::aborted("The rule is not found."));
+        };
+
+        // Delete the rule
+        diesel::delete(schema::link_rule::table.find(&rule_id))
+            .execute(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Drop the cached link, the rules change where it leads
+        let short_url =
+            link::get_short_url(db_conn, &link_id).map_err(|e| Status::internal(e.to_string()))?;
+        link_event::publish(
+            &self.kafka_producer,
+            &self.rabbitmq_channel,
+            &[LinkEvent {
+                kind: LinkEventKind::Updated,
+                short_url,
+            }],
+        )
+        .await;
+
+        Ok(Response::new(proto::link::OpRes { is_success: true }))
+    }
+
+    async fn reorder_link_rules(
+        &self,
+        req: Request<proto::link::ReorderLinkRulesReq>,
+    ) -> Result<Response<proto::link::LinkRules>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Check if the account can edit the link
+        let link_id =
+            Uuid::from_str(&req.get_ref().link_id).map_err(|e| Status::aborted(e.to_string()))?;
+        let rule_ids =
+            link::parse_ids(&req.get_ref().ids).map_err(|e| Status::aborted(e.to_string()))?;
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let space = link::get_links_space(db_conn, &[link_id])
+            .map_err(|e| Status::internal(e.to_string()))?;
+        check_role(
+            db_conn,
+            space,
+            &created_by_id,
+            model::WorkspaceRole::Editor,
+            "The link is not found.",
+        )?;
+
+        // Check if the ids are exactly the rules of the link
+        let rules = link_rule::get_rules_by_link_id(db_conn, &link_id)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        if rules.len() != rule_ids.len() || rules.iter().any(|rule| !rule_ids.contains(&rule.id)) {
+            return Err(Status::aborted(
+                "The ids must contain every rule of the link exactly once.",
+            ));
+        }
+
+        // Set the positions of the rules to the order of the ids
+        let rules = db_conn
+            .transaction(|db_conn| {
+                for (position, rule_id) in rule_ids.iter().enumerate() {
+                    diesel::update(schema::link_rule::table.find(rule_id))
+                        .set((
+                            schema::link_rule::position.eq(position as i32),
+                            schema::link_rule::updated_at.eq(diesel::dsl::now),
+                        ))
+                        .execute(db_conn)?;
+                }
+
+                link_rule::get_rules_by_link_id(db_conn, &link_id)
+            })
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Drop the cached link, the rules change where it leads
+        let short_url =
+            link::get_short_url(db_conn, &link_id).map_err(|e| Status::internal(e.to_string()))?;
+        link_event::publish(
+            &self.kafka_producer,
+            &self.rabbitmq_channel,
+            &[LinkEvent {
+                kind: LinkEventKind::Updated,
+                short_url,
+            }],
+        )
+        .await;
+
+        Ok(Response::new(proto::link::LinkRules {
+            rules: rules.iter().map(|rule| rule.to_grpc()).collect(),
+        }))
+    }
+
+    async fn create_link_variant(
+        &self,
+        req: Request<proto::link::CreateLinkVariantReq>,
+    ) -> Result<Response<proto::link::LinkVariant>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        link_validation::validate_long_url(&req.get_ref().destination_url)
+            .and_then(|_| link_validation::validate_weight(req.get_ref().weight))
+            .map_err(Status::aborted)?;
+
+        // Check if the account can edit the link
+        let link_id =
+            Uuid::from_str(&req.get_ref().link_id).map_err(|e| Status::aborted(e.to_string()))?;
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let space = link::get_links_space(db_conn, &[link_id])
+            .map_err(|e| Status::internal(e.to_string()))?;
+        check_role(
+            db_conn,
+            space,
+            &created_by_id,
+            model::WorkspaceRole::Editor,
+            "The link is not found.",
+        )?;
+
+        // Add variant to database
+        let variant = diesel::insert_into(schema::link_variant::table)
+            .values((
+                schema::link_variant::link_id.eq(&link_id),
+                schema::link_variant::destination_url.eq(&req.get_ref().destination_url),
+                schema::link_variant::weight.eq(req.get_ref().weight),
+            ))
+            .get_result::<model::LinkVariant>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Drop the cached link, the variants change where it leads
+        let short_url =
+            link::get_short_url(db_conn, &link_id).map_err(|e| Status::internal(e.to_string()))?;
+        link_event::publish(
+            &self.kafka_producer,
+            &self.rabbitmq_channel,
+            &[LinkEvent {
+                kind: LinkEventKind::Updated,
+                short_url,
+            }],
+        )
+        .await;
+
+        Ok(Response::new(variant.to_grpc()))
+    }
+
+    async fn update_link_variant(
+        &self,
+        req: Request<proto::link::UpdateLinkVariantReq>,
+    ) -> Result<Response<proto::link::LinkVariant>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        if let Some(destination_url) = &req.get_ref().destination_url {
+            link_validation::validate_long_url(destination_url).map_err(Status::aborted)?;
+        }
+        if let Some(weight) = req.get_ref().weight {
+            link_validation::validate_weight(weight).map_err(Status::aborted)?;
+        }
+
+        // Check if the account can edit the link of the variant
+        let variant_id =
+            Uuid::from_str(&req.get_ref().id).map_err(|e| Status::aborted(e.to_string()))?;
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let link = link_variant::get_variant_link(db_conn, &variant_id)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        check_role(
+            db_conn,
+            link.map(|(_, space)| space),
+            &created_by_id,
+            model::WorkspaceRole::Editor,
+            "The variant is not found.",
+        )?;
+        let Some((link_id, _)) = link else {
+            return Err(Status::aborted("The variant is not found."));
+        };
+
+        // Update the variant
+        let variant = diesel::update(schema::link_variant::table.find(&variant_id))
+            .set((
+                model::LinkVariantChangeSet {
+                    destination_url: req.get_ref().destination_url.to_owned(),
+                    weight: req.get_ref().weight,
+                },
+                schema::link_variant::updated_at.eq(diesel::dsl::now),
+            ))
+            .get_result::<model::LinkVariant>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Drop the cached link, the variants change where it leads
+        let short_url =
+            link::get_short_url(db_conn, &link_id).map_err(|e| Status::internal(e.to_string()))?;
+        link_event::publish(
+            &self.kafka_producer,
+            &self.rabbitmq_channel,
+            &[LinkEvent {
+                kind: LinkEventKind::Updated,
+                short_url,
+            }],
+        )
+        .await;
+
+        Ok(Response::new(variant.to_grpc()))
+    }
+
+    async fn delete_link_variant(
+        &self,
+        req: Request<proto::link::DeleteLinkVariantReq>,
+    ) -> Result<Response<proto::link::OpRes>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Check if the account can edit the link of the variant
+        let variant_id =
+            Uuid::from_str(&req.get_ref().id).map_err(|e| Status::aborted(e.to_string()))?;
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let link = link_variant::get_variant_link(db_conn, &variant_id)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        check_role(
+            db_conn,
+            link.map(|(_, space)| space),
+            &created_by_id,
+            model::WorkspaceRole::Editor,
+            "The variant is not found.",
+        )?;
+        let Some((link_id, _)) = link else {
+            return Err(Status::aborted("The variant is not found."));
+        };
+
+        // Delete the variant
+        diesel::delete(schema::link_variant::table.find(&variant_id))
+            .execute(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Drop the cached link, the variants change where it leads
+        let short_url =
+            link::get_short_url(db_conn, &link_id).map_err(|e| Status::internal(e.to_string()))?;
+        link_event::publish(
+            &self.kafka_producer,
+            &self.rabbitmq_channel,
+            &[LinkEvent {
+                kind: LinkEventKind::Updated,
+                short_url,
+            }],
+        )
+        .await;
+
+        Ok(Response::new(proto::link::OpRes { is_success: true }))
+    }
+
+    async fn report_link(
+        &self,
+        req: Request<proto::link::ReportLinkReq>,
+    ) -> Result<Response<proto::link::OpRes>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        let reason = model::LinkReportReason::from_grpc_enum(req.get_ref().reason)
+            .ok_or_else(|| Status::aborted("The reason is not supported."))?;
+        let details = req
+            .get_ref()
+            .details
+            .as_deref()
+            .map(str::trim)
+            .filter(|details| !details.is_empty());
+        if let Some(details) = details {
+            link_validation::validate_report_details(details).map_err(Status::aborted)?;
+        }
+
+        // Get a link with a short url
+        let link_id = schema::link::table
+            .filter(schema::link::short_url.eq(&req.get_ref().short_url))
+            .filter(schema::link::deleted_at.is_null())
+            .select(schema::link::id)
+            .first::<Uuid>(db_conn)
+            .optional()
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| Status::not_found("The link is not found."))?;
+
+        // Check if the visitor already reported the link
+        if let Some(reporter_ip) = &req.get_ref().reporter_ip {
+            let is_report_pending = link_report::is_report_pending(db_conn, &link_id, reporter_ip)
+                .map_err(|e| Status::internal(e.to_string()))?;
+            if is_report_pending {
+                return Ok(Response::new(proto::link::OpRes { is_success: true }));
+            }
+
+            // Check if the visitor sent too many reports in the last hour
+            if self.report_rate_limit > 0 {
+                let report_count = link_report::count_reports_since(
+                    db_conn,
+                    reporter_ip,
+                    &(Utc::now().naive_utc() - Duration::hours(1)),
+                )
+                .map_err(|e| Status::internal(e.to_string()))?;
+                if report_count >= self.report_rate_limit {
+                    return Err(Status::resource_exhausted(
+                        "Too many reports, try again later.",
+                    ));
+                }
+            }
+        }
+
+        // Add report to database
+        diesel::insert_into(schema::link_report::table)
+            .values((
+                schema::link_report::link_id.eq(&link_id),
+                schema::link_report::reason.eq(reason),
+                schema::link_report::details.eq(details),
+                schema::link_report::reporter_ip.eq(&req.get_ref().reporter_ip),
+            ))
+            .execute(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(proto::link::OpRes { is_success: true }))
+    }
+
+    async fn get_link_reports(
+        &self,
+        req: Request<proto::link::GetLinkReportsReq>,
+    ) -> Result<Response<proto::link::LinkReports>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        let status = match req.get_ref().status {
+            Some(status) => Some(
+                model::LinkReportStatus::from_grpc_enum(status)
+                    .ok_or_else(|| Status::aborted("The status is not supported."))?,
+            ),
+            None => None,
+        };
+
+        let reports = link_report::get_reports(db_conn, status)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(proto::link::LinkReports { reports }))
+    }
+
+    async fn dismiss_link_report(
+        &self,
+        req: Request<proto::link::DismissLinkReportReq>,
+    ) -> Result<Response<proto::link::LinkReport>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        let report_id =
+            Uuid::from_str(&req.get_ref().id).map_err(|e| Status::aborted(e.to_string()))?;
+        let resolved_by_id = Uuid::from_str(&req.get_ref().resolved_by_id).unwrap();
+
+        // Dismiss the report if it is pending
+        let report = diesel::update(
+            schema::link_report::table
+                .find(&report_id)
+                .filter(schema::link_report::status.eq(model::LinkReportStatus::Pending)),
+        )
+        .set((
+            schema::link_report::status.eq(model::LinkReportStatus::Dismissed),
+            schema::link_report::resolved_by_id.eq(&resolved_by_id),
+            schema::link_report::updated_at.eq(diesel::dsl::now),
+        ))
+        .get_result::<model::LinkReport>(db_conn)
+        .optional()
+        .map_err(|e| Status::internal(e.to_string()))?;
+        let Some(report) = report else {
+            return Err(Status::aborted("The pending report is not found."));
+        };
+
+        // Get the link of the report
+        let (short_url, long_url) = schema::link::table
+            .find(&report.link_id)
+            .select((schema::link::short_url, schema::link::long_url))
+            .first::<(String, String)>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(report.to_grpc(short_url, long_url)))
+    }
+
+    async fn disable_link(
+        &self,
+        req: Request<proto::link::DisableLinkReq>,
+    ) -> Result<Response<proto::link::Link>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        link_validation::validate_disabled_reason(&req.get_ref().reason)
+            .map_err(Status::aborted)?;
+
+        let link_id =
+            Uuid::from_str(&req.get_ref().id).map_err(|e| Status::aborted(e.to_string()))?;
+        let disabled_by_id = Uuid::from_str(&req.get_ref().disabled_by_id).unwrap();
+
+        // Disable the link and action its pending reports
+        let link = db_conn
+            .transaction::<_, diesel::result::Error, _>(|db_conn| {
+                let link = diesel::update(schema::link::table.find(&link_id))
+                    .set((
+                        schema::link::disabled_at.eq(diesel::dsl::now),
+                        schema::link::disabled_reason.eq(req.get_ref().reason.trim()),
+                        schema::link::disabled_by_id.eq(&disabled_by_id),
+                        schema::link::updated_at.eq(diesel::dsl::now),
+                    ))
+                    .get_result::<model::Link>(db_conn)
+                    .optional()?;
+
+                diesel::update(
+                    schema::link_report::table
+                        .filter(schema::link_report::link_id.eq(&link_id))
+                        .filter(schema::link_report::status.eq(model::LinkReportStatus::Pending)),
+                )
+                .set((
+                    schema::link_report::status.eq(model::LinkReportStatus::Actioned),
+                    schema::link_report::resolved_by_id.eq(&disabled_by_id),
+                    schema::link_report::updated_at.eq(diesel::dsl::now),
+                ))
+                .execute(db_conn)?;
+
+                Ok(link)
+            })
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let Some(link) = link else {
+            return Err(Status::aborted("The link is not found."));
+        };
+
+        // Drop the cached link, it must not lead anywhere anymore
+        link_event::publish(
+            &self.kafka_producer,
+            &self.rabbitmq_channel,
+            &[LinkEvent {
+                kind: LinkEventKind::Updated,
+                short_url: link.short_url.to_owned(),
+            }],
+        )
+        .await;
+
+        // Get the tags and the variants of the link
+        let tags = link_tag::get_tags_by_link_ids(db_conn, &[link.id])
+            .map_err(|e| Status::internal(e.to_string()))?
+            .remove(&link.id)
+            .unwrap_or_default();
+        let variants = link_variant::get_variants_by_link_ids(db_conn, &[link.id])
+            .map_err(|e| Status::internal(e.to_string()))?
+            .remove(&link.id)
+            .unwrap_or_default();
+
+        Ok(Response::new(link.to_grpc(tags, variants)))
+    }
+
+    async fn enable_link(
+        &self,
+        req: Request<proto::link::EnableLinkReq>,
+    ) -> Result<Response<proto::link::Link>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        let link_id =
+            Uuid::from_str(&req.get_ref().id).map_err(|e| Status::aborted(e.to_string()))?;
+
+        // Enable the link
+        let link = diesel::update(schema::link::table.find(&link_id))
+            .set((
+                schema::link::disabled_at.eq(None::<NaiveDateTime>),
+                schema::link::disabled_reason.eq(None::<String>),
+                schema::link::disabled_by_id.eq(None::<Uuid>),
+                schema::link::updated_at.eq(diesel::dsl::now),
+            ))
+            .get_result::<model::Link>(db_conn)
+            .optional()
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let Some(link) = link else {
+            return Err(Status::aborted("The link is not found."));
+        };
+
+        // Drop the cached takedown of the short url
+        link_event::publish(
+            &self.kafka_producer,
+            &self.rabbitmq_channel,
+            &[LinkEvent {
+                kind: LinkEventKind::Updated,
+                short_url: link.short_url.to_owned(),
+            }],
+        )
+        .await;
+
+        // Get the tags and the variants of the link
+        let tags = link_tag::get_tags_by_link_ids(db_conn, &[link.id])
+            .map_err(|e| Status::internal(e.to_string()))?
+            .remove(&link.id)
+            .unwrap_or_default();
+        let variants = link_variant::get_variants_by_link_ids(db_conn, &[link.id])
+            .map_err(|e| Status::internal(e.to_string()))?
+            .remove(&link.id)
+            .unwrap_or_default();
+
+        Ok(Response::new(link.to_grpc(tags, variants)))
+    }
+
+    async fn transfer_links(
+        &self,
+        req: Request<proto::link::TransferLinksReq>,
+    ) -> Result<Response<proto::link::OpRes>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        let link_ids =
+            link::parse_ids(&req.get_ref().ids).map_err(|e| Status::aborted(e.to_string()))?;
+        if link_ids.is_empty() {
+            return Ok(Response::new(proto::link::OpRes { is_success: true }));
+        }
+
+        // Check if the account owns the links
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let space = link::get_links_space(db_conn, &link_ids)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        check_role(
+            db_conn,
+            space,
+            &created_by_id,
+            model::WorkspaceRole::Owner,
+            "Some of the links are not found.",
+        )?;
+
+        // Check if the account can add links to the workspace they go to
+        let to_space = match (&req.get_ref().to_account_id, &req.get_ref().to_workspace_id) {
+            (Some(to_account_id), None) => Space::Personal(
+                Uuid::from_str(to_account_id).map_err(|e| Status::aborted(e.to_string()))?,
+            ),
+            (None, Some(to_workspace_id)) => check_role(
+                db_conn,
+                Some(Space::Workspace(
+                    Uuid::from_str(to_workspace_id).map_err(|e| Status::aborted(e.to_string()))?,
+                )),
+                &created_by_id,
+                model::WorkspaceRole::Editor,
+                "The workspace is not found.",
+            )?,
+            _ => {
+                return Err(Status::aborted(
+                    "The links must go to either an account or a workspace.",
+                ))
+            }
+        };
+        if let Space::Personal(to_account_id) = &to_space {
+            check_account(&self.account_service, to_account_id).await?;
+        }
+
+        // Hand the links over, they leave their folder and their tags behind
+        db_conn
+            .transaction::<_, diesel::result::Error, _>(|db_conn| {
+                diesel::delete(
+                    schema::link_tag_map::table
+                        .filter(schema::link_tag_map::link_id.eq_any(&link_ids)),
+                )
+                .execute(db_conn)?;
+
+                let links = schema::link::table.filter(schema::link::id.eq_any(&link_ids));
+                match to_space {
+                    Space::Personal(to_account_id) => diesel::update(links)
+                        .set((
+                            schema::link::created_by_id.eq(to_account_id),
+                            schema::link::workspace_id.eq(None::<Uuid>),
+                            schema::link::folder_id.eq(None::<Uuid>),
+                            schema::link::updated_at.eq(diesel::dsl::now),
+                        ))
+                        .execute(db_conn)?,
+                    Space::Workspace(to_workspace_id) => diesel::update(links)
+                        .set((
+                            schema::link::workspace_id.eq(to_workspace_id),
+                            schema::link::folder_id.eq(None::<Uuid>),
+                            schema::link::updated_at.eq(diesel::dsl::now),
+                        ))
+                        .execute(db_conn)?,
+                };
+
+                Ok(())
+            })
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(proto::link::OpRes { is_success: true }))
+    }
+
+    async fn get_workspaces(
+        &self,
+        req: Request<proto::link::GetWorkspacesReq>,
+    ) -> Result<Response<proto::link::Workspaces>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Get all workspaces the account is a member of
+        let account_id = Uuid::from_str(&req.get_ref().account_id).unwrap();
+        let workspaces = schema::workspace_member::table
+            .inner_join(schema::workspace::table)
+            .filter(schema::workspace_member::account_id.eq(&account_id))
+            .order(schema::workspace::name.asc())
+            .select((
+                schema::workspace::all_columns,
+                schema::workspace_member::role,
+            ))
+            .load::<(model::Workspace, model::WorkspaceRole)>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(proto::link::Workspaces {
+            workspaces: workspaces
+                .iter()
+                .map(|(workspace, role)| workspace.to_grpc(*role))
+                .collect(),
+        }))
+    }
+
+    async fn create_workspace(
+        &self,
+        req: Request<proto::link::CreateWorkspaceReq>,
+    ) -> Result<Response<proto::link::Workspace>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        link_validation::validate_name(&req.get_ref().name).map_err(Status::aborted)?;
+
+        // Add workspace to database with the account as its owner
+        let account_id = Uuid::from_str(&req.get_ref().account_id).unwrap();
+        let workspace = db_conn
+            .transaction(|db_conn| {
+                let workspace = diesel::insert_into(schema::workspace::table)
+                    .values((
+                        schema::workspace::name.eq(req.get_ref().name.trim()),
+                        schema::workspace::created_by_id.eq(&account_id),
+                    ))
+                    .get_result::<model::Workspace>(db_conn)?;
+
+                diesel::insert_into(schema::workspace_member::table)
+                    .values((
+                        schema::workspace_member::workspace_id.eq(&workspace.id),
+                        schema::workspace_member::account_id.eq(&account_id),
+                        schema::workspace_member::role.eq(model::WorkspaceRole::Owner),
+                    ))
+                    .execute(db_conn)?;
+
+                Ok::<_, diesel::result::Error>(workspace)
+            })
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(
+            workspace.to_grpc(model::WorkspaceRole::Owner),
+        ))
+    }
+
+    async fn update_workspace(
+        &self,
+        req: Request<proto::link::UpdateWorkspaceReq>,
+    ) -> Result<Response<proto::link::Workspace>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        link_validation::validate_name(&req.get_ref().name).map_err(Status::aborted)?;
+
+        // Check if the account owns the workspace
+        let workspace_id =
+            Uuid::from_str(&req.get_ref().id).map_err(|e| Status::aborted(e.to_string()))?;
+        let account_id = Uuid::from_str(&req.get_ref().account_id).unwrap();
+        check_role(
+            db_conn,
+            Some(Space::Workspace(workspace_id)),
+            &account_id,
+            model::WorkspaceRole::Owner,
+            "The workspace is not found.",
+        )?;
+
+        // Rename the workspace
+        let workspace = diesel::update(schema::workspace::table.find(&workspace_id))
+            .set((
+                schema::workspace::name.eq(req.get_ref().name.trim()),
+                schema::workspace::updated_at.eq(diesel::dsl::now),
+            ))
+            .get_result::<model::Workspace>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(
+            workspace.to_grpc(model::WorkspaceRole::Owner),
+        ))
+    }
+
+    async fn get_workspace_members(
+        &self,
+        req: Request<proto::link::GetWorkspaceMembersReq>,
+    ) -> Result<Response<proto::link::WorkspaceMembers>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Check if the account is a member of the workspace
+        let workspace_id = Uuid::from_str(&req.get_ref().workspace_id)
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        let account_id = Uuid::from_str(&req.get_ref().account_id).unwrap();
+        check_role(
+            db_conn,
+            Some(Space::Workspace(workspace_id)),
+            &account_id,
+            model::WorkspaceRole::Viewer,
+            "The workspace is not found.",
+        )?;
+
+        // Get the members in the order they joined
+        let members = schema::workspace_member::table
+            .filter(schema::workspace_member::workspace_id.eq(&workspace_id))
+            .order(schema::workspace_member::created_at.asc())
+            .load::<model::WorkspaceMember>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(proto::link::WorkspaceMembers {
+            members: members.iter().map(|member| member.to_grpc()).collect(),
+        }))
+    }
+
+    async fn invite_workspace_member(
+        &self,
+        req: Request<proto::link::InviteWorkspaceMemberReq>,
+    ) -> Result<Response<proto::link::WorkspaceMember>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        let role = model::WorkspaceRole::from_grpc_enum(req.get_ref().role)
+            .ok_or_else(|| Status::aborted("The role is not supported."))?;
+        let member_id =
+            Uuid::from_str(&req.get_ref().member_id).map_err(|e| Status::aborted(e.to_string()))?;
+
+        // Check if the account owns the workspace
+        let workspace_id = Uuid::from_str(&req.get_ref().workspace_id)
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        let account_id = Uuid::from_str(&req.get_ref().account_id).unwrap();
+        check_role(
+            db_conn,
+            Some(Space::Workspace(workspace_id)),
+            &account_id,
+            model::WorkspaceRole::Owner,
+            "The workspace is not found.",
+        )?;
+
+        // Check if the account to invite exists
+        check_account(&self.account_service, &member_id).await?;
+
+        // Add member to database, an account is only added once
+        let member = diesel::insert_into(schema::workspace_member::table)
+            .values((
+                schema::workspace_member::workspace_id.eq(&workspace_id),
+                schema::workspace_member::account_id.eq(&member_id),
+                schema::workspace_member::role.eq(role),
+                schema::workspace_member::invited_by_id.eq(&account_id),
+            ))
+            .on_conflict_do_nothing()
+            .get_result::<model::WorkspaceMember>(db_conn)
+            .optional()
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let Some(member) = member else {
+            return Err(Status::aborted(
+                "The account is already a member of the workspace.",
+            ));
+        };
+
+        Ok(Response::new(member.to_grpc()))
+    }
+
+    async fn update_workspace_member(
+        &self,
+        req: Request<proto::link::UpdateWorkspaceMemberReq>,
+    ) -> Result<Response<proto::link::WorkspaceMember>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        let role = model::WorkspaceRole::from_grpc_enum(req.get_ref().role)
+            .ok_or_else(|| Status::aborted("The role is not supported."))?;
+        let member_id =
+            Uuid::from_str(&req.get_ref().member_id).map_err(|e| Status::aborted(e.to_string()))?;
+
+        // Check if the account owns the workspace
+        let workspace_id = Uuid::from_str(&req.get_ref().workspace_id)
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        let account_id = Uuid::from_str(&req.get_ref().account_id).unwrap();
+        check_role(
+            db_conn,
+            Some(Space::Workspace(workspace_id)),
+            &account_id,
+            model::WorkspaceRole::Owner,
+            "The workspace is not found.",
+        )?;
+
+        // Change the role of the member, the workspace keeps at least one owner
+        let member = db_conn
+            .transaction(|db_conn| {
+                let member_role = workspace::get_member_role(db_conn, &workspace_id, &member_id)?;
+                let Some(member_role) = member_role else {
+                    return Ok(Err("The member is not found."));
+                };
+                if member_role == model::WorkspaceRole::Owner
+                    && role != model::WorkspaceRole::Owner
+                    && workspace::count_owners(db_conn, &workspace_id)? <= 1
+                {
+                    return Ok(Err("The workspace needs at least one owner."));
+                }
+
+                diesel::update(schema::workspace_member::table.find((&workspace_id, &member_id)))
+                    .set((
+                        schema::workspace_member::role.eq(role),
+                        schema::workspace_member::updated_at.eq(diesel::dsl::now),
+                    ))
+                    .get_result::<model::WorkspaceMember>(db_conn)
+                    .map(Ok)
+            })
+            .map_err(|e: diesel::result::Error| Status::internal(e.to_string()))?
+            .map_err(Status::aborted)?;
+
+        Ok(Response::new(member.to_grpc()))
+    }
+
+    async fn remove_workspace_member(
+        &self,
+        req: Request<proto::link::RemoveWorkspaceMemberReq>,
+    ) -> Result<Response<proto::link::OpRes>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        let member_id =
+            Uuid::from_str(&req.get_ref().member_id).map_err(|e| Status::aborted(e.to_string()))?;
+
+        // Check if the account owns the workspace or is leaving it
+        let workspace_id = Uuid::from_str(&req.get_ref().workspace_id)
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        let account_id = Uuid::from_str(&req.get_ref().account_id).unwrap();
+        check_role(
+            db_conn,
+            Some(Space::Workspace(workspace_id)),
+            &account_id,
+            match member_id == account_id {
+                true => model::WorkspaceRole::Viewer,
+                false => model::WorkspaceRole::Owner,
+            },
+            "The workspace is not found.",
+        )?;
+
+        // Remove the member, the workspace keeps at least one owner and the links they
+        // created
+        db_conn
+            .transaction(|db_conn| {
+                let member_role = workspace::get_member_role(db_conn, &workspace_id, &member_id)?;
+                let Some(member_role) = member_role else {
+                    return Ok(Err("The member is not found."));
+                };
+                if member_role == model::WorkspaceRole::Owner
+                    && workspace::count_owners(db_conn, &workspace_id)? <= 1
+                {
+                    return Ok(Err("The workspace needs at least one owner."));
+                }
+
+                diesel::delete(schema::workspace_member::table.find((&workspace_id, &member_id)))
+                    .execute(db_conn)
+                    .map(|_| Ok(()))
+            })
+            .map_err(|e: diesel::result::Error| Status::internal(e.to_string()))?
+            .map_err(Status::aborted)?;
+
+        Ok(Response::new(proto::link::OpRes { is_success: true }))
+    }
+
+    async fn get_link_revisions(
+        &self,
+        req: Request<proto::link::GetLinkRevisionsReq>,
+    ) -> Result<Response<proto::link::LinkRevisions>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Check if the account can see the link
+        let link_id =
+            Uuid::from_str(&req.get_ref().link_id).map_err(|e| Status::aborted(e.to_string()))?;
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let space = link::get_links_space(db_conn, &[link_id])
+            .map_err(|e| Status::internal(e.to_string()))?;
+        check_role(
+            db_conn,
+            space,
+            &created_by_id,
+            model::WorkspaceRole::Viewer,
+            "The link is not found.",
+        )?;
+
+        // Get the revisions of the link
+        let revisions = link_revision::get_revisions_by_link_id(db_conn, &link_id)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(proto::link::LinkRevisions {
+            revisions: revisions.iter().map(model::LinkRevision::to_grpc).collect(),
+        }))
+    }
+
+    async fn get_deleted_links(
+        &self,
+        req: Request<proto::link::GetDeletedLinksReq>,
+    ) -> Result<Response<proto::link::Links>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Check if the account can see the links of the space
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let space = Space::parse(&created_by_id, &req.get_ref().workspace_id)
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        check_role(
+            db_conn,
+            Some(space),
+            &created_by_id,
+            model::WorkspaceRole::Viewer,
+            "The workspace is not found.",
+        )?;
+
+        // Get the links in the trash of the space, the last deleted first
+        let links = link::filter_by_space(
+            schema::link::table
+                .filter(schema::link::deleted_at.is_not_null())
+                .order(schema::link::deleted_at.desc())
+                .into_boxed(),
+            &space,
+        )
+        .load::<model::Link>(db_conn)
+        .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Get the tags and the variants of the links
+        let link_ids = links.iter().map(|link| link.id).collect::<Vec<Uuid>>();
+        let mut tags_by_link_id = link_tag::get_tags_by_link_ids(db_conn, &link_ids)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let mut variants_by_link_id = link_variant::get_variants_by_link_ids(db_conn, &link_ids)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(proto::link::Links {
+            links: links
+                .iter()
+                .map(|link| {
+                    link.to_grpc(
+                        tags_by_link_id.remove(&link.id).unwrap_or_default(),
+                        variants_by_link_id.remove(&link.id).unwrap_or_default(),
+                    )
+                })
+                .collect(),
+        }))
+    }
+
+    async fn restore_link(
+        &self,
+        req: Request<proto::link::RestoreLinkReq>,
+    ) -> Result<Response<proto::link::Link>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Check if the account can edit the link in the trash
+        let link_id =
+            Uuid::from_str(&req.get_ref().id).map_err(|e| Status::aborted(e.to_string()))?;
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let space = link::get_deleted_link_space(db_conn, &link_id)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        check_role(
+            db_conn,
+            space,
+            &created_by_id,
+            model::WorkspaceRole::Editor,
+            "The link is not found in the trash.",
+        )?;
+
+        // Check if another link took the short url in the meantime
+        let short_url =
+            link::get_short_url(db_conn, &link_id).map_err(|e| Status::internal(e.to_string()))?;
+        if link::is_short_url_used(db_conn, &short_url)
+            .map_err(|e| Status::internal(e.to_string()))?
+        {
+            return Err(Status::aborted(
+                "The short url of the link is used by another link.",
+            ));
+        }
+
+        // Take the link out of the trash
+        let link = diesel::update(schema::link::table.find(&link_id))
+            .set((
+                schema::link::deleted_at.eq(None::<NaiveDateTime>),
+                schema::link::deleted_by_id.eq(None::<Uuid>),
+                schema::link::updated_at.eq(diesel::dsl::now),
+            ))
+            .get_result::<model::Link>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Drop the cached not found of the short url
+        link_event::publish(
+            &self.kafka_producer,
+            &self.rabbitmq_channel,
+            &[LinkEvent {
+                kind: LinkEventKind::Created,
+                short_url: link.short_url.to_owned(),
+            }],
+        )
+        .await;
+
+        // Get the tags and the variants of the link
+        let tags = link_tag::get_tags_by_link_ids(db_conn, &[link.id])
+            .map_err(|e| Status::internal(e.to_string()))?
+            .remove(&link.id)
+            .unwrap_or_default();
+        let variants = link_variant::get_variants_by_link_ids(db_conn, &[link.id])
+            .map_err(|e| Status::internal(e.to_string()))?
+            .remove(&link.id)
+            .unwrap_or_default();
+
+        Ok(Response::new(link.to_grpc(tags, variants)))
+    }
+
+    async fn purge_link(
+        &self,
+        req: Request<proto::link::PurgeLinkReq>,
+    ) -> Result<Response<proto::link::OpRes>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Check if the account can edit the link in the trash
+        let link_id =
+            Uuid::from_str(&req.get_ref().id).map_err(|e| Status::aborted(e.to_string()))?;
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let space = link::get_deleted_link_space(db_conn, &link_id)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        check_role(
+            db_conn,
+            space,
+            &created_by_id,
+            model::WorkspaceRole::Editor,
+            "The link is not found in the trash.",
+        )?;
+
+        // Delete the link for good, with its revisions, rules, variants and reports
+        diesel::delete(schema::link::table.find(&link_id))
+            .execute(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(proto::link::OpRes { is_success: true }))
+    }
+
+    async fn revert_link(
+        &self,
+        req: Request<proto::link::RevertLinkReq>,
+    ) -> Result<Response<proto::link::Link>> {
+        let db_conn =
+            &mut tools_lib_db::pg::connection::get_connection(&self.app_mode, &self.db_pool)
+                .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Check if the account can edit the link
+        let link_id =
+            Uuid::from_str(&req.get_ref().id).map_err(|e| Status::aborted(e.to_string()))?;
+        let created_by_id = Uuid::from_str(&req.get_ref().created_by_id).unwrap();
+        let space = link::get_links_space(db_conn, &[link_id])
+            .map_err(|e| Status::internal(e.to_string()))?;
+        check_role(
+            db_conn,
+            space,
+            &created_by_id,
+            model::WorkspaceRole::Editor,
+            "The link is not found.",
+        )?;
+
+        // Check if the revision belongs to the link
+        let revision_id = Uuid::from_str(&req.get_ref().revision_id)
+            .map_err(|e| Status::aborted(e.to_string()))?;
+        let revision = schema::link_revision::table
+            .find(&revision_id)
+            .filter(schema::link_revision::link_id.eq(&link_id))
+            .first::<model::LinkRevision>(db_conn)
+            .optional()
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| Status::aborted("The revision is not found."))?;
+        let (old_short_url, old_long_url) = schema::link::table
+            .find(&link_id)
+            .select((schema::link::short_url, schema::link::long_url))
+            .first::<(String, String)>(db_conn)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        if revision.short_url != old_short_url
+            && link::is_short_url_used(db_conn, &revision.short_url)
+                .map_err(|e| Status::internal(e.to_string()))?
+        {
+            return Err(Status::aborted(
+                "The short url of the revision is used by another link.",
+            ));
+        }
+
+        // Put the revision back, which is a change of its own
+        let link = db_conn
+            .transaction::<_, diesel::result::Error, _>(|db_conn| {
+                let link = diesel::update(schema::link::table.find(&link_id))
+                    .set((
+                        schema::link::title.eq(&revision.title),
+                        schema::link::short_url.eq(&revision.short_url),
+                        schema::link::long_url.eq(&revision.long_url),
+                        schema::link::updated_at.eq(diesel::dsl::now),
+                    ))
+                    .get_result::<model::Link>(db_conn)?;
+                link_revision::record(db_conn, &link, &created_by_id)?;
+
+                Ok(link)
+            })
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        // Fetch the Open Graph metadata of the restored long url
+        if link.long_url != old_long_url {
+            link_og_metadata::refresh(
+                self.app_mode.to_owned(),
+                self.db_pool.to_owned(),
+                self.http_client.to_owned(),
+                self.og_fetch_allow_private_hosts,
+                link.id,
+                link.long_url.to_owned(),
+            );
+        }
+
+        // Drop the cached links of the old and the new short url
+        let events = match link.short_url == old_short_url {
+            true => vec![LinkEvent {
+                kind: LinkEventKind::Updated,
+                short_url: old_short_url,
+            }],
+            false => vec![
+                LinkEvent {
+                    kind: LinkEventKind::Deleted,
+                    short_url: old_short_url,
+                },
+                LinkEvent {
+                    kind: LinkEventKind::Created,
+                    short_url: link.short_url.to_owned(),
+                },
+            ],
+        };
+        link_event::publish(&self.kafka_producer, &self.rabbitmq_channel, &events).await;
+
+        // Get the tags and the variants of the link
+        let tags = link_tag::get_tags_by_link_ids(db_conn, &[link.id])
+            .map_err(|e| Status::internal(e.to_string()))?
+            .remove(&link.id)
+            .unwrap_or_default();
+        let variants = link_variant::get_variants_by_link_ids(db_conn, &[link.id])
+            .map_err(|e| Status::internal(e.to_string()))?
+            .remove(&link.id)
+            .unwrap_or_default();
+
+        Ok(Response::new(link.to_grpc(tags, variants)))
+    }
+}
+
+/// Checks if the account has at least the role in the space and gives the space back. The
+/// spaces the account can't see are reported as not found, as if they don't exist.
+#[allow(clippy::result_large_err)]
+fn check_role(
+    db_conn: &mut PgConnection,
+    space: Option<Space>,
+    account_id: &Uuid,
+    role: model::WorkspaceRole,
+    not_found: &str,
+) -> Result<Space> {
+    let Some(space) = space else {
+        return Err(Status::aborted(not_found));
+    };
+
+    match workspace::get_role(db_conn, &space, account_id)
+        .map_err(|e| Status::internal(e.to_string()))?
+    {
+        Some(account_role) if account_role.includes(role) => Ok(space),
+        Some(_) => Err(Status::aborted(match role {
+            model::WorkspaceRole::Owner => "Only the owners of the workspace can do this.",
+            _ => "Only the editors and the owners of the workspace can do this.",
+        })),
+        None => Err(Status::aborted(not_found)),
+    }
+}
+
+/// Checks with the account service that the account exists, the accounts live in the account
+/// service so the links can't refer to them with a foreign key.
+async fn check_account(
+    account_service: &Option<transport::Channel>,
+    account_id: &Uuid,
+) -> Result<()> {
+    let Some(account_service) = account_service else {
+        return Err(Status::unavailable("The account service is not set."));
+    };
+
+    let res = AccountServiceClient::new(account_service.to_owned())
+        .get_account_emails(Request::new(GetAccountEmailsReq {
+            ids: vec![account_id.to_string()],
+        }))
+        .await?;
+    if res.get_ref().accounts.is_empty() {
+        return Err(Status::aborted("The account is not found."));
+    }
+
+    Ok(())
 }
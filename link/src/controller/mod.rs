@@ -1,3 +1,7 @@
+use lapin::Channel;
+use maxminddb::Reader;
+use rdkafka::producer::FutureProducer;
+use tonic::transport;
 use tools_lib_db::pg::connection::DbPool;
 
 mod link;
@@ -5,4 +9,11 @@ mod link;
 pub struct LinkController {
     pub app_mode: String,
     pub db_pool: DbPool,
+    pub geoip_reader: Option<Reader<Vec<u8>>>,
+    pub http_client: reqwest::Client,
+    pub og_fetch_allow_private_hosts: bool,
+    pub report_rate_limit: i64,
+    pub account_service: Option<transport::Channel>,
+    pub kafka_producer: Option<FutureProducer>,
+    pub rabbitmq_channel: Option<Channel>,
 }
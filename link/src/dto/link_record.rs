@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+pub struct LinkRecord {
+    pub title: String,
+    pub short_url: String,
+    pub long_url: String,
+}
@@ -0,0 +1 @@
+pub mod link_record;
@@ -1,4 +1,4 @@
-use std::env;
+use std::{env, time::Duration};
 
 pub struct Env;
 
@@ -22,4 +22,92 @@ impl Env {
     pub fn database_url() -> String {
         env::var("DATABASE_URL").unwrap()
     }
+
+    pub fn geoip_database_path() -> Option<String> {
+        env::var("GEOIP_DATABASE_PATH").ok()
+    }
+
+    pub fn og_fetch_allow_private_hosts() -> bool {
+        env::var("OG_FETCH_ALLOW_PRIVATE_HOSTS")
+            .map(|value| value == "true")
+            .unwrap_or(false)
+    }
+
+    pub fn trash_retention_days() -> i64 {
+        env::var("TRASH_RETENTION_DAYS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(30)
+    }
+
+    /// How many links a visitor can report in an hour, the reports aren't limited when it is 0.
+    pub fn report_rate_limit() -> i64 {
+        env::var("REPORT_RATE_LIMIT")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(10)
+    }
+
+    /// How long a healthy link waits for its next check, no link is checked when it is 0.
+    pub fn health_check_interval() -> Duration {
+        Duration::from_secs(
+            env::var("HEALTH_CHECK_INTERVAL")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(6 * 60 * 60),
+        )
+    }
+
+    pub fn health_check_concurrency() -> usize {
+        env::var("HEALTH_CHECK_CONCURRENCY")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(8)
+    }
+
+    pub fn health_check_failure_threshold() -> i32 {
+        env::var("HEALTH_CHECK_FAILURE_THRESHOLD")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(3)
+    }
+
+    /// The account service checks the accounts the links are transferred to and the members
+    /// invited to a workspace, and gives the emails of the owners of the broken links. Neither
+    /// works without it.
+    pub fn account_service_addrs() -> Option<String> {
+        env::var("ACCOUNT_SERVICE_ADDRS").ok()
+    }
+
+    pub fn use_msg_broker() -> UseMsgBroker {
+        UseMsgBroker(env::var("USE_MSG_BROKER").unwrap())
+    }
+
+    pub fn kafka_addrs() -> String {
+        env::var("KAFKA_ADDRS").unwrap()
+    }
+
+    pub fn kafka_group_id() -> String {
+        env::var("KAFKA_GROUP_ID").unwrap()
+    }
+
+    pub fn kafka_msg_timeout() -> String {
+        env::var("KAFKA_MSG_TIMEOUT").unwrap()
+    }
+
+    pub fn rabbitmq_addrs() -> String {
+        env::var("RABBITMQ_ADDRS").unwrap()
+    }
+}
+
+pub struct UseMsgBroker(String);
+
+impl UseMsgBroker {
+    pub fn is_kafka(&self) -> bool {
+        self.0 == "KAFKA"
+    }
+
+    pub fn is_rabbitmq(&self) -> bool {
+        self.0 == "RABBITMQ"
+    }
 }
@@ -0,0 +1,188 @@
+use chrono::{Duration, Utc};
+use diesel::{pg::Pg, ExpressionMethods, OptionalExtension, PgConnection, QueryDsl, RunQueryDsl};
+use tools_link::contract::{LinkVisit, LinkVisited};
+use uuid::Uuid;
+
+use crate::{
+    helper::{link_validation, workspace::Space},
+    model, proto, schema,
+};
+
+/// Gets the space all the links are in, `None` when some of them don't exist, are in the
+/// trash or are spread over spaces.
+pub fn get_links_space(
+    db_conn: &mut PgConnection,
+    link_ids: &[Uuid],
+) -> Result<Option<Space>, diesel::result::Error> {
+    let owners = schema::link::table
+        .filter(schema::link::id.eq_any(link_ids))
+        .filter(schema::link::deleted_at.is_null())
+        .select((schema::link::created_by_id, schema::link::workspace_id))
+        .load::<(Uuid, Option<Uuid>)>(db_conn)?;
+    if owners.len() != link_ids.len() {
+        return Ok(None);
+    }
+
+    Ok(Space::common(owners.into_iter().map(
+        |(created_by_id, workspace_id)| Space::new(created_by_id, workspace_id),
+    )))
+}
+
+/// Gets the space of a link in the trash.
+pub fn get_deleted_link_space(
+    db_conn: &mut PgConnection,
+    link_id: &Uuid,
+) -> Result<Option<Space>, diesel::result::Error> {
+    let owner = schema::link::table
+        .find(link_id)
+        .filter(schema::link::deleted_at.is_not_null())
+        .select((schema::link::created_by_id, schema::link::workspace_id))
+        .first::<(Uuid, Option<Uuid>)>(db_conn)
+        .optional()?;
+
+    Ok(owner.map(|(created_by_id, workspace_id)| Space::new(created_by_id, workspace_id)))
+}
+
+/// Checks if a link that isn't in the trash uses the short url.
+pub fn is_short_url_used(
+    db_conn: &mut PgConnection,
+    short_url: &str,
+) -> Result<bool, diesel::result::Error> {
+    diesel::select(diesel::dsl::exists(
+        schema::link::table
+            .filter(schema::link::short_url.eq(short_url))
+            .filter(schema::link::deleted_at.is_null()),
+    ))
+    .get_result::<bool>(db_conn)
+}
+
+/// Deletes the links that have been in the trash for longer than the retention period.
+pub fn purge_deleted_links(
+    db_conn: &mut PgConnection,
+    retention_days: i64,
+) -> Result<usize, diesel::result::Error> {
+    diesel::delete(schema::link::table.filter(
+        schema::link::deleted_at.lt(Utc::now().naive_utc() - Duration::days(retention_days)),
+    ))
+    .execute(db_conn)
+}
+
+pub fn filter_by_space<'a>(
+    query: schema::link::BoxedQuery<'a, Pg>,
+    space: &Space,
+) -> schema::link::BoxedQuery<'a, Pg> {
+    match *space {
+        Space::Personal(created_by_id) => query
+            .filter(schema::link::created_by_id.eq(created_by_id))
+            .filter(schema::link::workspace_id.is_null()),
+        Space::Workspace(workspace_id) => query.filter(schema::link::workspace_id.eq(workspace_id)),
+    }
+}
+
+/// Parses a list of ids, dropping duplicates.
+pub fn parse_ids(ids: &[String]) -> Result<Vec<Uuid>, uuid::Error> {
+    let mut parsed_ids = Vec::with_capacity(ids.len());
+    for id in ids {
+        let id = id.parse::<Uuid>()?;
+        if !parsed_ids.contains(&id) {
+            parsed_ids.push(id);
+        }
+    }
+
+    Ok(parsed_ids)
+}
+
+fn non_empty_value(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+pub fn utm_from_grpc(utm: &proto::link::LinkUtm) -> model::LinkUtm {
+    model::LinkUtm {
+        utm_source: non_empty_value(&utm.source),
+        utm_medium: non_empty_value(&utm.medium),
+        utm_campaign: non_empty_value(&utm.campaign),
+        utm_term: non_empty_value(&utm.term),
+        utm_content: non_empty_value(&utm.content),
+    }
+}
+
+pub fn deep_link_from_grpc(
+    deep_link: &proto::link::LinkDeepLink,
+) -> Result<model::LinkDeepLink, String> {
+    let deep_link = model::LinkDeepLink {
+        app_uri: non_empty_value(&deep_link.app_uri),
+        android_package: non_empty_value(&deep_link.android_package),
+        ios_fallback_url: non_empty_value(&deep_link.ios_fallback_url),
+    };
+    if let Some(app_uri) = &deep_link.app_uri {
+        link_validation::validate_app_uri(app_uri)?;
+    }
+    if let Some(android_package) = &deep_link.android_package {
+        if deep_link.app_uri.is_none() {
+            return Err("The Android package needs an app uri.".to_string());
+        }
+        link_validation::validate_android_package(android_package)?;
+    }
+    if let Some(ios_fallback_url) = &deep_link.ios_fallback_url {
+        link_validation::validate_long_url(ios_fallback_url)?;
+    }
+
+    Ok(deep_link)
+}
+
+pub fn og_metadata_override_from_grpc(
+    og_metadata: &proto::link::LinkOgMetadata,
+) -> model::LinkOgMetadataOverride {
+    model::LinkOgMetadataOverride {
+        og_title_override: non_empty_value(&og_metadata.title),
+        og_description_override: non_empty_value(&og_metadata.description),
+        og_image_override: non_empty_value(&og_metadata.image),
+    }
+}
+
+pub fn get_short_url(
+    db_conn: &mut PgConnection,
+    link_id: &Uuid,
+) -> Result<String, diesel::result::Error> {
+    schema::link::table
+        .find(link_id)
+        .select(schema::link::short_url)
+        .first::<String>(db_conn)
+}
+
+/// Counts the visits that were served without the link service, and gives the visits of the
+/// links that are still live.
+pub fn add_visits(
+    db_conn: &mut PgConnection,
+    visits: &[LinkVisit],
+) -> Result<Vec<LinkVisited>, diesel::result::Error> {
+    let mut visited = vec![];
+    for visit in visits {
+        let link = diesel::update(
+            schema::link::table
+                .filter(schema::link::short_url.eq(&visit.short_url))
+                .filter(schema::link::deleted_at.is_null()),
+        )
+        .set((
+            schema::link::visits.eq(schema::link::visits + visit.count),
+            schema::link::updated_at.eq(diesel::dsl::now),
+        ))
+        .returning((schema::link::id, schema::link::visits))
+        .get_result::<(Uuid, i32)>(db_conn)
+        .optional()?;
+        if let Some((link_id, link_visits)) = link {
+            visited.push(LinkVisited {
+                link_id: link_id.to_string(),
+                short_url: visit.short_url.to_owned(),
+                count: visit.count,
+                visits: link_visits,
+            });
+        }
+    }
+
+    Ok(visited)
+}
@@ -0,0 +1,182 @@
+use std::borrow::Cow;
+
+/// A query pair, the decoded key is used for matching while the raw pair is
+/// written back as is so that the original encoding is preserved.
+struct QueryPair<'a> {
+    key: Cow<'a, str>,
+    raw: Cow<'a, str>,
+}
+
+fn split_query(query: &str) -> Vec<QueryPair<'_>> {
+    query
+        .split('&')
+        .filter(|raw| !raw.is_empty())
+        .map(|raw| {
+            let raw_key = raw.split('=').next().unwrap_or_default();
+            let key = form_urlencoded::parse(raw_key.as_bytes())
+                .next()
+                .map(|(key, _)| key)
+                .unwrap_or_default();
+            QueryPair {
+                key,
+                raw: Cow::Borrowed(raw),
+            }
+        })
+        .collect()
+}
+
+/// Sets the pair in place of the first pair with the same key and drops the
+/// other pairs with that key, or appends it when the key is new.
+fn set_pair<'a>(pairs: &mut Vec<QueryPair<'a>>, pair: QueryPair<'a>) {
+    match pairs.iter().position(|p| p.key == pair.key) {
+        Some(index) => {
+            let mut current_index = 0;
+            pairs.retain(|p| {
+                let is_kept = current_index <= index || p.key != pair.key;
+                current_index += 1;
+                is_kept
+            });
+            pairs[index] = pair;
+        }
+        None => pairs.push(pair),
+    }
+}
+
+/// Builds the url a visitor is redirected to.
+///
+/// The query of the long url is kept, the UTM parameters override the pairs
+/// with the same key, and the forwarded query overrides both. The fragment of
+/// the long url stays at the end.
+pub fn build(long_url: &str, utm_params: &[(&str, &str)], forwarded_query: Option<&str>) -> String {
+    let forwarded_pairs = forwarded_query.map(split_query).unwrap_or_default();
+    if utm_params.is_empty() && forwarded_pairs.is_empty() {
+        return long_url.to_owned();
+    }
+
+    let (url, fragment) = match long_url.find('#') {
+        Some(index) => long_url.split_at(index),
+        None => (long_url, ""),
+    };
+    let (base_url, query) = match url.find('?') {
+        Some(index) => (&url[..index], &url[index + 1..]),
+        None => (url, ""),
+    };
+
+    let mut pairs = split_query(query);
+    for (key, value) in utm_params {
+        set_pair(
+            &mut pairs,
+            QueryPair {
+                key: Cow::Borrowed(key),
+                raw: Cow::Owned(
+                    form_urlencoded::Serializer::new(String::new())
+                        .append_pair(key, value)
+                        .finish(),
+                ),
+            },
+        );
+    }
+    for pair in forwarded_pairs {
+        set_pair(&mut pairs, pair);
+    }
+
+    let mut destination_url = base_url.to_owned();
+    if !pairs.is_empty() {
+        destination_url.push('?');
+        destination_url.push_str(
+            &pairs
+                .iter()
+                .map(|pair| pair.raw.as_ref())
+                .collect::<Vec<&str>>()
+                .join("&"),
+        );
+    }
+    destination_url.push_str(fragment);
+
+    destination_url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build;
+
+    #[test]
+    fn keeps_the_query_and_the_fragment_of_the_long_url() {
+        assert_eq!(
+            build(
+                "https://example.com/page?a=1#top",
+                &[("utm_source", "news")],
+                None
+            ),
+            "https://example.com/page?a=1&utm_source=news#top"
+        );
+    }
+
+    #[test]
+    fn replaces_the_first_duplicate_key_and_drops_the_others() {
+        assert_eq!(
+            build("https://example.com/?a=1&b=2&a=3", &[("a", "x")], None),
+            "https://example.com/?a=x&b=2"
+        );
+        assert_eq!(
+            build("https://example.com/", &[], Some("a=1&a=2")),
+            "https://example.com/?a=2"
+        );
+    }
+
+    #[test]
+    fn matches_percent_encoded_keys_and_plus() {
+        assert_eq!(
+            build(
+                "https://example.com/?utm%5Fsource=old&q=a+b",
+                &[("utm_source", "new")],
+                None
+            ),
+            "https://example.com/?utm_source=new&q=a+b"
+        );
+        assert_eq!(
+            build("https://example.com/?my%20key=0", &[], Some("my+key=1")),
+            "https://example.com/?my+key=1"
+        );
+    }
+
+    #[test]
+    fn encodes_the_utm_values() {
+        assert_eq!(
+            build(
+                "https://example.com/",
+                &[("utm_campaign", "a&b=c"), ("utm_term", "café au lait")],
+                None
+            ),
+            "https://example.com/?utm_campaign=a%26b%3Dc&utm_term=caf%C3%A9+au+lait"
+        );
+    }
+
+    #[test]
+    fn lets_the_forwarded_query_override_the_utm_parameters() {
+        assert_eq!(
+            build(
+                "https://example.com/?utm_source=site",
+                &[("utm_source", "news"), ("utm_medium", "email")],
+                Some("utm_source=ad&x=1")
+            ),
+            "https://example.com/?utm_source=ad&utm_medium=email&x=1"
+        );
+    }
+
+    #[test]
+    fn handles_an_empty_trailing_question_mark() {
+        assert_eq!(
+            build("https://example.com/page?", &[("utm_source", "news")], None),
+            "https://example.com/page?utm_source=news"
+        );
+        assert_eq!(
+            build("https://example.com/page?", &[], Some("")),
+            "https://example.com/page?"
+        );
+        assert_eq!(
+            build("https://example.com/page?#top", &[], Some("&")),
+            "https://example.com/page?#top"
+        );
+    }
+}
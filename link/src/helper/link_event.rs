@@ -0,0 +1,69 @@
+use lapin::Channel;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use serde::Serialize;
+use tools_link::contract::{LinkEvent, LinkVisited, LINK_EVENT_TOPIC, LINK_VISITED_TOPIC};
+
+/// Publishes link events so that the link resolvers drop what they cached for the short
+/// urls. The change is already saved when this is called, so a failed publish is only
+/// logged and the cached links are left to expire.
+pub async fn publish(
+    kafka_producer: &Option<FutureProducer>,
+    rabbitmq_channel: &Option<Channel>,
+    events: &[LinkEvent],
+) {
+    if events.is_empty() {
+        return;
+    }
+
+    if let Err(e) = send(kafka_producer, rabbitmq_channel, LINK_EVENT_TOPIC, events).await {
+        eprintln!("Failed to publish link events: {e}");
+    }
+}
+
+/// Publishes the counted visits for the live dashboards, they are only logged when they
+/// can't be published since the visits are already counted.
+pub async fn publish_visited(
+    kafka_producer: &Option<FutureProducer>,
+    rabbitmq_channel: &Option<Channel>,
+    visits: &[LinkVisited],
+) {
+    if visits.is_empty() {
+        return;
+    }
+
+    if let Err(e) = send(kafka_producer, rabbitmq_channel, LINK_VISITED_TOPIC, visits).await {
+        eprintln!("Failed to publish link visits: {e}");
+    }
+}
+
+async fn send<T: Serialize>(
+    kafka_producer: &Option<FutureProducer>,
+    rabbitmq_channel: &Option<Channel>,
+    topic: &str,
+    messages: &[T],
+) -> Result<(), String> {
+    let payload = serde_json::to_string(messages).map_err(|e| e.to_string())?;
+    if let Some(kafka_producer) = kafka_producer {
+        kafka_producer
+            .send_result(FutureRecord::<(), String>::to(topic).payload(&payload))
+            .map_err(|e| e.0.to_string())?
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.0.to_string())?;
+    } else if let Some(rabbitmq_channel) = rabbitmq_channel {
+        rabbitmq_channel
+            .basic_publish(
+                topic,
+                "",
+                lapin::options::BasicPublishOptions::default(),
+                payload.as_bytes(),
+                lapin::BasicProperties::default(),
+            )
+            .await
+            .map_err(|e| e.to_string())?
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
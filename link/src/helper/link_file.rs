@@ -0,0 +1,46 @@
+use std::error::Error;
+
+use crate::{dto::link_record::LinkRecord, proto::link::LinkFileFormat};
+
+pub fn content_type(format: LinkFileFormat) -> &'static str {
+    match format {
+        LinkFileFormat::Csv => "text/csv",
+        LinkFileFormat::Json => "application/json",
+    }
+}
+
+/// Parses an import file into records. A malformed file fails as a whole,
+/// while a malformed row only fails that row.
+pub fn parse(
+    format: LinkFileFormat,
+    content: &[u8],
+) -> Result<Vec<Result<LinkRecord, String>>, Box<dyn Error + Send + Sync>> {
+    match format {
+        LinkFileFormat::Csv => Ok(csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(content)
+            .deserialize::<LinkRecord>()
+            .map(|record| record.map_err(|e| e.to_string()))
+            .collect()),
+        LinkFileFormat::Json => Ok(serde_json::from_slice::<Vec<serde_json::Value>>(content)?
+            .into_iter()
+            .map(|record| serde_json::from_value::<LinkRecord>(record).map_err(|e| e.to_string()))
+            .collect()),
+    }
+}
+
+pub fn serialize(
+    format: LinkFileFormat,
+    records: &[LinkRecord],
+) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+    match format {
+        LinkFileFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            for record in records {
+                writer.serialize(record)?;
+            }
+            Ok(writer.into_inner()?)
+        }
+        LinkFileFormat::Json => Ok(serde_json::to_vec_pretty(records)?),
+    }
+}
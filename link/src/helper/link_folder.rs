@@ -0,0 +1,61 @@
+use diesel::{pg::Pg, ExpressionMethods, OptionalExtension, PgConnection, QueryDsl, RunQueryDsl};
+use uuid::Uuid;
+
+use crate::{helper::workspace::Space, schema};
+
+/// Gets the space a folder is in, `None` when it doesn't exist.
+pub fn get_folder_space(
+    db_conn: &mut PgConnection,
+    folder_id: &Uuid,
+) -> Result<Option<Space>, diesel::result::Error> {
+    let owner = schema::link_folder::table
+        .find(folder_id)
+        .select((
+            schema::link_folder::created_by_id,
+            schema::link_folder::workspace_id,
+        ))
+        .first::<(Uuid, Option<Uuid>)>(db_conn)
+        .optional()?;
+
+    Ok(owner.map(|(created_by_id, workspace_id)| Space::new(created_by_id, workspace_id)))
+}
+
+pub fn filter_by_space<'a>(
+    query: schema::link_folder::BoxedQuery<'a, Pg>,
+    space: &Space,
+) -> schema::link_folder::BoxedQuery<'a, Pg> {
+    match *space {
+        Space::Personal(created_by_id) => query
+            .filter(schema::link_folder::created_by_id.eq(created_by_id))
+            .filter(schema::link_folder::workspace_id.is_null()),
+        Space::Workspace(workspace_id) => {
+            query.filter(schema::link_folder::workspace_id.eq(workspace_id))
+        }
+    }
+}
+
+/// Gets the id of the folder followed by the ids of all of its subfolders.
+pub fn get_folder_tree_ids(
+    db_conn: &mut PgConnection,
+    folder_id: &Uuid,
+    space: &Space,
+) -> Result<Vec<Uuid>, diesel::result::Error> {
+    let folders = filter_by_space(schema::link_folder::table.into_boxed(), space)
+        .select((schema::link_folder::id, schema::link_folder::parent_id))
+        .load::<(Uuid, Option<Uuid>)>(db_conn)?;
+
+    let mut folder_ids = vec![*folder_id];
+    let mut index = 0;
+    while index < folder_ids.len() {
+        let parent_id = folder_ids[index];
+        folder_ids.extend(
+            folders
+                .iter()
+                .filter(|(_, folder_parent_id)| *folder_parent_id == Some(parent_id))
+                .map(|(id, _)| *id),
+        );
+        index += 1;
+    }
+
+    Ok(folder_ids)
+}
@@ -0,0 +1,408 @@
+use std::time::{Duration, Instant};
+
+use chrono::{NaiveDateTime, Utc};
+use diesel::{
+    BoolExpressionMethods, Connection, ExpressionMethods, OptionalExtension, PgConnection,
+    PgSortExpressionMethods, QueryDsl, RunQueryDsl,
+};
+use reqwest::{Client, Method, Url};
+use uuid::Uuid;
+
+use crate::{
+    helper::{link_og_metadata, workspace},
+    schema,
+};
+
+/// How long a claimed link is left to the checker that claimed it before another one can
+/// claim it again.
+const CLAIM_LEASE: Duration = Duration::from_secs(10 * 60);
+const RETRY_MIN_DELAY: Duration = Duration::from_secs(5 * 60);
+const ERROR_MAX_LEN: usize = 300;
+
+pub struct Check {
+    pub status_code: Option<u16>,
+    pub latency_ms: i32,
+    pub error: Option<String>,
+}
+
+impl Check {
+    /// Redirects count as healthy, the client only follows the ones to public hosts.
+    pub fn is_healthy(&self) -> bool {
+        self.error.is_none() && matches!(self.status_code, Some(status_code) if status_code < 400)
+    }
+}
+
+async fn send(client: &Client, method: Method, url: &Url) -> (Result<u16, String>, Duration) {
+    let started_at = Instant::now();
+    let res = client
+        .request(method, url.to_owned())
+        .send()
+        .await
+        .map(|res| res.status().as_u16())
+        .map_err(|e| e.to_string());
+
+    (res, started_at.elapsed())
+}
+
+/// Requests the long url of a link with HEAD, and with GET when HEAD fails since some
+/// servers don't answer it. The body of the page isn't read.
+pub async fn check(client: &Client, long_url: &str, allow_private_hosts: bool) -> Check {
+    let url = match Url::parse(long_url) {
+        Ok(url) if allow_private_hosts || link_og_metadata::is_public_url(&url) => url,
+        Ok(_) => {
+            return Check {
+                status_code: None,
+                latency_ms: 0,
+                error: Some("The url doesn't point to a public host.".to_string()),
+            }
+        }
+        Err(e) => {
+            return Check {
+                status_code: None,
+                latency_ms: 0,
+                error: Some(e.to_string()),
+            }
+        }
+    };
+
+    let (res, latency) = match send(client, Method::HEAD, &url).await {
+        (Ok(status_code), latency) if status_code < 400 => (Ok(status_code), latency),
+        _ => send(client, Method::GET, &url).await,
+    };
+
+    let latency_ms = i32::try_from(latency.as_millis()).unwrap_or(i32::MAX);
+    match res {
+        Ok(status_code) => Check {
+            status_code: Some(status_code),
+            latency_ms,
+            error: None,
+        },
+        Err(e) => Check {
+            status_code: None,
+            latency_ms,
+            error: Some(e.chars().take(ERROR_MAX_LEN).collect()),
+        },
+    }
+}
+
+/// Claims the links that are due for a check, the links in the trash, disabled or expired
+/// are left out. The claimed links aren't due again until the lease is over, so that the
+/// other instances of the service don't check them too.
+pub fn claim_due_links(
+    db_conn: &mut PgConnection,
+    limit: i64,
+) -> Result<Vec<(Uuid, String)>, diesel::result::Error> {
+    db_conn.transaction(|db_conn| {
+        let now = Utc::now().naive_utc();
+        let links = schema::link::table
+            .filter(schema::link::deleted_at.is_null())
+            .filter(schema::link::disabled_at.is_null())
+            .filter(
+                schema::link::expires_at
+                    .is_null()
+                    .or(schema::link::expires_at.gt(now)),
+            )
+            .filter(
+                schema::link::health_next_check_at
+                    .is_null()
+                    .or(schema::link::health_next_check_at.le(now)),
+            )
+            .order(schema::link::health_next_check_at.asc().nulls_first())
+            .select((schema::link::id, schema::link::long_url))
+            .limit(limit)
+            .for_update()
+            .skip_locked()
+            .load::<(Uuid, String)>(db_conn)?;
+
+        let link_ids = links.iter().map(|(id, _)| *id).collect::<Vec<Uuid>>();
+        diesel::update(schema::link::table.filter(schema::link::id.eq_any(&link_ids)))
+            .set(schema::link::health_next_check_at.eq(now + lease(CLAIM_LEASE)))
+            .execute(db_conn)?;
+
+        Ok(links)
+    })
+}
+
+fn lease(duration: Duration) -> chrono::Duration {
+    chrono::Duration::from_std(duration).unwrap_or_else(|_| chrono::Duration::days(1))
+}
+
+/// Waits longer after each failure in a row, but never longer than a healthy link waits.
+fn retry_delay(failures: i32, interval: Duration) -> Duration {
+    let exponent = failures.clamp(1, 16) as u32 - 1;
+    RETRY_MIN_DELAY
+        .saturating_mul(1 << exponent)
+        .min(interval.max(RETRY_MIN_DELAY))
+}
+
+/// The failed checks in a row of a link and when it was flagged as broken.
+#[derive(Debug, PartialEq)]
+struct Health {
+    failures: i32,
+    broken_at: Option<NaiveDateTime>,
+}
+
+impl Health {
+    /// Gives the health after the check and how long the link waits for the next one, with
+    /// true when the failures have just reached the threshold.
+    fn after(
+        &self,
+        check: &Check,
+        interval: Duration,
+        failure_threshold: i32,
+        now: NaiveDateTime,
+    ) -> (Health, Duration, bool) {
+        let (failures, next_check_in) = match check.is_healthy() {
+            true => (0, interval),
+            false => (self.failures + 1, retry_delay(self.failures + 1, interval)),
+        };
+        let is_flagged = self.broken_at.is_none() && failures >= failure_threshold;
+        let broken_at = match (failures, self.broken_at) {
+            (0, _) => None,
+            (_, Some(broken_at)) => Some(broken_at),
+            (_, None) => is_flagged.then_some(now),
+        };
+
+        (
+            Health {
+                failures,
+                broken_at,
+            },
+            next_check_in,
+            is_flagged,
+        )
+    }
+}
+
+/// Saves a check and schedules the next one. Gives true when the failures of the link have
+/// just reached the threshold, which flags it as broken until a check succeeds.
+pub fn record(
+    db_conn: &mut PgConnection,
+    link_id: &Uuid,
+    check: &Check,
+    interval: Duration,
+    failure_threshold: i32,
+) -> Result<bool, diesel::result::Error> {
+    db_conn.transaction(|db_conn| {
+        let health = schema::link::table
+            .find(link_id)
+            .select((
+                schema::link::health_failures,
+                schema::link::health_broken_at,
+            ))
+            .for_update()
+            .first::<(i32, Option<NaiveDateTime>)>(db_conn)
+            .optional()?;
+        let Some((failures, broken_at)) = health else {
+            return Ok(false);
+        };
+
+        let now = Utc::now().naive_utc();
+        let (health, next_check_in, is_flagged) = Health {
+            failures,
+            broken_at,
+        }
+        .after(check, interval, failure_threshold, now);
+
+        diesel::update(schema::link::table.find(link_id))
+            .set((
+                schema::link::health_status_code.eq(check.status_code.map(i32::from)),
+                schema::link::health_latency_ms.eq(check.latency_ms),
+                schema::link::health_error.eq(&check.error),
+                schema::link::health_checked_at.eq(now),
+                schema::link::health_failures.eq(health.failures),
+                schema::link::health_broken_at.eq(health.broken_at),
+                schema::link::health_next_check_at.eq(now + lease(next_check_in)),
+            ))
+            .execute(db_conn)?;
+
+        Ok(is_flagged)
+    })
+}
+
+/// Gets the short url and the long url of a link with the accounts to tell when it breaks,
+/// the account of a personal link or the owners of the workspace of the link.
+pub fn get_link_owners(
+    db_conn: &mut PgConnection,
+    link_id: &Uuid,
+) -> Result<Option<(String, String, Vec<Uuid>)>, diesel::result::Error> {
+    let link = schema::link::table
+        .find(link_id)
+        .select((
+            schema::link::short_url,
+            schema::link::long_url,
+            schema::link::created_by_id,
+            schema::link::workspace_id,
+        ))
+        .first::<(String, String, Uuid, Option<Uuid>)>(db_conn)
+        .optional()?;
+    let Some((short_url, long_url, created_by_id, workspace_id)) = link else {
+        return Ok(None);
+    };
+
+    let owner_ids = match workspace_id {
+        Some(workspace_id) => workspace::get_owner_ids(db_conn, &workspace_id)?,
+        None => vec![created_by_id],
+    };
+
+    Ok(Some((short_url, long_url, owner_ids)))
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::test_server::{Response, TestServer};
+
+    fn failed_check() -> Check {
+        Check {
+            status_code: Some(500),
+            latency_ms: 10,
+            error: None,
+        }
+    }
+
+    fn healthy_check() -> Check {
+        Check {
+            status_code: Some(200),
+            latency_ms: 10,
+            error: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn records_the_status_and_the_latency() {
+        let server = TestServer::start(Response {
+            delay: Duration::from_millis(150),
+            ..Response::default()
+        })
+        .await;
+
+        let check = check(&Client::new(), &server.url, true).await;
+        assert_eq!(check.status_code, Some(200));
+        assert!(check.latency_ms >= 150, "latency {}", check.latency_ms);
+        assert_eq!(check.error, None);
+        assert!(check.is_healthy());
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_get_when_head_fails() {
+        let server = TestServer::start(Response {
+            status: 204,
+            head_status: Some(405),
+            ..Response::default()
+        })
+        .await;
+
+        let check = check(&Client::new(), &server.url, true).await;
+        assert_eq!(check.status_code, Some(204));
+        assert!(check.is_healthy());
+    }
+
+    #[tokio::test]
+    async fn records_the_error_statuses_as_unhealthy() {
+        let server = TestServer::start(Response {
+            status: 503,
+            ..Response::default()
+        })
+        .await;
+
+        let check = check(&Client::new(), &server.url, true).await;
+        assert_eq!(check.status_code, Some(503));
+        assert_eq!(check.error, None);
+        assert!(!check.is_healthy());
+    }
+
+    #[tokio::test]
+    async fn records_the_error_of_an_unreachable_host() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        drop(listener);
+
+        let check = check(&Client::new(), &url, true).await;
+        assert_eq!(check.status_code, None);
+        assert!(check.error.is_some());
+        assert!(!check.is_healthy());
+    }
+
+    #[tokio::test]
+    async fn rejects_private_hosts() {
+        let server = TestServer::start(Response::default()).await;
+
+        let check = check(&Client::new(), &server.url, false).await;
+        assert_eq!(check.status_code, None);
+        assert_eq!(
+            check.error.as_deref(),
+            Some("The url doesn't point to a public host.")
+        );
+    }
+
+    #[test]
+    fn doubles_the_retry_delay_up_to_the_interval() {
+        let minute = Duration::from_secs(60);
+        let interval = 360 * minute;
+
+        assert_eq!(retry_delay(1, interval), 5 * minute);
+        assert_eq!(retry_delay(2, interval), 10 * minute);
+        assert_eq!(retry_delay(3, interval), 20 * minute);
+        assert_eq!(retry_delay(6, interval), 160 * minute);
+        assert_eq!(retry_delay(7, interval), 320 * minute);
+        assert_eq!(retry_delay(8, interval), interval);
+        assert_eq!(retry_delay(i32::MAX, interval), interval);
+        assert_eq!(retry_delay(3, minute), 5 * minute);
+    }
+
+    #[test]
+    fn flags_a_link_once_after_the_failure_threshold() {
+        let interval = Duration::from_secs(6 * 60 * 60);
+        let now = Utc::now().naive_utc();
+        let mut health = Health {
+            failures: 0,
+            broken_at: None,
+        };
+
+        let mut flags = vec![];
+        for _ in 0..4 {
+            let (next_health, next_check_in, is_flagged) =
+                health.after(&failed_check(), interval, 3, now);
+            assert_eq!(next_check_in, retry_delay(next_health.failures, interval));
+            flags.push(is_flagged);
+            health = next_health;
+        }
+        assert_eq!(flags, [false, false, true, false]);
+        assert_eq!(
+            health,
+            Health {
+                failures: 4,
+                broken_at: Some(now),
+            }
+        );
+
+        let (health, next_check_in, is_flagged) = health.after(&healthy_check(), interval, 3, now);
+        assert!(!is_flagged);
+        assert_eq!(next_check_in, interval);
+        assert_eq!(
+            health,
+            Health {
+                failures: 0,
+                broken_at: None,
+            }
+        );
+    }
+
+    #[test]
+    fn starts_over_after_a_healthy_check() {
+        let interval = Duration::from_secs(6 * 60 * 60);
+        let now = Utc::now().naive_utc();
+
+        let (health, _, _) = Health {
+            failures: 2,
+            broken_at: None,
+        }
+        .after(&healthy_check(), interval, 3, now);
+        let (health, _, is_flagged) = health.after(&failed_check(), interval, 3, now);
+        assert!(!is_flagged);
+        assert_eq!(health.failures, 1);
+    }
+}
@@ -0,0 +1,153 @@
+use diesel::{ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+use uuid::Uuid;
+
+use crate::{
+    dto::link_record::LinkRecord,
+    helper::{link, link_revision, link_validation, workspace::Space},
+    model,
+    proto::link::{ImportConflictStrategy, ImportLinksRowRes, ImportRowStatus},
+    schema,
+};
+
+const RENAME_MAX_ATTEMPTS: u32 = 1000;
+
+fn row_res(row: u32, short_url: &str, status: ImportRowStatus, message: &str) -> ImportLinksRowRes {
+    ImportLinksRowRes {
+        row,
+        short_url: short_url.to_owned(),
+        status: status.into(),
+        message: message.to_owned(),
+    }
+}
+
+pub fn invalid_row(row: u32, short_url: &str, message: &str) -> ImportLinksRowRes {
+    row_res(row, short_url, ImportRowStatus::Invalid, message)
+}
+
+pub fn failed_row(row: u32, short_url: &str, message: &str) -> ImportLinksRowRes {
+    row_res(row, short_url, ImportRowStatus::Failed, message)
+}
+
+fn insert_record(
+    db_conn: &mut PgConnection,
+    record: &LinkRecord,
+    short_url: &str,
+    created_by_id: &Uuid,
+    space: &Space,
+) -> Result<(), diesel::result::Error> {
+    let link = diesel::insert_into(schema::link::table)
+        .values((
+            schema::link::title.eq(&record.title),
+            schema::link::short_url.eq(short_url),
+            schema::link::long_url.eq(&record.long_url),
+            schema::link::created_by_id.eq(created_by_id),
+            schema::link::workspace_id.eq(space.workspace_id()),
+        ))
+        .get_result::<model::Link>(db_conn)?;
+
+    link_revision::record(db_conn, &link, created_by_id)
+}
+
+/// Imports a single row into a space. Validation problems and conflicts are
+/// reported in the returned row result, only database errors are returned as
+/// `Err` so that the caller can roll back the whole batch.
+pub fn import_record(
+    db_conn: &mut PgConnection,
+    row: u32,
+    record: &LinkRecord,
+    created_by_id: &Uuid,
+    space: &Space,
+    conflict_strategy: ImportConflictStrategy,
+) -> Result<ImportLinksRowRes, diesel::result::Error> {
+    // Validate the row
+    if let Err(e) = link_validation::validate_title(&record.title)
+        .and_then(|_| link_validation::validate_short_url(&record.short_url))
+        .and_then(|_| link_validation::validate_long_url(&record.long_url))
+    {
+        return Ok(invalid_row(row, &record.short_url, &e));
+    }
+
+    // Get the link that already uses the short url
+    let existing_link = schema::link::table
+        .filter(schema::link::short_url.eq(&record.short_url))
+        .filter(schema::link::deleted_at.is_null())
+        .select((
+            schema::link::id,
+            schema::link::created_by_id,
+            schema::link::workspace_id,
+        ))
+        .first::<(Uuid, Uuid, Option<Uuid>)>(db_conn);
+    let (existing_link_id, existing_link_space) = match existing_link {
+        Ok((id, created_by_id, workspace_id)) => (id, Space::new(created_by_id, workspace_id)),
+        Err(diesel::result::Error::NotFound) => {
+            insert_record(db_conn, record, &record.short_url, created_by_id, space)?;
+            return Ok(row_res(
+                row,
+                &record.short_url,
+                ImportRowStatus::Created,
+                "",
+            ));
+        }
+        Err(e) => return Err(e),
+    };
+
+    match conflict_strategy {
+        ImportConflictStrategy::Skip => Ok(row_res(
+            row,
+            &record.short_url,
+            ImportRowStatus::Skipped,
+            "The short url is already used.",
+        )),
+        ImportConflictStrategy::Overwrite => {
+            if existing_link_space != *space {
+                return Ok(row_res(
+                    row,
+                    &record.short_url,
+                    ImportRowStatus::Skipped,
+                    "The short url is used by a link outside of where the links are imported.",
+                ));
+            }
+
+            let link = diesel::update(schema::link::table.find(&existing_link_id))
+                .set((
+                    schema::link::title.eq(&record.title),
+                    schema::link::long_url.eq(&record.long_url),
+                    schema::link::updated_at.eq(diesel::dsl::now),
+                ))
+                .get_result::<model::Link>(db_conn)?;
+            link_revision::record(db_conn, &link, created_by_id)?;
+
+            Ok(row_res(
+                row,
+                &record.short_url,
+                ImportRowStatus::Updated,
+                "",
+            ))
+        }
+        ImportConflictStrategy::Rename => {
+            // Find the first free "<short_url>-<n>"
+            for n in 1..=RENAME_MAX_ATTEMPTS {
+                let short_url = format!("{}-{n}", record.short_url);
+                if link_validation::validate_short_url(&short_url).is_err() {
+                    break;
+                }
+                if !link::is_short_url_used(db_conn, &short_url)? {
+                    insert_record(db_conn, record, &short_url, created_by_id, space)?;
+                    return Ok(row_res(
+                        row,
+                        &short_url,
+                        ImportRowStatus::Renamed,
+                        &format!("Renamed from {}.", record.short_url),
+                    ));
+                }
+            }
+
+            Ok(row_res(
+                row,
+                &record.short_url,
+                ImportRowStatus::Skipped,
+                "Couldn't find a free short url to rename to.",
+            ))
+        }
+    }
+}
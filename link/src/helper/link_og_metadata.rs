@@ -0,0 +1,478 @@
+use std::{net::IpAddr, time::Duration};
+
+use chrono::Utc;
+use diesel::{QueryDsl, RunQueryDsl};
+use reqwest::{redirect, Client, Url};
+use tools_lib_db::pg::connection::DbPool;
+use uuid::Uuid;
+
+use crate::{model, proto, schema};
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+const FETCH_MAX_SIZE: usize = 1024 * 1024;
+const FETCH_MAX_REDIRECTS: usize = 5;
+const FETCH_USER_AGENT: &str = "Mozilla/5.0 (compatible; ToolsLinkBot/1.0)";
+
+const TITLE_MAX_LEN: usize = 300;
+const DESCRIPTION_MAX_LEN: usize = 1000;
+const IMAGE_MAX_LEN: usize = 2048;
+
+/// Checks if a url points to a host on the internet, so that links can't be used to make
+/// the service request its own network.
+pub fn is_public_url(url: &Url) -> bool {
+    match url.host() {
+        Some(url::Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+            domain != "localhost" && !domain.ends_with(".localhost")
+        }
+        Some(url::Host::Ipv4(ip)) => is_public_ip(&IpAddr::V4(ip)),
+        Some(url::Host::Ipv6(ip)) => is_public_ip(&IpAddr::V6(ip)),
+        None => false,
+    }
+}
+
+fn is_public_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast())
+        }
+        IpAddr::V6(ip) => {
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || (ip.segments()[0] & 0xfe00) == 0xfc00
+                || (ip.segments()[0] & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+pub fn create_client(allow_private_hosts: bool) -> reqwest::Result<Client> {
+    Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .user_agent(FETCH_USER_AGENT)
+        .redirect(redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= FETCH_MAX_REDIRECTS {
+                attempt.error("Too many redirects.")
+            } else if !allow_private_hosts && !is_public_url(attempt.url()) {
+                attempt.stop()
+            } else {
+                attempt.follow()
+            }
+        }))
+        .build()
+}
+
+/// Fetches the page of a url and reads its metadata. Only the first megabyte of an HTML
+/// page is read.
+pub async fn fetch(
+    client: &Client,
+    url: &str,
+    allow_private_hosts: bool,
+) -> Result<proto::link::LinkOgMetadata, String> {
+    let url = Url::parse(url).map_err(|e| e.to_string())?;
+    if !allow_private_hosts && !is_public_url(&url) {
+        return Err("The url doesn't point to a public host.".to_string());
+    }
+
+    let mut res = client
+        .get(url)
+        .header(reqwest::header::ACCEPT, "text/html,application/xhtml+xml")
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())?;
+
+    let is_html = res
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .map(|content_type| content_type.to_ascii_lowercase())
+        .map(|content_type| content_type.contains("html"))
+        .unwrap_or(false);
+    if !is_html {
+        return Err("The url doesn't point to an HTML page.".to_string());
+    }
+
+    let page_url = res.url().to_owned();
+    let mut body = Vec::new();
+    while let Some(chunk) = res.chunk().await.map_err(|e| e.to_string())? {
+        body.extend_from_slice(&chunk);
+        if body.len() >= FETCH_MAX_SIZE {
+            body.truncate(FETCH_MAX_SIZE);
+            break;
+        }
+    }
+
+    Ok(parse(&String::from_utf8_lossy(&body), &page_url))
+}
+
+/// Fetches the metadata of a link in the background and stores it. A failed fetch clears
+/// the metadata so that a page that changed doesn't keep its old card.
+pub fn refresh(
+    app_mode: String,
+    db_pool: DbPool,
+    client: Client,
+    allow_private_hosts: bool,
+    link_id: Uuid,
+    long_url: String,
+) {
+    tokio::spawn(async move {
+        let og_metadata = fetch(&client, &long_url, allow_private_hosts)
+            .await
+            .unwrap_or_default();
+
+        let Ok(db_conn) = &mut tools_lib_db::pg::connection::get_connection(&app_mode, &db_pool)
+        else {
+            return;
+        };
+        let _ = diesel::update(schema::link::table.find(&link_id))
+            .set(model::LinkOgMetadata {
+                og_title: og_metadata.title,
+                og_description: og_metadata.description,
+                og_image: og_metadata.image,
+                og_fetched_at: Some(Utc::now().naive_utc()),
+            })
+            .execute(db_conn);
+    });
+}
+
+/// Reads the title, the description and the image of an HTML page. Open Graph tags are
+/// preferred over Twitter tags, which are preferred over the plain HTML ones.
+pub fn parse(html: &str, page_url: &Url) -> proto::link::LinkOgMetadata {
+    // The metadata is in the head, don't look at the body when the head is closed
+    let lowercase_html = html.to_ascii_lowercase();
+    let head_end = lowercase_html.find("</head").unwrap_or(html.len());
+    let head = &html[..head_end];
+    let lowercase_head = &lowercase_html[..head_end];
+
+    let mut metas = Vec::<(String, String)>::new();
+    let mut index = 0;
+    while let Some(start) = lowercase_head[index..].find("<meta") {
+        let start = index + start + "<meta".len();
+        let end = lowercase_head[start..]
+            .find('>')
+            .map_or(head.len(), |end| start + end);
+        let attrs = parse_attrs(&head[start..end]);
+        let key = attrs
+            .iter()
+            .find(|(name, _)| name == "property" || name == "name")
+            .map(|(_, value)| value.to_ascii_lowercase());
+        let content = attrs
+            .iter()
+            .find(|(name, _)| name == "content")
+            .map(|(_, value)| value.to_owned());
+        if let (Some(key), Some(content)) = (key, content) {
+            metas.push((key, content));
+        }
+        index = end;
+    }
+    let meta = |keys: &[&str]| {
+        keys.iter().find_map(|key| {
+            metas
+                .iter()
+                .find(|(meta_key, content)| meta_key == key && !content.trim().is_empty())
+                .map(|(_, content)| content.to_owned())
+        })
+    };
+
+    let html_title = lowercase_head.find("<title").and_then(|start| {
+        let start = start + lowercase_head[start..].find('>')? + 1;
+        let end = start + lowercase_head[start..].find("</title")?;
+        Some(decode_entities(&head[start..end]))
+    });
+
+    proto::link::LinkOgMetadata {
+        title: clean(
+            meta(&["og:title", "twitter:title"]).or(html_title),
+            TITLE_MAX_LEN,
+        ),
+        description: clean(
+            meta(&["og:description", "twitter:description", "description"]),
+            DESCRIPTION_MAX_LEN,
+        ),
+        image: clean(
+            meta(&[
+                "og:image:secure_url",
+                "og:image",
+                "og:image:url",
+                "twitter:image",
+                "twitter:image:src",
+            ])
+            .and_then(|image| page_url.join(image.trim()).ok())
+            .filter(|image| image.scheme() == "http" || image.scheme() == "https")
+            .map(String::from),
+            IMAGE_MAX_LEN,
+        ),
+    }
+}
+
+/// Collapses whitespace and drops values that are empty or too long to be shown.
+fn clean(value: Option<String>, max_len: usize) -> Option<String> {
+    value
+        .map(|value| value.split_whitespace().collect::<Vec<&str>>().join(" "))
+        .filter(|value| !value.is_empty() && value.chars().count() <= max_len)
+}
+
+/// Parses the attributes of a tag, the names are lowercased and the values are decoded.
+fn parse_attrs(tag: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    let mut chars = tag.char_indices().peekable();
+    loop {
+        // Skip to the start of the name
+        while matches!(chars.peek(), Some((_, c)) if c.is_whitespace() || *c == '/') {
+            chars.next();
+        }
+        let Some(&(name_start, _)) = chars.peek() else {
+            break;
+        };
+        let mut name_end = tag.len();
+        while let Some(&(i, c)) = chars.peek() {
+            if c.is_whitespace() || c == '=' || c == '/' {
+                name_end = i;
+                break;
+            }
+            chars.next();
+        }
+        let name = tag[name_start..name_end].to_ascii_lowercase();
+
+        while matches!(chars.peek(), Some((_, c)) if c.is_whitespace()) {
+            chars.next();
+        }
+        if chars.peek().map(|(_, c)| *c) != Some('=') {
+            attrs.push((name, String::new()));
+            continue;
+        }
+        chars.next();
+        while matches!(chars.peek(), Some((_, c)) if c.is_whitespace()) {
+            chars.next();
+        }
+
+        let value = match chars.peek().map(|(_, c)| *c) {
+            Some(quote @ ('"' | '\'')) => {
+                let (value_start, _) = chars.next().unwrap();
+                let value_start = value_start + 1;
+                let mut value_end = tag.len();
+                for (i, c) in chars.by_ref() {
+                    if c == quote {
+                        value_end = i;
+                        break;
+                    }
+                }
+                &tag[value_start..value_end]
+            }
+            Some(_) => {
+                let (value_start, _) = *chars.peek().unwrap();
+                let mut value_end = tag.len();
+                while let Some(&(i, c)) = chars.peek() {
+                    if c.is_whitespace() {
+                        value_end = i;
+                        break;
+                    }
+                    chars.next();
+                }
+                &tag[value_start..value_end]
+            }
+            None => "",
+        };
+        attrs.push((name, decode_entities(value)));
+    }
+
+    attrs
+}
+
+/// Decodes the character references that are common in metadata.
+fn decode_entities(value: &str) -> String {
+    let mut decoded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest[1..]
+            .find(';')
+            .filter(|end| *end <= 10)
+            .map(|end| &rest[1..end + 1]);
+        let c = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(|dec| dec.parse::<u32>()))
+                .and_then(|code| code.ok())
+                .and_then(char::from_u32),
+        });
+        match (entity, c) {
+            (Some(entity), Some(c)) => {
+                decoded.push(c);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::test_server::{Body, Response, TestServer};
+
+    /// Serves the page on a local port and gives its url.
+    async fn serve(content_type: &'static str, body: Body) -> String {
+        TestServer::start(Response {
+            content_type,
+            body,
+            ..Response::default()
+        })
+        .await
+        .url
+    }
+
+    async fn fetch_local(url: &str) -> Result<proto::link::LinkOgMetadata, String> {
+        fetch(&create_client(true).unwrap(), url, true).await
+    }
+
+    #[tokio::test]
+    async fn prefers_og_tags_over_the_title() {
+        let url = serve(
+            "text/html; charset=utf-8",
+            Body::Fixed(
+                r#"<html><head><title>Plain title</title>
+                <meta property="og:title" content="Open Graph title">
+                <meta name="description" content="Plain description">
+                <meta property="og:description" content="Open Graph description">
+                </head><body></body></html>"#,
+            ),
+        )
+        .await;
+
+        let og_metadata = fetch_local(&url).await.unwrap();
+        assert_eq!(og_metadata.title.as_deref(), Some("Open Graph title"));
+        assert_eq!(
+            og_metadata.description.as_deref(),
+            Some("Open Graph description")
+        );
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_the_title() {
+        let url = serve(
+            "text/html",
+            Body::Fixed("<head><title>Tom &amp; Jerry</title></head>"),
+        )
+        .await;
+
+        let og_metadata = fetch_local(&url).await.unwrap();
+        assert_eq!(og_metadata.title.as_deref(), Some("Tom & Jerry"));
+        assert_eq!(og_metadata.description, None);
+        assert_eq!(og_metadata.image, None);
+    }
+
+    #[tokio::test]
+    async fn reads_quoted_and_unquoted_attributes() {
+        let url = serve(
+            "text/html",
+            Body::Fixed(
+                r#"<head>
+                <META PROPERTY='og:title' CONTENT='Single "quoted"'>
+                <meta property=og:image content=/images/card.png />
+                <meta name = "description" content = "Spaced &#x41;ttributes">
+                </head>"#,
+            ),
+        )
+        .await;
+
+        let og_metadata = fetch_local(&url).await.unwrap();
+        assert_eq!(og_metadata.title.as_deref(), Some(r#"Single "quoted""#));
+        assert_eq!(og_metadata.image, Some(format!("{url}images/card.png")));
+        assert_eq!(
+            og_metadata.description.as_deref(),
+            Some("Spaced Attributes")
+        );
+    }
+
+    #[test]
+    fn parses_attributes() {
+        assert_eq!(
+            parse_attrs(r#" a="double" b='single' c=bare d e = "spaced" /"#),
+            vec![
+                ("a".to_string(), "double".to_string()),
+                ("b".to_string(), "single".to_string()),
+                ("c".to_string(), "bare".to_string()),
+                ("d".to_string(), String::new()),
+                ("e".to_string(), "spaced".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_other_content_types() {
+        let url = serve(
+            "application/json",
+            Body::Fixed(r#"{"title": "Not a page"}"#),
+        )
+        .await;
+
+        assert_eq!(
+            fetch_local(&url).await.unwrap_err(),
+            "The url doesn't point to an HTML page."
+        );
+    }
+
+    #[tokio::test]
+    async fn stops_reading_after_the_max_size() {
+        let url = serve(
+            "text/html",
+            Body::Endless(r#"<head><meta property="og:title" content="Endless">"#),
+        )
+        .await;
+
+        let started_at = Instant::now();
+        let og_metadata = fetch_local(&url).await.unwrap();
+        assert_eq!(og_metadata.title.as_deref(), Some("Endless"));
+        assert!(started_at.elapsed() < FETCH_TIMEOUT);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_the_timeout() {
+        let url = TestServer::start(Response {
+            delay: FETCH_TIMEOUT * 4,
+            ..Response::default()
+        })
+        .await
+        .url;
+
+        let started_at = Instant::now();
+        assert!(fetch_local(&url).await.is_err());
+        let elapsed = started_at.elapsed();
+        assert!(elapsed >= FETCH_TIMEOUT && elapsed < FETCH_TIMEOUT * 2);
+    }
+
+    #[tokio::test]
+    async fn rejects_private_hosts() {
+        let url = serve("text/html", Body::Fixed("<title>Private</title>")).await;
+
+        assert_eq!(
+            fetch(&create_client(false).unwrap(), &url, false)
+                .await
+                .unwrap_err(),
+            "The url doesn't point to a public host."
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use chrono::NaiveDateTime;
+use diesel::{ExpressionMethods, OptionalExtension, PgConnection, QueryDsl, RunQueryDsl};
+use uuid::Uuid;
+
+use crate::{model, proto, schema};
+
+/// Gets the reports with the short url and the long url of their link, the oldest first.
+pub fn get_reports(
+    db_conn: &mut PgConnection,
+    status: Option<model::LinkReportStatus>,
+) -> Result<Vec<proto::link::LinkReport>, diesel::result::Error> {
+    let mut query = schema::link_report::table
+        .inner_join(schema::link::table)
+        .select((
+            (
+                schema::link_report::id,
+                schema::link_report::link_id,
+                schema::link_report::reason,
+                schema::link_report::details,
+                schema::link_report::reporter_ip,
+                schema::link_report::status,
+                schema::link_report::resolved_by_id,
+                schema::link_report::created_at,
+                schema::link_report::updated_at,
+            ),
+            schema::link::short_url,
+            schema::link::long_url,
+        ))
+        .order((
+            schema::link_report::created_at.asc(),
+            schema::link_report::id.asc(),
+        ))
+        .into_boxed();
+    if let Some(status) = status {
+        query = query.filter(schema::link_report::status.eq(status));
+    }
+
+    Ok(query
+        .load::<(model::LinkReport, String, String)>(db_conn)?
+        .into_iter()
+        .map(|(report, short_url, long_url)| report.to_grpc(short_url, long_url))
+        .collect())
+}
+
+/// Checks if the visitor already has a pending report of the link.
+pub fn is_report_pending(
+    db_conn: &mut PgConnection,
+    link_id: &Uuid,
+    reporter_ip: &str,
+) -> Result<bool, diesel::result::Error> {
+    schema::link_report::table
+        .filter(schema::link_report::link_id.eq(link_id))
+        .filter(schema::link_report::reporter_ip.eq(reporter_ip))
+        .filter(schema::link_report::status.eq(model::LinkReportStatus::Pending))
+        .select(schema::link_report::id)
+        .first::<Uuid>(db_conn)
+        .optional()
+        .map(|report_id| report_id.is_some())
+}
+
+/// Counts the reports the visitor sent since the time, of any link.
+pub fn count_reports_since(
+    db_conn: &mut PgConnection,
+    reporter_ip: &str,
+    since: &NaiveDateTime,
+) -> Result<i64, diesel::result::Error> {
+    schema::link_report::table
+        .filter(schema::link_report::reporter_ip.eq(reporter_ip))
+        .filter(schema::link_report::created_at.gt(since))
+        .count()
+        .get_result(db_conn)
+}
@@ -0,0 +1,38 @@
+use diesel::{ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+use uuid::Uuid;
+
+use crate::{model, schema};
+
+/// Records the title, the short url and the long url the link has now. Call it in the same
+/// transaction as the change so the history can't miss a write.
+pub fn record(
+    db_conn: &mut PgConnection,
+    link: &model::Link,
+    created_by_id: &Uuid,
+) -> Result<(), diesel::result::Error> {
+    diesel::insert_into(schema::link_revision::table)
+        .values((
+            schema::link_revision::link_id.eq(&link.id),
+            schema::link_revision::title.eq(&link.title),
+            schema::link_revision::short_url.eq(&link.short_url),
+            schema::link_revision::long_url.eq(&link.long_url),
+            schema::link_revision::created_by_id.eq(created_by_id),
+        ))
+        .execute(db_conn)?;
+
+    Ok(())
+}
+
+/// Gets the revisions of a link, the newest first.
+pub fn get_revisions_by_link_id(
+    db_conn: &mut PgConnection,
+    link_id: &Uuid,
+) -> Result<Vec<model::LinkRevision>, diesel::result::Error> {
+    schema::link_revision::table
+        .filter(schema::link_revision::link_id.eq(link_id))
+        .order((
+            schema::link_revision::created_at.desc(),
+            schema::link_revision::id.desc(),
+        ))
+        .load::<model::LinkRevision>(db_conn)
+}
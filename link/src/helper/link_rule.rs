@@ -0,0 +1,203 @@
+use std::net::{IpAddr, SocketAddr};
+
+use chrono::{DateTime, NaiveDateTime};
+use diesel::{ExpressionMethods, OptionalExtension, PgConnection, QueryDsl, RunQueryDsl};
+use maxminddb::{geoip2, Reader};
+use uuid::Uuid;
+
+use crate::{
+    helper::{link_validation, workspace::Space},
+    model, schema,
+};
+
+/// What the rules of a link are matched against.
+pub struct Visitor {
+    pub platform: Option<model::LinkRulePlatform>,
+    pub country: Option<String>,
+    pub language: Option<String>,
+}
+
+impl Visitor {
+    pub fn new(
+        user_agent: Option<&str>,
+        ip_address: Option<&str>,
+        accept_language: Option<&str>,
+        geoip_reader: Option<&Reader<Vec<u8>>>,
+    ) -> Self {
+        Self {
+            platform: user_agent.and_then(detect_platform),
+            country: geoip_reader
+                .zip(ip_address)
+                .and_then(|(geoip_reader, ip_address)| lookup_country(geoip_reader, ip_address)),
+            language: accept_language.and_then(preferred_language),
+        }
+    }
+}
+
+fn detect_platform(user_agent: &str) -> Option<model::LinkRulePlatform> {
+    let user_agent = user_agent.to_ascii_lowercase();
+
+    // iOS and Android user agents also mention Mac OS X and Linux, so they are checked first
+    if ["iphone", "ipad", "ipod"]
+        .iter()
+        .any(|device| user_agent.contains(device))
+    {
+        Some(model::LinkRulePlatform::Ios)
+    } else if user_agent.contains("android") {
+        Some(model::LinkRulePlatform::Android)
+    } else if user_agent.contains("windows") {
+        Some(model::LinkRulePlatform::Windows)
+    } else if user_agent.contains("macintosh") || user_agent.contains("mac os x") {
+        Some(model::LinkRulePlatform::Macos)
+    } else if user_agent.contains("linux") {
+        Some(model::LinkRulePlatform::Linux)
+    } else {
+        None
+    }
+}
+
+/// Gets the primary subtag of the language with the highest quality value.
+fn preferred_language(accept_language: &str) -> Option<String> {
+    let mut preferred: Option<(&str, f32)> = None;
+    for item in accept_language.split(',') {
+        let mut parts = item.split(';').map(str::trim);
+        let tag = parts.next().unwrap_or_default();
+        let quality = parts
+            .find_map(|part| part.strip_prefix("q="))
+            .map_or(Some(1.0), |quality| quality.parse::<f32>().ok());
+        let Some(quality) = quality else {
+            continue;
+        };
+        if tag.is_empty() || tag == "*" || quality <= 0.0 {
+            continue;
+        }
+        if !matches!(preferred, Some((_, preferred_quality)) if preferred_quality >= quality) {
+            preferred = Some((tag, quality));
+        }
+    }
+
+    preferred
+        .and_then(|(tag, _)| tag.split('-').next())
+        .filter(|language| link_validation::validate_language(language).is_ok())
+        .map(str::to_ascii_lowercase)
+}
+
+/// Gets the ISO 3166-1 alpha-2 code of the country of an ip address, which may
+/// have a port.
+fn lookup_country(geoip_reader: &Reader<Vec<u8>>, ip_address: &str) -> Option<String> {
+    let ip_address = ip_address
+        .parse::<IpAddr>()
+        .or_else(|_| {
+            ip_address
+                .parse::<SocketAddr>()
+                .map(|socket_addr| socket_addr.ip())
+        })
+        .ok()?;
+
+    geoip_reader
+        .lookup::<geoip2::Country>(ip_address)
+        .ok()?
+        .country?
+        .iso_code
+        .map(str::to_string)
+}
+
+/// Checks if every condition of the rule matches, a condition that isn't set
+/// matches any visitor.
+pub fn is_rule_matched(rule: &model::LinkRule, visitor: &Visitor, now: &NaiveDateTime) -> bool {
+    (rule.platform.is_none() || rule.platform == visitor.platform)
+        && (rule.country.is_none() || rule.country == visitor.country)
+        && (rule.language.is_none() || rule.language == visitor.language)
+        && !matches!(rule.starts_at, Some(starts_at) if starts_at > *now)
+        && !matches!(rule.ends_at, Some(ends_at) if ends_at <= *now)
+}
+
+fn condition_value(value: &Option<String>) -> Option<&str> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn parse_time(time: &Option<String>) -> Result<Option<NaiveDateTime>, String> {
+    condition_value(time)
+        .map(|time| {
+            DateTime::parse_from_rfc3339(time)
+                .map(|time| time.naive_utc())
+                .map_err(|_| "The time must be an RFC 3339 date and time.".to_string())
+        })
+        .transpose()
+}
+
+pub fn parse_change_set(
+    platform: Option<i32>,
+    country: &Option<String>,
+    language: &Option<String>,
+    starts_at: &Option<String>,
+    ends_at: &Option<String>,
+    destination_url: &str,
+) -> Result<model::LinkRuleChangeSet, String> {
+    let platform = platform
+        .map(|platform| {
+            model::LinkRulePlatform::from_grpc_enum(platform)
+                .ok_or_else(|| "The platform is unknown.".to_string())
+        })
+        .transpose()?;
+    let country = condition_value(country)
+        .map(|country| link_validation::validate_country(country).map(|_| country.to_uppercase()))
+        .transpose()?;
+    let language = condition_value(language)
+        .map(|language| {
+            link_validation::validate_language(language).map(|_| language.to_lowercase())
+        })
+        .transpose()?;
+    let starts_at = parse_time(starts_at)?;
+    let ends_at = parse_time(ends_at)?;
+    if let (Some(starts_at), Some(ends_at)) = (starts_at, ends_at) {
+        if starts_at >= ends_at {
+            return Err("The start time must be before the end time.".to_string());
+        }
+    }
+    link_validation::validate_long_url(destination_url)?;
+
+    Ok(model::LinkRuleChangeSet {
+        platform,
+        country,
+        language,
+        starts_at,
+        ends_at,
+        destination_url: destination_url.to_string(),
+    })
+}
+
+pub fn get_rules_by_link_id(
+    db_conn: &mut PgConnection,
+    link_id: &Uuid,
+) -> Result<Vec<model::LinkRule>, diesel::result::Error> {
+    schema::link_rule::table
+        .filter(schema::link_rule::link_id.eq(link_id))
+        .order(schema::link_rule::position.asc())
+        .load::<model::LinkRule>(db_conn)
+}
+
+/// Gets the link id of a rule with the space of the link.
+pub fn get_rule_link(
+    db_conn: &mut PgConnection,
+    rule_id: &Uuid,
+) -> Result<Option<(Uuid, Space)>, diesel::result::Error> {
+    let link = schema::link_rule::table
+        .inner_join(schema::link::table)
+        .filter(schema::link_rule::id.eq(rule_id))
+        .filter(schema::link::deleted_at.is_null())
+        .select((
+            schema::link_rule::link_id,
+            schema::link::created_by_id,
+            schema::link::workspace_id,
+        ))
+        .first::<(Uuid, Uuid, Option<Uuid>)>(db_conn)
+        .optional()?;
+
+    Ok(link.map(|(link_id, created_by_id, workspace_id)| {
+        (link_id, Space::new(created_by_id, workspace_id))
+    }))
+}
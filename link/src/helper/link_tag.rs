@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+
+use diesel::{pg::Pg, ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+use uuid::Uuid;
+
+use crate::{helper::workspace::Space, model, proto, schema};
+
+/// Gets the tags of each link, links without tags are left out of the map.
+pub fn get_tags_by_link_ids(
+    db_conn: &mut PgConnection,
+    link_ids: &[Uuid],
+) -> Result<HashMap<Uuid, Vec<proto::link::LinkTag>>, diesel::result::Error> {
+    let link_tags = schema::link_tag_map::table
+        .inner_join(schema::link_tag::table)
+        .filter(schema::link_tag_map::link_id.eq_any(link_ids))
+        .order(schema::link_tag::name.asc())
+        .select((schema::link_tag_map::link_id, schema::link_tag::all_columns))
+        .load::<(Uuid, model::LinkTag)>(db_conn)?;
+
+    let mut tags_by_link_id = HashMap::<Uuid, Vec<proto::link::LinkTag>>::new();
+    for (link_id, link_tag) in link_tags {
+        tags_by_link_id
+            .entry(link_id)
+            .or_default()
+            .push(link_tag.to_grpc());
+    }
+
+    Ok(tags_by_link_id)
+}
+
+/// Gets the space all the tags are in, `None` when some of them don't exist or they are
+/// spread over spaces.
+pub fn get_tags_space(
+    db_conn: &mut PgConnection,
+    tag_ids: &[Uuid],
+) -> Result<Option<Space>, diesel::result::Error> {
+    let owners = schema::link_tag::table
+        .filter(schema::link_tag::id.eq_any(tag_ids))
+        .select((
+            schema::link_tag::created_by_id,
+            schema::link_tag::workspace_id,
+        ))
+        .load::<(Uuid, Option<Uuid>)>(db_conn)?;
+    if owners.len() != tag_ids.len() {
+        return Ok(None);
+    }
+
+    Ok(Space::common(owners.into_iter().map(
+        |(created_by_id, workspace_id)| Space::new(created_by_id, workspace_id),
+    )))
+}
+
+pub fn filter_by_space<'a>(
+    query: schema::link_tag::BoxedQuery<'a, Pg>,
+    space: &Space,
+) -> schema::link_tag::BoxedQuery<'a, Pg> {
+    match *space {
+        Space::Personal(created_by_id) => query
+            .filter(schema::link_tag::created_by_id.eq(created_by_id))
+            .filter(schema::link_tag::workspace_id.is_null()),
+        Space::Workspace(workspace_id) => {
+            query.filter(schema::link_tag::workspace_id.eq(workspace_id))
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use chrono::{DateTime, NaiveDateTime};
+
+const SHORT_URL_MAX_LEN: usize = 128;
+const REPORT_DETAILS_MAX_LEN: usize = 1000;
+const BLOCKED_APP_URI_SCHEMES: [&str; 7] = [
+    "http",
+    "https",
+    "javascript",
+    "data",
+    "vbscript",
+    "file",
+    "intent",
+];
+
+pub fn validate_title(title: &str) -> Result<(), String> {
+    if title.trim().is_empty() {
+        return Err("The title is empty.".to_string());
+    }
+
+    Ok(())
+}
+
+pub fn validate_short_url(short_url: &str) -> Result<(), String> {
+    if short_url.is_empty() {
+        return Err("The short url is empty.".to_string());
+    }
+    if short_url.len() > SHORT_URL_MAX_LEN {
+        return Err(format!(
+            "The short url is longer than {SHORT_URL_MAX_LEN} characters."
+        ));
+    }
+    if !short_url
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(
+            "The short url may only contain letters, digits, hyphens and underscores.".to_string(),
+        );
+    }
+
+    Ok(())
+}
+
+pub fn validate_long_url(long_url: &str) -> Result<(), String> {
+    if !long_url.starts_with("http://") && !long_url.starts_with("https://") {
+        return Err("The long url must start with http:// or https://.".to_string());
+    }
+    if long_url.chars().any(|c| c.is_whitespace()) {
+        return Err("The long url must not contain whitespace.".to_string());
+    }
+
+    Ok(())
+}
+
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("The name is empty.".to_string());
+    }
+
+    Ok(())
+}
+
+pub fn validate_country(country: &str) -> Result<(), String> {
+    if country.len() != 2 || !country.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err("The country must be an ISO 3166-1 alpha-2 code.".to_string());
+    }
+
+    Ok(())
+}
+
+pub fn validate_language(language: &str) -> Result<(), String> {
+    if !(2..=8).contains(&language.len()) || !language.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err("The language must be a primary language subtag like en.".to_string());
+    }
+
+    Ok(())
+}
+
+const REFERRER_POLICIES: [&str; 8] = [
+    "no-referrer",
+    "no-referrer-when-downgrade",
+    "origin",
+    "origin-when-cross-origin",
+    "same-origin",
+    "strict-origin",
+    "strict-origin-when-cross-origin",
+    "unsafe-url",
+];
+
+pub fn validate_referrer_policy(referrer_policy: &str) -> Result<(), String> {
+    if !REFERRER_POLICIES.contains(&referrer_policy) {
+        return Err(format!(
+            "The referrer policy must be one of {}.",
+            REFERRER_POLICIES.join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+/// Checks if the uri opens an app, web and script schemes are left to the long url.
+pub fn validate_app_uri(app_uri: &str) -> Result<(), String> {
+    let scheme = app_uri.split_once(':').map(|(scheme, _)| scheme);
+    let is_scheme_valid = scheme
+        .filter(|scheme| {
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+        })
+        .map(|scheme| !BLOCKED_APP_URI_SCHEMES.contains(&scheme.to_ascii_lowercase().as_str()))
+        .unwrap_or(false);
+    if !is_scheme_valid {
+        return Err("The app uri must start with the custom scheme of an app.".to_string());
+    }
+    if app_uri.chars().any(|c| c.is_whitespace()) {
+        return Err("The app uri must not contain whitespace.".to_string());
+    }
+
+    Ok(())
+}
+
+pub fn validate_android_package(android_package: &str) -> Result<(), String> {
+    let segments = android_package.split('.').collect::<Vec<&str>>();
+    let is_package_valid = segments.len() >= 2
+        && segments.iter().all(|segment| {
+            segment.starts_with(|c: char| c.is_ascii_alphabetic())
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        });
+    if !is_package_valid {
+        return Err(
+            "The Android package must be an application id like com.example.app.".to_string(),
+        );
+    }
+
+    Ok(())
+}
+
+pub fn validate_weight(weight: i32) -> Result<(), String> {
+    if weight <= 0 {
+        return Err("The weight must be greater than zero.".to_string());
+    }
+
+    Ok(())
+}
+
+pub fn validate_report_details(details: &str) -> Result<(), String> {
+    if details.chars().count() > REPORT_DETAILS_MAX_LEN {
+        return Err(format!(
+            "The details are longer than {REPORT_DETAILS_MAX_LEN} characters."
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn validate_disabled_reason(reason: &str) -> Result<(), String> {
+    if reason.trim().is_empty() {
+        return Err("The reason is empty.".to_string());
+    }
+
+    Ok(())
+}
+
+pub fn parse_expires_at(expires_at: &str) -> Result<NaiveDateTime, String> {
+    DateTime::parse_from_rfc3339(expires_at.trim())
+        .map(|expires_at| expires_at.naive_utc())
+        .map_err(|_| "The expiry must be an RFC 3339 date and time.".to_string())
+}
@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+
+use diesel::{ExpressionMethods, OptionalExtension, PgConnection, QueryDsl, RunQueryDsl};
+use uuid::Uuid;
+
+use crate::{helper::workspace::Space, model, proto, schema};
+
+pub fn get_variants_by_link_id(
+    db_conn: &mut PgConnection,
+    link_id: &Uuid,
+) -> Result<Vec<model::LinkVariant>, diesel::result::Error> {
+    schema::link_variant::table
+        .filter(schema::link_variant::link_id.eq(link_id))
+        .order((
+            schema::link_variant::created_at.asc(),
+            schema::link_variant::id.asc(),
+        ))
+        .load::<model::LinkVariant>(db_conn)
+}
+
+/// Gets the variants of each link, links without variants are left out of the map.
+pub fn get_variants_by_link_ids(
+    db_conn: &mut PgConnection,
+    link_ids: &[Uuid],
+) -> Result<HashMap<Uuid, Vec<proto::link::LinkVariant>>, diesel::result::Error> {
+    let link_variants = schema::link_variant::table
+        .filter(schema::link_variant::link_id.eq_any(link_ids))
+        .order((
+            schema::link_variant::created_at.asc(),
+            schema::link_variant::id.asc(),
+        ))
+        .load::<model::LinkVariant>(db_conn)?;
+
+    let mut variants_by_link_id = HashMap::<Uuid, Vec<proto::link::LinkVariant>>::new();
+    for link_variant in link_variants {
+        variants_by_link_id
+            .entry(link_variant.link_id)
+            .or_default()
+            .push(link_variant.to_grpc());
+    }
+
+    Ok(variants_by_link_id)
+}
+
+/// Gets the link id of a variant with the space of the link.
+pub fn get_variant_link(
+    db_conn: &mut PgConnection,
+    variant_id: &Uuid,
+) -> Result<Option<(Uuid, Space)>, diesel::result::Error> {
+    let link = schema::link_variant::table
+        .inner_join(schema::link::table)
+        .filter(schema::link_variant::id.eq(variant_id))
+        .filter(schema::link::deleted_at.is_null())
+        .select((
+            schema::link_variant::link_id,
+            schema::link::created_by_id,
+            schema::link::workspace_id,
+        ))
+        .first::<(Uuid, Uuid, Option<Uuid>)>(db_conn)
+        .optional()?;
+
+    Ok(link.map(|(link_id, created_by_id, workspace_id)| {
+        (link_id, Space::new(created_by_id, workspace_id))
+    }))
+}
+
+/// 64-bit FNV-1a, used instead of the std hasher because its output must not
+/// change between builds for a visitor to keep their variant.
+fn fnv1a(bytes: impl Iterator<Item = u8>) -> u64 {
+    bytes.fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Picks a variant by weight. The same visitor always gets the same variant of
+/// a link as long as its variants and their weights don't change.
+pub fn pick_variant<'a>(
+    variants: &'a [model::LinkVariant],
+    link_id: &Uuid,
+    visitor_id: &str,
+) -> Option<&'a model::LinkVariant> {
+    let total_weight = variants
+        .iter()
+        .map(|variant| variant.weight.max(0) as u64)
+        .sum::<u64>();
+    if total_weight == 0 {
+        return None;
+    }
+
+    let mut bucket =
+        fnv1a(link_id.as_bytes().iter().copied().chain(visitor_id.bytes())) % total_weight;
+    for variant in variants {
+        let weight = variant.weight.max(0) as u64;
+        if bucket < weight {
+            return Some(variant);
+        }
+        bucket -= weight;
+    }
+
+    None
+}
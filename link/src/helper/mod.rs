@@ -0,0 +1,15 @@
+pub mod link;
+pub mod link_destination;
+pub mod link_event;
+pub mod link_file;
+pub mod link_folder;
+pub mod link_health;
+pub mod link_import;
+pub mod link_og_metadata;
+pub mod link_report;
+pub mod link_revision;
+pub mod link_rule;
+pub mod link_tag;
+pub mod link_validation;
+pub mod link_variant;
+pub mod workspace;
@@ -0,0 +1,95 @@
+use std::str::FromStr;
+
+use diesel::{ExpressionMethods, OptionalExtension, PgConnection, QueryDsl, RunQueryDsl};
+use uuid::Uuid;
+
+use crate::{model, schema};
+
+/// Where links, tags and folders belong, either a workspace or the personal space of the
+/// account that created them.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Space {
+    Personal(Uuid),
+    Workspace(Uuid),
+}
+
+impl Space {
+    pub fn new(created_by_id: Uuid, workspace_id: Option<Uuid>) -> Self {
+        match workspace_id {
+            Some(workspace_id) => Space::Workspace(workspace_id),
+            None => Space::Personal(created_by_id),
+        }
+    }
+
+    /// Gets the space a request is made in, the personal space of the account when no
+    /// workspace is given.
+    pub fn parse(account_id: &Uuid, workspace_id: &Option<String>) -> Result<Self, uuid::Error> {
+        Ok(match workspace_id {
+            Some(workspace_id) => Space::Workspace(Uuid::from_str(workspace_id)?),
+            None => Space::Personal(*account_id),
+        })
+    }
+
+    pub fn workspace_id(&self) -> Option<Uuid> {
+        match self {
+            Space::Personal(_) => None,
+            Space::Workspace(workspace_id) => Some(*workspace_id),
+        }
+    }
+
+    /// Gets the one space all the items are in, `None` when they are spread over spaces.
+    pub fn common(spaces: impl IntoIterator<Item = Space>) -> Option<Self> {
+        let mut spaces = spaces.into_iter();
+        let space = spaces.next()?;
+        spaces.all(|other| other == space).then_some(space)
+    }
+}
+
+/// Gets the role of an account in a space, `None` when the account can't see it. An
+/// account owns its personal space.
+pub fn get_role(
+    db_conn: &mut PgConnection,
+    space: &Space,
+    account_id: &Uuid,
+) -> Result<Option<model::WorkspaceRole>, diesel::result::Error> {
+    match space {
+        Space::Personal(owner_id) => {
+            Ok((owner_id == account_id).then_some(model::WorkspaceRole::Owner))
+        }
+        Space::Workspace(workspace_id) => get_member_role(db_conn, workspace_id, account_id),
+    }
+}
+
+pub fn get_member_role(
+    db_conn: &mut PgConnection,
+    workspace_id: &Uuid,
+    account_id: &Uuid,
+) -> Result<Option<model::WorkspaceRole>, diesel::result::Error> {
+    schema::workspace_member::table
+        .find((workspace_id, account_id))
+        .select(schema::workspace_member::role)
+        .first::<model::WorkspaceRole>(db_conn)
+        .optional()
+}
+
+pub fn get_owner_ids(
+    db_conn: &mut PgConnection,
+    workspace_id: &Uuid,
+) -> Result<Vec<Uuid>, diesel::result::Error> {
+    schema::workspace_member::table
+        .filter(schema::workspace_member::workspace_id.eq(workspace_id))
+        .filter(schema::workspace_member::role.eq(model::WorkspaceRole::Owner))
+        .select(schema::workspace_member::account_id)
+        .load::<Uuid>(db_conn)
+}
+
+pub fn count_owners(
+    db_conn: &mut PgConnection,
+    workspace_id: &Uuid,
+) -> Result<i64, diesel::result::Error> {
+    schema::workspace_member::table
+        .filter(schema::workspace_member::workspace_id.eq(workspace_id))
+        .filter(schema::workspace_member::role.eq(model::WorkspaceRole::Owner))
+        .count()
+        .get_result::<i64>(db_conn)
+}
@@ -1,15 +1,26 @@
-use std::error::Error;
+#![recursion_limit = "256"]
+
+use std::{error::Error, future::Future};
 
 use diesel_migrations::{embed_migrations, EmbeddedMigrations};
 use tonic::transport::Server;
+use tools_link::contract::{LINK_EVENT_TOPIC, LINK_VISITED_TOPIC, LINK_VISIT_TOPIC};
 
-use crate::controller::LinkController;
+use crate::{
+    controller::LinkController,
+    service::{kafka_consumer, link_health, link_trash, rabbitmq_consumer},
+};
 
 mod controller;
+mod dto;
 mod env;
+mod helper;
 mod model;
 mod proto;
 mod schema;
+mod service;
+#[cfg(test)]
+mod test_server;
 
 const MIGRATIONS: EmbeddedMigrations = embed_migrations!("./migrations");
 
@@ -20,20 +31,153 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
     let service_name = env::Env::service_name();
     let service_addrs = env::Env::service_addrs();
     let database_url = env::Env::database_url();
+    let geoip_database_path = env::Env::geoip_database_path();
+    let og_fetch_allow_private_hosts = env::Env::og_fetch_allow_private_hosts();
+    let trash_retention_days = env::Env::trash_retention_days();
+    let health_check_interval = env::Env::health_check_interval();
+    let account_service_addrs = env::Env::account_service_addrs();
+    let use_msg_broker = env::Env::use_msg_broker();
 
     let db_pool = tools_lib_db::pg::connection::create_connection_pool(&database_url);
     let db_conn = &mut tools_lib_db::pg::connection::get_connection(&app_mode, &db_pool).unwrap();
     tools_lib_db::pg::migration::run_migrations(db_conn, MIGRATIONS)?;
 
+    let geoip_reader = geoip_database_path
+        .map(maxminddb::Reader::open_readfile)
+        .transpose()?;
+    let http_client = helper::link_og_metadata::create_client(og_fetch_allow_private_hosts)?;
+
+    // Links stay in the trash for the retention period before they are deleted for good
+    tokio::spawn(link_trash::purge(
+        app_mode.to_owned(),
+        db_pool.to_owned(),
+        trash_retention_days,
+    ));
+
+    // Link events are published for the link resolvers, which send back the visits of the
+    // links they cached, and the counted visits are published for the gateway
+    let mut kafka_producer: Option<rdkafka::producer::FutureProducer> = None;
+    let mut rabbitmq_channel: Option<lapin::Channel> = None;
+    if use_msg_broker.is_kafka() {
+        let kafka_addrs = env::Env::kafka_addrs();
+        let kafka_group_id = env::Env::kafka_group_id();
+        let kafka_msg_timeout = env::Env::kafka_msg_timeout();
+        let producer: rdkafka::producer::FutureProducer = rdkafka::ClientConfig::new()
+            .set("bootstrap.servers", &kafka_addrs)
+            .set("message.timeout.ms", &kafka_msg_timeout)
+            .create()?;
+        tokio::spawn(log_consumer_error(kafka_consumer::link_visits(
+            kafka_consumer::Config {
+                brokers: kafka_addrs,
+                group_id: kafka_group_id,
+            },
+            app_mode.to_owned(),
+            db_pool.to_owned(),
+            producer.to_owned(),
+        )));
+        kafka_producer = Some(producer);
+    } else if use_msg_broker.is_rabbitmq() {
+        let rabbitmq_addrs = env::Env::rabbitmq_addrs();
+        let rabbitmq_connection = lapin::Connection::connect(
+            &rabbitmq_addrs,
+            lapin::ConnectionProperties::default()
+                .with_executor(tokio_executor_trait::Tokio::current())
+                .with_reactor(tokio_reactor_trait::Tokio),
+        )
+        .await?;
+        let channel = rabbitmq_connection.create_channel().await?;
+        channel
+            .confirm_select(lapin::options::ConfirmSelectOptions::default())
+            .await?;
+        channel
+            .exchange_declare(
+                LINK_EVENT_TOPIC,
+                lapin::ExchangeKind::Fanout,
+                lapin::options::ExchangeDeclareOptions::default(),
+                lapin::types::FieldTable::default(),
+            )
+            .await?;
+        channel
+            .exchange_declare(
+                LINK_VISITED_TOPIC,
+                lapin::ExchangeKind::Fanout,
+                lapin::options::ExchangeDeclareOptions::default(),
+                lapin::types::FieldTable::default(),
+            )
+            .await?;
+        channel
+            .queue_declare(
+                LINK_VISIT_TOPIC,
+                lapin::options::QueueDeclareOptions::default(),
+                lapin::types::FieldTable::default(),
+            )
+            .await?;
+        channel
+            .queue_declare(
+                link_health::MAILER_QUEUE,
+                lapin::options::QueueDeclareOptions::default(),
+                lapin::types::FieldTable::default(),
+            )
+            .await?;
+        tokio::spawn(log_consumer_error(rabbitmq_consumer::link_visits(
+            rabbitmq_connection.create_channel().await?,
+            app_mode.to_owned(),
+            db_pool.to_owned(),
+            channel.to_owned(),
+        )));
+        rabbitmq_channel = Some(channel);
+    }
+
+    let account_service = account_service_addrs
+        .map(|account_service_addrs| {
+            tonic::transport::Endpoint::from_shared(account_service_addrs)
+                .map(|endpoint| endpoint.connect_lazy())
+        })
+        .transpose()?;
+
+    // The long urls are checked in the background and the owners of the broken links are
+    // mailed through the mailer service
+    if !health_check_interval.is_zero() {
+        tokio::spawn(link_health::check(
+            app_mode.to_owned(),
+            db_pool.to_owned(),
+            http_client.to_owned(),
+            link_health::Notifier {
+                account_service: account_service.to_owned(),
+                kafka_producer: kafka_producer.to_owned(),
+                rabbitmq_channel: rabbitmq_channel.to_owned(),
+            },
+            link_health::Config {
+                interval: health_check_interval,
+                concurrency: env::Env::health_check_concurrency().max(1),
+                failure_threshold: env::Env::health_check_failure_threshold().max(1),
+                allow_private_hosts: og_fetch_allow_private_hosts,
+            },
+        ));
+    }
+
     println!("{app_name} {service_name} is running on {service_addrs} in {app_mode}.");
 
     Server::builder()
         .add_service(proto::link::LinkServiceServer::new(LinkController {
             app_mode,
             db_pool,
+            geoip_reader,
+            http_client,
+            og_fetch_allow_private_hosts,
+            report_rate_limit: env::Env::report_rate_limit(),
+            account_service,
+            kafka_producer,
+            rabbitmq_channel,
         }))
         .serve(service_addrs.parse()?)
         .await?;
 
     Ok(())
 }
+
+async fn log_consumer_error<E: std::fmt::Display>(consumer: impl Future<Output = Result<(), E>>) {
+    if let Err(e) = consumer.await {
+        eprintln!("Link visit consumer stopped: {e}");
+    }
+}
@@ -1,8 +1,8 @@
-use chrono::NaiveDateTime;
+use chrono::{NaiveDateTime, TimeZone, Utc};
 use diesel::{AsChangeset, Insertable, Queryable};
 use uuid::Uuid;
 
-use crate::schema;
+use crate::{model::LinkRedirectStatus, proto, schema};
 
 #[derive(Queryable, Insertable)]
 #[diesel(table_name = schema::link)]
@@ -15,6 +15,41 @@ pub struct Link {
     pub created_by_id: Uuid,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub folder_id: Option<Uuid>,
+    pub utm_source: Option<String>,
+    pub utm_medium: Option<String>,
+    pub utm_campaign: Option<String>,
+    pub utm_term: Option<String>,
+    pub utm_content: Option<String>,
+    pub forward_query: bool,
+    pub interstitial: bool,
+    pub og_title: Option<String>,
+    pub og_description: Option<String>,
+    pub og_image: Option<String>,
+    pub og_fetched_at: Option<NaiveDateTime>,
+    pub og_title_override: Option<String>,
+    pub og_description_override: Option<String>,
+    pub og_image_override: Option<String>,
+    pub redirect_status: LinkRedirectStatus,
+    pub referrer_policy: Option<String>,
+    pub no_index: bool,
+    pub expires_at: Option<NaiveDateTime>,
+    pub disabled_at: Option<NaiveDateTime>,
+    pub disabled_reason: Option<String>,
+    pub disabled_by_id: Option<Uuid>,
+    pub app_uri: Option<String>,
+    pub android_package: Option<String>,
+    pub ios_fallback_url: Option<String>,
+    pub workspace_id: Option<Uuid>,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub deleted_by_id: Option<Uuid>,
+    pub health_status_code: Option<i32>,
+    pub health_latency_ms: Option<i32>,
+    pub health_error: Option<String>,
+    pub health_checked_at: Option<NaiveDateTime>,
+    pub health_failures: i32,
+    pub health_broken_at: Option<NaiveDateTime>,
+    pub health_next_check_at: Option<NaiveDateTime>,
 }
 
 #[derive(AsChangeset)]
@@ -23,4 +58,164 @@ pub struct LinkChangeSet {
     pub title: Option<String>,
     pub short_url: Option<String>,
     pub long_url: Option<String>,
+    pub forward_query: Option<bool>,
+    pub interstitial: Option<bool>,
+    pub redirect_status: Option<LinkRedirectStatus>,
+    pub referrer_policy: Option<Option<String>>,
+    pub no_index: Option<bool>,
+    pub expires_at: Option<Option<NaiveDateTime>>,
+}
+
+/// Sets all UTM parameters at once, unset parameters are cleared on update.
+#[derive(Insertable, AsChangeset)]
+#[diesel(table_name = schema::link, treat_none_as_null = true)]
+pub struct LinkUtm {
+    pub utm_source: Option<String>,
+    pub utm_medium: Option<String>,
+    pub utm_campaign: Option<String>,
+    pub utm_term: Option<String>,
+    pub utm_content: Option<String>,
+}
+
+/// Sets all deep link settings at once, unset settings are cleared on update.
+#[derive(Insertable, AsChangeset)]
+#[diesel(table_name = schema::link, treat_none_as_null = true)]
+pub struct LinkDeepLink {
+    pub app_uri: Option<String>,
+    pub android_package: Option<String>,
+    pub ios_fallback_url: Option<String>,
+}
+
+/// The Open Graph metadata fetched from the long url.
+#[derive(AsChangeset)]
+#[diesel(table_name = schema::link, treat_none_as_null = true)]
+pub struct LinkOgMetadata {
+    pub og_title: Option<String>,
+    pub og_description: Option<String>,
+    pub og_image: Option<String>,
+    pub og_fetched_at: Option<NaiveDateTime>,
+}
+
+/// Sets all Open Graph overrides at once, unset overrides are cleared on update.
+#[derive(Insertable, AsChangeset)]
+#[diesel(table_name = schema::link, treat_none_as_null = true)]
+pub struct LinkOgMetadataOverride {
+    pub og_title_override: Option<String>,
+    pub og_description_override: Option<String>,
+    pub og_image_override: Option<String>,
+}
+
+impl Link {
+    pub fn to_grpc(
+        &self,
+        tags: Vec<proto::link::LinkTag>,
+        variants: Vec<proto::link::LinkVariant>,
+    ) -> proto::link::Link {
+        proto::link::Link {
+            id: self.id.to_string(),
+            title: self.title.to_owned(),
+            short_url: self.short_url.to_owned(),
+            long_url: self.long_url.to_owned(),
+            visits: self.visits,
+            created_at: self.created_at.to_string(),
+            updated_at: self.updated_at.to_string(),
+            folder_id: self.folder_id.map(|folder_id| folder_id.to_string()),
+            tags,
+            utm: Some(proto::link::LinkUtm {
+                source: self.utm_source.to_owned(),
+                medium: self.utm_medium.to_owned(),
+                campaign: self.utm_campaign.to_owned(),
+                term: self.utm_term.to_owned(),
+                content: self.utm_content.to_owned(),
+            }),
+            forward_query: self.forward_query,
+            variants,
+            interstitial: self.interstitial,
+            og_metadata: Some(proto::link::LinkOgMetadata {
+                title: self.og_title.to_owned(),
+                description: self.og_description.to_owned(),
+                image: self.og_image.to_owned(),
+            }),
+            og_metadata_override: Some(proto::link::LinkOgMetadata {
+                title: self.og_title_override.to_owned(),
+                description: self.og_description_override.to_owned(),
+                image: self.og_image_override.to_owned(),
+            }),
+            redirect_status: self.redirect_status.to_grpc_enum(),
+            referrer_policy: self.referrer_policy.to_owned(),
+            no_index: self.no_index,
+            expires_at: self
+                .expires_at
+                .map(|expires_at| Utc.from_utc_datetime(&expires_at).to_rfc3339()),
+            disabled_at: self
+                .disabled_at
+                .map(|disabled_at| Utc.from_utc_datetime(&disabled_at).to_rfc3339()),
+            disabled_reason: self.disabled_reason.to_owned(),
+            deep_link: Some(self.deep_link()),
+            created_by_id: self.created_by_id.to_string(),
+            workspace_id: self
+                .workspace_id
+                .map(|workspace_id| workspace_id.to_string()),
+            deleted_at: self
+                .deleted_at
+                .map(|deleted_at| Utc.from_utc_datetime(&deleted_at).to_rfc3339()),
+            health: Some(proto::link::LinkHealth {
+                status_code: self.health_status_code,
+                latency_ms: self.health_latency_ms,
+                error: self.health_error.to_owned(),
+                checked_at: self
+                    .health_checked_at
+                    .map(|checked_at| Utc.from_utc_datetime(&checked_at).to_rfc3339()),
+                failures: self.health_failures,
+                broken_at: self
+                    .health_broken_at
+                    .map(|broken_at| Utc.from_utc_datetime(&broken_at).to_rfc3339()),
+            }),
+        }
+    }
+
+    pub fn deep_link(&self) -> proto::link::LinkDeepLink {
+        proto::link::LinkDeepLink {
+            app_uri: self.app_uri.to_owned(),
+            android_package: self.android_package.to_owned(),
+            ios_fallback_url: self.ios_fallback_url.to_owned(),
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        matches!(self.expires_at, Some(expires_at) if expires_at <= Utc::now().naive_utc())
+    }
+
+    /// Gets the Open Graph metadata shown to crawlers, each override takes the place of
+    /// the fetched value.
+    pub fn effective_og_metadata(&self) -> proto::link::LinkOgMetadata {
+        proto::link::LinkOgMetadata {
+            title: self
+                .og_title_override
+                .to_owned()
+                .or_else(|| self.og_title.to_owned()),
+            description: self
+                .og_description_override
+                .to_owned()
+                .or_else(|| self.og_description.to_owned()),
+            image: self
+                .og_image_override
+                .to_owned()
+                .or_else(|| self.og_image.to_owned()),
+        }
+    }
+
+    /// Gets the UTM parameters that are set, in the order they are added to the url.
+    pub fn utm_params(&self) -> Vec<(&str, &str)> {
+        [
+            ("utm_source", &self.utm_source),
+            ("utm_medium", &self.utm_medium),
+            ("utm_campaign", &self.utm_campaign),
+            ("utm_term", &self.utm_term),
+            ("utm_content", &self.utm_content),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.as_deref().map(|value| (key, value)))
+        .collect()
+    }
 }
@@ -0,0 +1,39 @@
+use chrono::NaiveDateTime;
+use diesel::{AsChangeset, Insertable, Queryable};
+use uuid::Uuid;
+
+use crate::{proto, schema};
+
+#[derive(Queryable, Insertable)]
+#[diesel(table_name = schema::link_folder)]
+pub struct LinkFolder {
+    pub id: Uuid,
+    pub name: String,
+    pub parent_id: Option<Uuid>,
+    pub created_by_id: Uuid,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub workspace_id: Option<Uuid>,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = schema::link_folder)]
+pub struct LinkFolderChangeSet {
+    pub name: Option<String>,
+    pub parent_id: Option<Option<Uuid>>,
+}
+
+impl LinkFolder {
+    pub fn to_grpc(&self) -> proto::link::LinkFolder {
+        proto::link::LinkFolder {
+            id: self.id.to_string(),
+            name: self.name.to_owned(),
+            parent_id: self.parent_id.map(|parent_id| parent_id.to_string()),
+            created_at: self.created_at.to_string(),
+            updated_at: self.updated_at.to_string(),
+            workspace_id: self
+                .workspace_id
+                .map(|workspace_id| workspace_id.to_string()),
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use chrono::NaiveDateTime;
+use diesel::{Insertable, Queryable};
+use uuid::Uuid;
+
+use crate::{proto, schema};
+
+use super::{LinkReportReason, LinkReportStatus};
+
+#[derive(Queryable, Insertable)]
+#[diesel(table_name = schema::link_report)]
+pub struct LinkReport {
+    pub id: Uuid,
+    pub link_id: Uuid,
+    pub reason: LinkReportReason,
+    pub details: Option<String>,
+    pub reporter_ip: Option<String>,
+    pub status: LinkReportStatus,
+    pub resolved_by_id: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl LinkReport {
+    pub fn to_grpc(&self, short_url: String, long_url: String) -> proto::link::LinkReport {
+        proto::link::LinkReport {
+            id: self.id.to_string(),
+            link_id: self.link_id.to_string(),
+            short_url,
+            long_url,
+            reason: self.reason.to_grpc_enum(),
+            details: self.details.to_owned(),
+            status: self.status.to_grpc_enum(),
+            resolved_by_id: self
+                .resolved_by_id
+                .map(|resolved_by_id| resolved_by_id.to_string()),
+            created_at: self.created_at.to_string(),
+            updated_at: self.updated_at.to_string(),
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use chrono::NaiveDateTime;
+use diesel::{Insertable, Queryable};
+use uuid::Uuid;
+
+use crate::{proto, schema};
+
+#[derive(Queryable, Insertable)]
+#[diesel(table_name = schema::link_revision)]
+pub struct LinkRevision {
+    pub id: Uuid,
+    pub link_id: Uuid,
+    pub title: String,
+    pub short_url: String,
+    pub long_url: String,
+    pub created_by_id: Uuid,
+    pub created_at: NaiveDateTime,
+}
+
+impl LinkRevision {
+    pub fn to_grpc(&self) -> proto::link::LinkRevision {
+        proto::link::LinkRevision {
+            id: self.id.to_string(),
+            link_id: self.link_id.to_string(),
+            title: self.title.to_owned(),
+            short_url: self.short_url.to_owned(),
+            long_url: self.long_url.to_owned(),
+            created_by_id: self.created_by_id.to_string(),
+            created_at: self.created_at.to_string(),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use chrono::{NaiveDateTime, TimeZone, Utc};
+use diesel::{AsChangeset, Insertable, Queryable};
+use uuid::Uuid;
+
+use crate::{proto, schema};
+
+use super::LinkRulePlatform;
+
+#[derive(Queryable, Insertable)]
+#[diesel(table_name = schema::link_rule)]
+pub struct LinkRule {
+    pub id: Uuid,
+    pub link_id: Uuid,
+    pub position: i32,
+    pub platform: Option<LinkRulePlatform>,
+    pub country: Option<String>,
+    pub language: Option<String>,
+    pub starts_at: Option<NaiveDateTime>,
+    pub ends_at: Option<NaiveDateTime>,
+    pub destination_url: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// The conditions and the destination of a rule, unset conditions are cleared on update.
+#[derive(Insertable, AsChangeset)]
+#[diesel(table_name = schema::link_rule, treat_none_as_null = true)]
+pub struct LinkRuleChangeSet {
+    pub platform: Option<LinkRulePlatform>,
+    pub country: Option<String>,
+    pub language: Option<String>,
+    pub starts_at: Option<NaiveDateTime>,
+    pub ends_at: Option<NaiveDateTime>,
+    pub destination_url: String,
+}
+
+impl LinkRule {
+    pub fn to_grpc(&self) -> proto::link::LinkRule {
+        proto::link::LinkRule {
+            id: self.id.to_string(),
+            link_id: self.link_id.to_string(),
+            position: self.position,
+            platform: self.platform.map(|platform| platform.to_grpc_enum()),
+            country: self.country.to_owned(),
+            language: self.language.to_owned(),
+            starts_at: self
+                .starts_at
+                .map(|starts_at| Utc.from_utc_datetime(&starts_at).to_rfc3339()),
+            ends_at: self
+                .ends_at
+                .map(|ends_at| Utc.from_utc_datetime(&ends_at).to_rfc3339()),
+            destination_url: self.destination_url.to_owned(),
+            created_at: self.created_at.to_string(),
+            updated_at: self.updated_at.to_string(),
+        }
+    }
+}
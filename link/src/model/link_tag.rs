@@ -0,0 +1,30 @@
+use chrono::NaiveDateTime;
+use diesel::{Insertable, Queryable};
+use uuid::Uuid;
+
+use crate::{proto, schema};
+
+#[derive(Queryable, Insertable)]
+#[diesel(table_name = schema::link_tag)]
+pub struct LinkTag {
+    pub id: Uuid,
+    pub name: String,
+    pub created_by_id: Uuid,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub workspace_id: Option<Uuid>,
+}
+
+impl LinkTag {
+    pub fn to_grpc(&self) -> proto::link::LinkTag {
+        proto::link::LinkTag {
+            id: self.id.to_string(),
+            name: self.name.to_owned(),
+            created_at: self.created_at.to_string(),
+            updated_at: self.updated_at.to_string(),
+            workspace_id: self
+                .workspace_id
+                .map(|workspace_id| workspace_id.to_string()),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use chrono::NaiveDateTime;
+use diesel::{AsChangeset, Insertable, Queryable};
+use uuid::Uuid;
+
+use crate::{proto, schema};
+
+#[derive(Queryable, Insertable)]
+#[diesel(table_name = schema::link_variant)]
+pub struct LinkVariant {
+    pub id: Uuid,
+    pub link_id: Uuid,
+    pub destination_url: String,
+    pub weight: i32,
+    pub visits: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = schema::link_variant)]
+pub struct LinkVariantChangeSet {
+    pub destination_url: Option<String>,
+    pub weight: Option<i32>,
+}
+
+impl LinkVariant {
+    pub fn to_grpc(&self) -> proto::link::LinkVariant {
+        proto::link::LinkVariant {
+            id: self.id.to_string(),
+            destination_url: self.destination_url.to_owned(),
+            weight: self.weight,
+            visits: self.visits,
+            created_at: self.created_at.to_string(),
+            updated_at: self.updated_at.to_string(),
+        }
+    }
+}
@@ -1,4 +1,32 @@
 mod link;
+mod link_folder;
+mod link_report;
+mod link_revision;
+mod link_rule;
+mod link_tag;
+mod link_variant;
+mod model_type;
+mod workspace;
 
 pub use link::Link;
 pub use link::LinkChangeSet;
+pub use link::LinkDeepLink;
+pub use link::LinkOgMetadata;
+pub use link::LinkOgMetadataOverride;
+pub use link::LinkUtm;
+pub use link_folder::LinkFolder;
+pub use link_folder::LinkFolderChangeSet;
+pub use link_report::LinkReport;
+pub use link_revision::LinkRevision;
+pub use link_rule::LinkRule;
+pub use link_rule::LinkRuleChangeSet;
+pub use link_tag::LinkTag;
+pub use link_variant::LinkVariant;
+pub use link_variant::LinkVariantChangeSet;
+pub use model_type::enum_link_redirect_status::LinkRedirectStatus;
+pub use model_type::enum_link_report_reason::LinkReportReason;
+pub use model_type::enum_link_report_status::LinkReportStatus;
+pub use model_type::enum_link_rule_platform::LinkRulePlatform;
+pub use model_type::enum_workspace_role::WorkspaceRole;
+pub use workspace::Workspace;
+pub use workspace::WorkspaceMember;
@@ -0,0 +1,74 @@
+use std::io::Write;
+
+use diesel::{
+    deserialize::{self, FromSql},
+    pg::{Pg, PgValue},
+    serialize::{self, IsNull, Output, ToSql},
+};
+
+use crate::{proto, schema};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, diesel::AsExpression, diesel::FromSqlRow)]
+#[diesel(sql_type = schema::sql_types::LinkRedirectStatus)]
+pub enum LinkRedirectStatus {
+    TemporaryRedirect,
+    PermanentRedirect,
+    Found,
+    MovedPermanently,
+}
+
+impl ToSql<schema::sql_types::LinkRedirectStatus, Pg> for LinkRedirectStatus {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            LinkRedirectStatus::TemporaryRedirect => out.write_all(b"temporary_redirect")?,
+            LinkRedirectStatus::PermanentRedirect => out.write_all(b"permanent_redirect")?,
+            LinkRedirectStatus::Found => out.write_all(b"found")?,
+            LinkRedirectStatus::MovedPermanently => out.write_all(b"moved_permanently")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<schema::sql_types::LinkRedirectStatus, Pg> for LinkRedirectStatus {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"temporary_redirect" => Ok(LinkRedirectStatus::TemporaryRedirect),
+            b"permanent_redirect" => Ok(LinkRedirectStatus::PermanentRedirect),
+            b"found" => Ok(LinkRedirectStatus::Found),
+            b"moved_permanently" => Ok(LinkRedirectStatus::MovedPermanently),
+            _ => Err("Unrecognized enum variant".into()),
+        }
+    }
+}
+
+impl LinkRedirectStatus {
+    pub fn to_grpc_enum(self) -> i32 {
+        match self {
+            LinkRedirectStatus::TemporaryRedirect => {
+                proto::link::LinkRedirectStatus::TemporaryRedirect.into()
+            }
+            LinkRedirectStatus::PermanentRedirect => {
+                proto::link::LinkRedirectStatus::PermanentRedirect.into()
+            }
+            LinkRedirectStatus::Found => proto::link::LinkRedirectStatus::Found.into(),
+            LinkRedirectStatus::MovedPermanently => {
+                proto::link::LinkRedirectStatus::MovedPermanently.into()
+            }
+        }
+    }
+
+    pub fn from_grpc_enum(value: i32) -> Option<Self> {
+        match proto::link::LinkRedirectStatus::from_i32(value)? {
+            proto::link::LinkRedirectStatus::TemporaryRedirect => {
+                Some(LinkRedirectStatus::TemporaryRedirect)
+            }
+            proto::link::LinkRedirectStatus::PermanentRedirect => {
+                Some(LinkRedirectStatus::PermanentRedirect)
+            }
+            proto::link::LinkRedirectStatus::Found => Some(LinkRedirectStatus::Found),
+            proto::link::LinkRedirectStatus::MovedPermanently => {
+                Some(LinkRedirectStatus::MovedPermanently)
+            }
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use std::io::Write;
+
+use diesel::{
+    deserialize::{self, FromSql},
+    pg::{Pg, PgValue},
+    serialize::{self, IsNull, Output, ToSql},
+};
+
+use crate::{proto, schema};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, diesel::AsExpression, diesel::FromSqlRow)]
+#[diesel(sql_type = schema::sql_types::LinkReportReason)]
+pub enum LinkReportReason {
+    Spam,
+    Phishing,
+    Malware,
+    IllegalContent,
+    Other,
+}
+
+impl ToSql<schema::sql_types::LinkReportReason, Pg> for LinkReportReason {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            LinkReportReason::Spam => out.write_all(b"spam")?,
+            LinkReportReason::Phishing => out.write_all(b"phishing")?,
+            LinkReportReason::Malware => out.write_all(b"malware")?,
+            LinkReportReason::IllegalContent => out.write_all(b"illegal_content")?,
+            LinkReportReason::Other => out.write_all(b"other")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<schema::sql_types::LinkReportReason, Pg> for LinkReportReason {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"spam" => Ok(LinkReportReason::Spam),
+            b"phishing" => Ok(LinkReportReason::Phishing),
+            b"malware" => Ok(LinkReportReason::Malware),
+            b"illegal_content" => Ok(LinkReportReason::IllegalContent),
+            b"other" => Ok(LinkReportReason::Other),
+            _ => Err("Unrecognized enum variant".into()),
+        }
+    }
+}
+
+impl LinkReportReason {
+    pub fn to_grpc_enum(self) -> i32 {
+        match self {
+            LinkReportReason::Spam => proto::link::LinkReportReason::Spam.into(),
+            LinkReportReason::Phishing => proto::link::LinkReportReason::Phishing.into(),
+            LinkReportReason::Malware => proto::link::LinkReportReason::Malware.into(),
+            LinkReportReason::IllegalContent => {
+                proto::link::LinkReportReason::IllegalContent.into()
+            }
+            LinkReportReason::Other => proto::link::LinkReportReason::Other.into(),
+        }
+    }
+
+    pub fn from_grpc_enum(value: i32) -> Option<Self> {
+        match proto::link::LinkReportReason::from_i32(value)? {
+            proto::link::LinkReportReason::Spam => Some(LinkReportReason::Spam),
+            proto::link::LinkReportReason::Phishing => Some(LinkReportReason::Phishing),
+            proto::link::LinkReportReason::Malware => Some(LinkReportReason::Malware),
+            proto::link::LinkReportReason::IllegalContent => Some(LinkReportReason::IllegalContent),
+            proto::link::LinkReportReason::Other => Some(LinkReportReason::Other),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::io::Write;
+
+use diesel::{
+    deserialize::{self, FromSql},
+    pg::{Pg, PgValue},
+    serialize::{self, IsNull, Output, ToSql},
+};
+
+use crate::{proto, schema};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, diesel::AsExpression, diesel::FromSqlRow)]
+#[diesel(sql_type = schema::sql_types::LinkReportStatus)]
+pub enum LinkReportStatus {
+    Pending,
+    Dismissed,
+    Actioned,
+}
+
+impl ToSql<schema::sql_types::LinkReportStatus, Pg> for LinkReportStatus {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            LinkReportStatus::Pending => out.write_all(b"pending")?,
+            LinkReportStatus::Dismissed => out.write_all(b"dismissed")?,
+            LinkReportStatus::Actioned => out.write_all(b"actioned")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<schema::sql_types::LinkReportStatus, Pg> for LinkReportStatus {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"pending" => Ok(LinkReportStatus::Pending),
+            b"dismissed" => Ok(LinkReportStatus::Dismissed),
+            b"actioned" => Ok(LinkReportStatus::Actioned),
+            _ => Err("Unrecognized enum variant".into()),
+        }
+    }
+}
+
+impl LinkReportStatus {
+    pub fn to_grpc_enum(self) -> i32 {
+        match self {
+            LinkReportStatus::Pending => proto::link::LinkReportStatus::Pending.into(),
+            LinkReportStatus::Dismissed => proto::link::LinkReportStatus::Dismissed.into(),
+            LinkReportStatus::Actioned => proto::link::LinkReportStatus::Actioned.into(),
+        }
+    }
+
+    pub fn from_grpc_enum(value: i32) -> Option<Self> {
+        match proto::link::LinkReportStatus::from_i32(value)? {
+            proto::link::LinkReportStatus::Pending => Some(LinkReportStatus::Pending),
+            proto::link::LinkReportStatus::Dismissed => Some(LinkReportStatus::Dismissed),
+            proto::link::LinkReportStatus::Actioned => Some(LinkReportStatus::Actioned),
+        }
+    }
+}
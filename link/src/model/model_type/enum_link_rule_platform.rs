@@ -0,0 +1,67 @@
+use std::io::Write;
+
+use diesel::{
+    deserialize::{self, FromSql},
+    pg::{Pg, PgValue},
+    serialize::{self, IsNull, Output, ToSql},
+};
+
+use crate::{proto, schema};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, diesel::AsExpression, diesel::FromSqlRow)]
+#[diesel(sql_type = schema::sql_types::LinkRulePlatform)]
+pub enum LinkRulePlatform {
+    Ios,
+    Android,
+    Windows,
+    Macos,
+    Linux,
+}
+
+impl ToSql<schema::sql_types::LinkRulePlatform, Pg> for LinkRulePlatform {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            LinkRulePlatform::Ios => out.write_all(b"ios")?,
+            LinkRulePlatform::Android => out.write_all(b"android")?,
+            LinkRulePlatform::Windows => out.write_all(b"windows")?,
+            LinkRulePlatform::Macos => out.write_all(b"macos")?,
+            LinkRulePlatform::Linux => out.write_all(b"linux")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<schema::sql_types::LinkRulePlatform, Pg> for LinkRulePlatform {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"ios" => Ok(LinkRulePlatform::Ios),
+            b"android" => Ok(LinkRulePlatform::Android),
+            b"windows" => Ok(LinkRulePlatform::Windows),
+            b"macos" => Ok(LinkRulePlatform::Macos),
+            b"linux" => Ok(LinkRulePlatform::Linux),
+            _ => Err("Unrecognized enum variant".into()),
+        }
+    }
+}
+
+impl LinkRulePlatform {
+    pub fn to_grpc_enum(self) -> i32 {
+        match self {
+            LinkRulePlatform::Ios => proto::link::LinkRulePlatform::Ios.into(),
+            LinkRulePlatform::Android => proto::link::LinkRulePlatform::Android.into(),
+            LinkRulePlatform::Windows => proto::link::LinkRulePlatform::Windows.into(),
+            LinkRulePlatform::Macos => proto::link::LinkRulePlatform::Macos.into(),
+            LinkRulePlatform::Linux => proto::link::LinkRulePlatform::Linux.into(),
+        }
+    }
+
+    pub fn from_grpc_enum(value: i32) -> Option<Self> {
+        match proto::link::LinkRulePlatform::from_i32(value)? {
+            proto::link::LinkRulePlatform::Ios => Some(LinkRulePlatform::Ios),
+            proto::link::LinkRulePlatform::Android => Some(LinkRulePlatform::Android),
+            proto::link::LinkRulePlatform::Windows => Some(LinkRulePlatform::Windows),
+            proto::link::LinkRulePlatform::Macos => Some(LinkRulePlatform::Macos),
+            proto::link::LinkRulePlatform::Linux => Some(LinkRulePlatform::Linux),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use std::io::Write;
+
+use diesel::{
+    deserialize::{self, FromSql},
+    pg::{Pg, PgValue},
+    serialize::{self, IsNull, Output, ToSql},
+};
+
+use crate::{proto, schema};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, diesel::AsExpression, diesel::FromSqlRow)]
+#[diesel(sql_type = schema::sql_types::WorkspaceRole)]
+pub enum WorkspaceRole {
+    Owner,
+    Editor,
+    Viewer,
+}
+
+impl ToSql<schema::sql_types::WorkspaceRole, Pg> for WorkspaceRole {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        match *self {
+            WorkspaceRole::Owner => out.write_all(b"owner")?,
+            WorkspaceRole::Editor => out.write_all(b"editor")?,
+            WorkspaceRole::Viewer => out.write_all(b"viewer")?,
+        }
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<schema::sql_types::WorkspaceRole, Pg> for WorkspaceRole {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"owner" => Ok(WorkspaceRole::Owner),
+            b"editor" => Ok(WorkspaceRole::Editor),
+            b"viewer" => Ok(WorkspaceRole::Viewer),
+            _ => Err("Unrecognized enum variant".into()),
+        }
+    }
+}
+
+impl WorkspaceRole {
+    pub fn to_grpc_enum(self) -> i32 {
+        match self {
+            WorkspaceRole::Owner => proto::link::WorkspaceRole::Owner.into(),
+            WorkspaceRole::Editor => proto::link::WorkspaceRole::Editor.into(),
+            WorkspaceRole::Viewer => proto::link::WorkspaceRole::Viewer.into(),
+        }
+    }
+
+    pub fn from_grpc_enum(value: i32) -> Option<Self> {
+        match proto::link::WorkspaceRole::from_i32(value)? {
+            proto::link::WorkspaceRole::Owner => Some(WorkspaceRole::Owner),
+            proto::link::WorkspaceRole::Editor => Some(WorkspaceRole::Editor),
+            proto::link::WorkspaceRole::Viewer => Some(WorkspaceRole::Viewer),
+        }
+    }
+
+    fn rank(self) -> u8 {
+        match self {
+            WorkspaceRole::Owner => 2,
+            WorkspaceRole::Editor => 1,
+            WorkspaceRole::Viewer => 0,
+        }
+    }
+
+    /// Checks if the role can do everything the other role can.
+    pub fn includes(self, role: WorkspaceRole) -> bool {
+        self.rank() >= role.rank()
+    }
+}
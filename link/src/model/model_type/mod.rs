@@ -0,0 +1,5 @@
+pub mod enum_link_redirect_status;
+pub mod enum_link_report_reason;
+pub mod enum_link_report_status;
+pub mod enum_link_rule_platform;
+pub mod enum_workspace_role;
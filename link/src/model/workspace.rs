@@ -0,0 +1,55 @@
+use chrono::NaiveDateTime;
+use diesel::{Insertable, Queryable};
+use uuid::Uuid;
+
+use crate::{proto, schema};
+
+use super::WorkspaceRole;
+
+#[derive(Queryable, Insertable)]
+#[diesel(table_name = schema::workspace)]
+pub struct Workspace {
+    pub id: Uuid,
+    pub name: String,
+    pub created_by_id: Uuid,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Queryable, Insertable)]
+#[diesel(table_name = schema::workspace_member)]
+pub struct WorkspaceMember {
+    pub workspace_id: Uuid,
+    pub account_id: Uuid,
+    pub role: WorkspaceRole,
+    pub invited_by_id: Option<Uuid>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl Workspace {
+    pub fn to_grpc(&self, role: WorkspaceRole) -> proto::link::Workspace {
+        proto::link::Workspace {
+            id: self.id.to_string(),
+            name: self.name.to_owned(),
+            role: role.to_grpc_enum(),
+            created_at: self.created_at.to_string(),
+            updated_at: self.updated_at.to_string(),
+        }
+    }
+}
+
+impl WorkspaceMember {
+    pub fn to_grpc(&self) -> proto::link::WorkspaceMember {
+        proto::link::WorkspaceMember {
+            workspace_id: self.workspace_id.to_string(),
+            account_id: self.account_id.to_string(),
+            role: self.role.to_grpc_enum(),
+            invited_by_id: self
+                .invited_by_id
+                .map(|invited_by_id| invited_by_id.to_string()),
+            created_at: self.created_at.to_string(),
+            updated_at: self.updated_at.to_string(),
+        }
+    }
+}
@@ -1,6 +1,31 @@
 // @generated automatically by Diesel CLI.
 
+pub mod sql_types {
+    #[derive(diesel::sql_types::SqlType, diesel::query_builder::QueryId)]
+    #[diesel(postgres_type(name = "link_redirect_status"))]
+    pub struct LinkRedirectStatus;
+
+    #[derive(diesel::sql_types::SqlType, diesel::query_builder::QueryId)]
+    #[diesel(postgres_type(name = "link_report_reason"))]
+    pub struct LinkReportReason;
+
+    #[derive(diesel::sql_types::SqlType, diesel::query_builder::QueryId)]
+    #[diesel(postgres_type(name = "link_report_status"))]
+    pub struct LinkReportStatus;
+
+    #[derive(diesel::sql_types::SqlType, diesel::query_builder::QueryId)]
+    #[diesel(postgres_type(name = "link_rule_platform"))]
+    pub struct LinkRulePlatform;
+
+    #[derive(diesel::sql_types::SqlType, diesel::query_builder::QueryId)]
+    #[diesel(postgres_type(name = "workspace_role"))]
+    pub struct WorkspaceRole;
+}
+
 diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::LinkRedirectStatus;
+
     link (id) {
         id -> Uuid,
         title -> Text,
@@ -10,5 +35,181 @@ diesel::table! {
         created_by_id -> Uuid,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        folder_id -> Nullable<Uuid>,
+        utm_source -> Nullable<Text>,
+        utm_medium -> Nullable<Text>,
+        utm_campaign -> Nullable<Text>,
+        utm_term -> Nullable<Text>,
+        utm_content -> Nullable<Text>,
+        forward_query -> Bool,
+        interstitial -> Bool,
+        og_title -> Nullable<Text>,
+        og_description -> Nullable<Text>,
+        og_image -> Nullable<Text>,
+        og_fetched_at -> Nullable<Timestamp>,
+        og_title_override -> Nullable<Text>,
+        og_description_override -> Nullable<Text>,
+        og_image_override -> Nullable<Text>,
+        redirect_status -> LinkRedirectStatus,
+        referrer_policy -> Nullable<Text>,
+        no_index -> Bool,
+        expires_at -> Nullable<Timestamp>,
+        disabled_at -> Nullable<Timestamp>,
+        disabled_reason -> Nullable<Text>,
+        disabled_by_id -> Nullable<Uuid>,
+        app_uri -> Nullable<Text>,
+        android_package -> Nullable<Text>,
+        ios_fallback_url -> Nullable<Text>,
+        workspace_id -> Nullable<Uuid>,
+        deleted_at -> Nullable<Timestamp>,
+        deleted_by_id -> Nullable<Uuid>,
+        health_status_code -> Nullable<Int4>,
+        health_latency_ms -> Nullable<Int4>,
+        health_error -> Nullable<Text>,
+        health_checked_at -> Nullable<Timestamp>,
+        health_failures -> Int4,
+        health_broken_at -> Nullable<Timestamp>,
+        health_next_check_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    link_folder (id) {
+        id -> Uuid,
+        name -> Text,
+        parent_id -> Nullable<Uuid>,
+        created_by_id -> Uuid,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        workspace_id -> Nullable<Uuid>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::LinkReportReason;
+    use super::sql_types::LinkReportStatus;
+
+    link_report (id) {
+        id -> Uuid,
+        link_id -> Uuid,
+        reason -> LinkReportReason,
+        details -> Nullable<Text>,
+        reporter_ip -> Nullable<Text>,
+        status -> LinkReportStatus,
+        resolved_by_id -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    link_revision (id) {
+        id -> Uuid,
+        link_id -> Uuid,
+        title -> Text,
+        short_url -> Text,
+        long_url -> Text,
+        created_by_id -> Uuid,
+        created_at -> Timestamp,
     }
 }
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::LinkRulePlatform;
+
+    link_rule (id) {
+        id -> Uuid,
+        link_id -> Uuid,
+        position -> Int4,
+        platform -> Nullable<LinkRulePlatform>,
+        country -> Nullable<Text>,
+        language -> Nullable<Text>,
+        starts_at -> Nullable<Timestamp>,
+        ends_at -> Nullable<Timestamp>,
+        destination_url -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    link_tag (id) {
+        id -> Uuid,
+        name -> Text,
+        created_by_id -> Uuid,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        workspace_id -> Nullable<Uuid>,
+    }
+}
+
+diesel::table! {
+    link_tag_map (link_id, tag_id) {
+        link_id -> Uuid,
+        tag_id -> Uuid,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    link_variant (id) {
+        id -> Uuid,
+        link_id -> Uuid,
+        destination_url -> Text,
+        weight -> Int4,
+        visits -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    workspace (id) {
+        id -> Uuid,
+        name -> Text,
+        created_by_id -> Uuid,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::WorkspaceRole;
+
+    workspace_member (workspace_id, account_id) {
+        workspace_id -> Uuid,
+        account_id -> Uuid,
+        role -> WorkspaceRole,
+        invited_by_id -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::joinable!(link -> link_folder (folder_id));
+diesel::joinable!(link -> workspace (workspace_id));
+diesel::joinable!(link_report -> link (link_id));
+diesel::joinable!(link_revision -> link (link_id));
+diesel::joinable!(link_rule -> link (link_id));
+diesel::joinable!(link_tag_map -> link (link_id));
+diesel::joinable!(link_tag_map -> link_tag (tag_id));
+diesel::joinable!(link_folder -> workspace (workspace_id));
+diesel::joinable!(link_tag -> workspace (workspace_id));
+diesel::joinable!(link_variant -> link (link_id));
+diesel::joinable!(workspace_member -> workspace (workspace_id));
+
+diesel::allow_tables_to_appear_in_same_query!(
+    link,
+    link_folder,
+    link_report,
+    link_revision,
+    link_rule,
+    link_tag,
+    link_tag_map,
+    link_variant,
+    workspace,
+    workspace_member,
+);
@@ -0,0 +1,51 @@
+use std::error::Error;
+
+use rdkafka::{
+    consumer::{CommitMode, Consumer, StreamConsumer},
+    producer::FutureProducer,
+    ClientConfig, Message,
+};
+use tools_lib_db::pg::connection::DbPool;
+use tools_link::contract::LINK_VISIT_TOPIC;
+
+#[derive(Clone)]
+pub struct Config {
+    pub brokers: String,
+    pub group_id: String,
+}
+
+pub async fn link_visits(
+    config: Config,
+    app_mode: String,
+    db_pool: DbPool,
+    kafka_producer: FutureProducer,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let kafka_producer = Some(kafka_producer);
+    let consumer: StreamConsumer = ClientConfig::new()
+        .set("group.id", &config.group_id)
+        .set("bootstrap.servers", &config.brokers)
+        .set("session.timeout.ms", "6000")
+        .set("enable.auto.commit", "false")
+        .create()?;
+
+    consumer.subscribe(&[LINK_VISIT_TOPIC])?;
+
+    loop {
+        match consumer.recv().await {
+            Ok(message) => {
+                if let Some(payload) = message.payload() {
+                    if let Err(e) =
+                        super::count_visits(&app_mode, &db_pool, &kafka_producer, &None, payload)
+                            .await
+                    {
+                        eprintln!("Failed to count link visits: {e}");
+                    }
+                }
+                if let Err(e) = consumer.commit_message(&message, CommitMode::Async) {
+                    eprintln!("Kafka commit message error: {e}");
+                };
+            }
+            Err(e) => eprintln!("Kafka receive stream error: {e}"),
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use std::{error::Error, time::Duration};
+
+use futures::StreamExt;
+use lapin::Channel;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use reqwest::Client;
+use tonic::{transport, Request};
+use tools_account::proto::account::{AccountServiceClient, GetAccountEmailsReq};
+use tools_lib_db::pg::connection::DbPool;
+use tools_mailer::contract::MailReq;
+use uuid::Uuid;
+
+use crate::helper::link_health;
+
+const TICK_INTERVAL: Duration = Duration::from_secs(60);
+const BATCH_SIZE: i64 = 100;
+pub const MAILER_QUEUE: &str = "mailer";
+
+pub struct Config {
+    pub interval: Duration,
+    pub concurrency: usize,
+    pub failure_threshold: i32,
+    pub allow_private_hosts: bool,
+}
+
+pub struct Notifier {
+    pub account_service: Option<transport::Channel>,
+    pub kafka_producer: Option<FutureProducer>,
+    pub rabbitmq_channel: Option<Channel>,
+}
+
+/// Checks the links that are due every minute, a few at a time, and mails the owners of the
+/// links that break. Nothing is mailed without the account service to look up the owners.
+pub async fn check(
+    app_mode: String,
+    db_pool: DbPool,
+    http_client: Client,
+    notifier: Notifier,
+    config: Config,
+) {
+    let mut interval = tokio::time::interval(TICK_INTERVAL);
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = check_due_links(&app_mode, &db_pool, &http_client, &notifier, &config).await
+        {
+            eprintln!("Failed to check the health of links: {e}");
+        }
+    }
+}
+
+async fn check_due_links(
+    app_mode: &str,
+    db_pool: &DbPool,
+    http_client: &Client,
+    notifier: &Notifier,
+    config: &Config,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    loop {
+        let links = {
+            let db_conn = &mut tools_lib_db::pg::connection::get_connection(app_mode, db_pool)?;
+            link_health::claim_due_links(db_conn, BATCH_SIZE)?
+        };
+        if links.is_empty() {
+            return Ok(());
+        }
+
+        let checks = check_links(http_client, links, config).await;
+
+        let mut broken_link_ids = vec![];
+        {
+            let db_conn = &mut tools_lib_db::pg::connection::get_connection(app_mode, db_pool)?;
+            for (link_id, check) in checks {
+                let is_flagged = link_health::record(
+                    db_conn,
+                    &link_id,
+                    &check,
+                    config.interval,
+                    config.failure_threshold,
+                )?;
+                if is_flagged {
+                    broken_link_ids.push(link_id);
+                }
+            }
+        }
+
+        for link_id in broken_link_ids {
+            // The link stays flagged when the mail can't be sent, so it's only logged
+            if let Err(e) = notify(app_mode, db_pool, notifier, config, &link_id).await {
+                eprintln!("Failed to mail the owners of broken link {link_id}: {e}");
+            }
+        }
+    }
+}
+
+/// Checks the long urls of the links, a few at a time.
+async fn check_links(
+    http_client: &Client,
+    links: Vec<(Uuid, String)>,
+    config: &Config,
+) -> Vec<(Uuid, link_health::Check)> {
+    futures::stream::iter(links)
+        .map(|(link_id, long_url)| async move {
+            let check =
+                link_health::check(http_client, &long_url, config.allow_private_hosts).await;
+            (link_id, check)
+        })
+        .buffer_unordered(config.concurrency)
+        .collect()
+        .await
+}
+
+async fn notify(
+    app_mode: &str,
+    db_pool: &DbPool,
+    notifier: &Notifier,
+    config: &Config,
+    link_id: &Uuid,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let Some(account_service) = &notifier.account_service else {
+        return Ok(());
+    };
+
+    let link_owners = {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(app_mode, db_pool)?;
+        link_health::get_link_owners(db_conn, link_id)?
+    };
+    let Some((short_url, long_url, owner_ids)) = link_owners else {
+        return Ok(());
+    };
+
+    let res = AccountServiceClient::new(account_service.to_owned())
+        .get_account_emails(Request::new(GetAccountEmailsReq {
+            ids: owner_ids.iter().map(Uuid::to_string).collect(),
+        }))
+        .await?;
+
+    let mails = res
+        .get_ref()
+        .accounts
+        .iter()
+        .map(|account| MailReq {
+            to: account.email.to_owned(),
+            subject: format!("Your link {short_url} is broken"),
+            body: format!(
+                "Your link {short_url} failed to reach {long_url} {} times in a row. It will be checked again, edit the link if its destination has moved.",
+                config.failure_threshold
+            ),
+        })
+        .collect::<Vec<MailReq>>();
+    if mails.is_empty() {
+        return Ok(());
+    }
+
+    let payload = serde_json::to_string(&mails)?;
+    if let Some(kafka_producer) = &notifier.kafka_producer {
+        kafka_producer
+            .send_result(FutureRecord::<(), String>::to(MAILER_QUEUE).payload(&payload))
+            .map_err(|e| e.0.to_string())?
+            .await?
+            .map_err(|e| e.0.to_string())?;
+    } else if let Some(rabbitmq_channel) = &notifier.rabbitmq_channel {
+        rabbitmq_channel
+            .basic_publish(
+                "",
+                MAILER_QUEUE,
+                lapin::options::BasicPublishOptions::default(),
+                payload.as_bytes(),
+                lapin::BasicProperties::default(),
+            )
+            .await?
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::{Response, TestServer};
+
+    fn config(concurrency: usize) -> Config {
+        Config {
+            interval: Duration::from_secs(6 * 60 * 60),
+            concurrency,
+            failure_threshold: 3,
+            allow_private_hosts: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn checks_each_link_against_its_long_url() {
+        let healthy_server = TestServer::start(Response::default()).await;
+        let broken_server = TestServer::start(Response {
+            status: 500,
+            ..Response::default()
+        })
+        .await;
+        let healthy_link_id = Uuid::new_v4();
+        let broken_link_id = Uuid::new_v4();
+
+        let checks = check_links(
+            &Client::new(),
+            vec![
+                (healthy_link_id, healthy_server.url),
+                (broken_link_id, broken_server.url),
+            ],
+            &config(2),
+        )
+        .await;
+        assert_eq!(checks.len(), 2);
+        for (link_id, check) in checks {
+            match link_id == healthy_link_id {
+                true => assert_eq!(check.status_code, Some(200)),
+                false => assert_eq!((link_id, check.status_code), (broken_link_id, Some(500))),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn checks_a_few_links_at_a_time() {
+        let server = TestServer::start(Response {
+            delay: Duration::from_millis(100),
+            ..Response::default()
+        })
+        .await;
+        let links = (0..6)
+            .map(|_| (Uuid::new_v4(), server.url.to_owned()))
+            .collect::<Vec<(Uuid, String)>>();
+
+        let checks = check_links(&Client::new(), links, &config(2)).await;
+        assert_eq!(checks.len(), 6);
+        assert!(checks.iter().all(|(_, check)| check.is_healthy()));
+        assert_eq!(server.max_in_flight(), 2);
+    }
+}
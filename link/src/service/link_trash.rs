@@ -0,0 +1,27 @@
+use std::time::Duration;
+
+use tools_lib_db::pg::connection::DbPool;
+
+use crate::helper::link;
+
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Purges the links that have been in the trash for longer than the retention period, every
+/// hour.
+pub async fn purge(app_mode: String, db_pool: DbPool, retention_days: i64) {
+    let mut interval = tokio::time::interval(PURGE_INTERVAL);
+    loop {
+        interval.tick().await;
+
+        let purged = tools_lib_db::pg::connection::get_connection(&app_mode, &db_pool)
+            .map_err(|e| e.to_string())
+            .and_then(|mut db_conn| {
+                link::purge_deleted_links(&mut db_conn, retention_days).map_err(|e| e.to_string())
+            });
+        match purged {
+            Ok(0) => {}
+            Ok(purged) => println!("Purged {purged} links from the trash."),
+            Err(e) => eprintln!("Failed to purge the trash: {e}"),
+        }
+    }
+}
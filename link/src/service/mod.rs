@@ -0,0 +1,30 @@
+use std::error::Error;
+
+use lapin::Channel;
+use rdkafka::producer::FutureProducer;
+use tools_lib_db::pg::connection::DbPool;
+use tools_link::contract::LinkVisit;
+
+use crate::helper::{link, link_event};
+
+pub mod kafka_consumer;
+pub mod link_health;
+pub mod link_trash;
+pub mod rabbitmq_consumer;
+
+async fn count_visits(
+    app_mode: &str,
+    db_pool: &DbPool,
+    kafka_producer: &Option<FutureProducer>,
+    rabbitmq_channel: &Option<Channel>,
+    payload: &[u8],
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let visits: Vec<LinkVisit> = serde_json::from_slice(payload)?;
+    let visited = {
+        let db_conn = &mut tools_lib_db::pg::connection::get_connection(app_mode, db_pool)?;
+        link::add_visits(db_conn, &visits)?
+    };
+    link_event::publish_visited(kafka_producer, rabbitmq_channel, &visited).await;
+
+    Ok(())
+}
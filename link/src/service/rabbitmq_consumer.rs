@@ -0,0 +1,50 @@
+use futures::StreamExt;
+use lapin::{
+    options::{BasicAckOptions, BasicConsumeOptions},
+    types::FieldTable,
+    Channel, Result,
+};
+use tools_lib_db::pg::connection::DbPool;
+use tools_link::contract::LINK_VISIT_TOPIC;
+
+/// Consumes the visits on their own channel and publishes the counted ones on the publishing
+/// channel.
+pub async fn link_visits(
+    channel: Channel,
+    app_mode: String,
+    db_pool: DbPool,
+    rabbitmq_channel: Channel,
+) -> Result<()> {
+    let rabbitmq_channel = Some(rabbitmq_channel);
+    let consumer = &mut channel
+        .basic_consume(
+            LINK_VISIT_TOPIC,
+            "consumer_tag",
+            BasicConsumeOptions::default(),
+            FieldTable::default(),
+        )
+        .await?;
+
+    while let Some(delivery) = consumer.next().await {
+        match delivery {
+            Ok(delivery) => {
+                delivery.ack(BasicAckOptions::default()).await?;
+
+                if let Err(e) = super::count_visits(
+                    &app_mode,
+                    &db_pool,
+                    &None,
+                    &rabbitmq_channel,
+                    &delivery.data,
+                )
+                .await
+                {
+                    eprintln!("Failed to count link visits: {e}");
+                }
+            }
+            Err(e) => eprintln!("Failed to consume queue message: {e}"),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,113 @@
+//! A local HTTP server for the tests of the outgoing requests, like the Open Graph fetch and
+//! the health checks.
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+pub enum Body {
+    Fixed(&'static str),
+    /// The page never ends, spaces follow it until the client stops reading.
+    Endless(&'static str),
+}
+
+/// The response to every request, nothing is sent before the delay.
+pub struct Response {
+    pub status: u16,
+    /// The status of the HEAD requests when it differs from the other requests.
+    pub head_status: Option<u16>,
+    pub content_type: &'static str,
+    pub body: Body,
+    pub delay: Duration,
+}
+
+impl Default for Response {
+    fn default() -> Self {
+        Response {
+            status: 200,
+            head_status: None,
+            content_type: "text/html",
+            body: Body::Fixed(""),
+            delay: Duration::ZERO,
+        }
+    }
+}
+
+pub struct TestServer {
+    pub url: String,
+    max_in_flight: Arc<AtomicUsize>,
+}
+
+impl TestServer {
+    /// Answers every request on a local port with the same response.
+    pub async fn start(res: Response) -> TestServer {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let res = Arc::new(res);
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+
+        let server_max_in_flight = max_in_flight.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let res = res.clone();
+                let in_flight = in_flight.clone();
+                let max_in_flight = server_max_in_flight.clone();
+                tokio::spawn(async move {
+                    let mut req = [0; 4096];
+                    let len = stream.read(&mut req).await.unwrap_or_default();
+                    let is_head = req[..len].starts_with(b"HEAD ");
+
+                    let count = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_in_flight.fetch_max(count, Ordering::SeqCst);
+                    tokio::time::sleep(res.delay).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                    let status = match is_head {
+                        true => res.head_status.unwrap_or(res.status),
+                        false => res.status,
+                    };
+                    let content_length = match res.body {
+                        Body::Fixed(page) => format!("Content-Length: {}\r\n", page.len()),
+                        Body::Endless(_) => String::new(),
+                    };
+                    let head = format!(
+                        "HTTP/1.1 {status} Status\r\nContent-Type: {}\r\n{content_length}Connection: close\r\n\r\n",
+                        res.content_type
+                    );
+                    if stream.write_all(head.as_bytes()).await.is_err() || is_head {
+                        return;
+                    }
+                    match res.body {
+                        Body::Fixed(page) => {
+                            let _ = stream.write_all(page.as_bytes()).await;
+                        }
+                        Body::Endless(page) => {
+                            if stream.write_all(page.as_bytes()).await.is_err() {
+                                return;
+                            }
+                            let padding = [b' '; 64 * 1024];
+                            while stream.write_all(&padding).await.is_ok() {}
+                        }
+                    }
+                });
+            }
+        });
+
+        TestServer { url, max_in_flight }
+    }
+
+    /// The most requests the server answered at once.
+    pub fn max_in_flight(&self) -> usize {
+        self.max_in_flight.load(Ordering::SeqCst)
+    }
+}
@@ -0,0 +1,140 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use tools_link::proto::link::{LinkDeepLink, LinkRedirectStatus};
+
+#[derive(Clone)]
+pub struct CachedLink {
+    pub long_url: String,
+    pub title: String,
+    pub interstitial: bool,
+    pub redirect_status: LinkRedirectStatus,
+    pub referrer_policy: Option<String>,
+    pub no_index: bool,
+    pub deep_link: LinkDeepLink,
+}
+
+/// What a short url resolved to, `None` when the link doesn't exist.
+pub type CachedVisit = Option<CachedLink>;
+
+struct Entry {
+    visit: CachedVisit,
+    expires_at: Instant,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct Entries {
+    visits: HashMap<String, Entry>,
+    // The short urls by the tick they were last used at, the first one is evicted first
+    recency: BTreeMap<u64, String>,
+    tick: u64,
+    invalidations: u64,
+}
+
+impl Entries {
+    fn remove(&mut self, short_url: &str) -> Option<Entry> {
+        let entry = self.visits.remove(short_url)?;
+        self.recency.remove(&entry.last_used);
+        Some(entry)
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+}
+
+/// A least recently used cache of visits with an expiry, shared by the workers.
+pub struct LinkCache {
+    capacity: usize,
+    ttl: Duration,
+    not_found_ttl: Duration,
+    entries: Mutex<Entries>,
+}
+
+impl LinkCache {
+    pub fn new(capacity: usize, ttl: Duration, not_found_ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            not_found_ttl,
+            entries: Mutex::new(Entries::default()),
+        }
+    }
+
+    pub fn get(&self, short_url: &str) -> Option<CachedVisit> {
+        let mut entries = self.entries.lock().unwrap();
+        let mut entry = entries.remove(short_url)?;
+        if entry.expires_at <= Instant::now() {
+            return None;
+        }
+
+        entry.last_used = entries.next_tick();
+        let visit = entry.visit.to_owned();
+        entries
+            .recency
+            .insert(entry.last_used, short_url.to_owned());
+        entries.visits.insert(short_url.to_owned(), entry);
+
+        Some(visit)
+    }
+
+    /// Counts the invalidations, a visit read before one may already be stale.
+    pub fn invalidations(&self) -> u64 {
+        self.entries.lock().unwrap().invalidations
+    }
+
+    /// Caches a visit unless a short url was invalidated since `invalidations` were read.
+    pub fn insert(&self, short_url: &str, visit: CachedVisit, invalidations: u64) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        if entries.invalidations != invalidations {
+            return;
+        }
+
+        entries.remove(short_url);
+        let ttl = match visit {
+            Some(_) => self.ttl,
+            None => self.not_found_ttl,
+        };
+        let last_used = entries.next_tick();
+        entries.recency.insert(last_used, short_url.to_owned());
+        entries.visits.insert(
+            short_url.to_owned(),
+            Entry {
+                visit,
+                expires_at: Instant::now() + ttl,
+                last_used,
+            },
+        );
+
+        // Evict the least recently used visits
+        while entries.visits.len() > self.capacity {
+            let Some((_, short_url)) = entries.recency.pop_first() else {
+                break;
+            };
+            entries.visits.remove(&short_url);
+        }
+    }
+
+    pub fn invalidate(&self, short_url: &str) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.invalidations += 1;
+        entries.remove(short_url);
+    }
+
+    /// Drops every visit, for when invalidations may have been missed.
+    pub fn clear(&self) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.invalidations += 1;
+        entries.visits.clear();
+        entries.recency.clear();
+    }
+}
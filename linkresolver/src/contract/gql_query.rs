@@ -1,4 +1,4 @@
-use cynic::QueryBuilder;
+use cynic::{MutationBuilder, QueryBuilder};
 
 mod schema {
     cynic::use_schema!("./schema/gateway.schema.graphql");
@@ -7,12 +7,41 @@ mod schema {
 #[derive(cynic::QueryVariables)]
 pub struct VisitLinkReq {
     pub short_url: String,
+    pub query: Option<String>,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub accept_language: Option<String>,
+    pub visitor_id: Option<String>,
 }
 
 #[derive(cynic::QueryFragment)]
 #[cynic(schema_path = "./schema/gateway.schema.graphql")]
 pub struct VisitLinkRes {
     pub long_url: String,
+    pub title: String,
+    pub interstitial: bool,
+    pub cacheable: bool,
+    pub redirect_status: LinkRedirectStatus,
+    pub referrer_policy: Option<String>,
+    pub no_index: bool,
+    pub deep_link: LinkDeepLink,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(schema_path = "./schema/gateway.schema.graphql")]
+pub struct LinkDeepLink {
+    pub app_uri: Option<String>,
+    pub android_package: Option<String>,
+    pub ios_fallback_url: Option<String>,
+}
+
+#[derive(cynic::Enum, Clone, Copy)]
+#[cynic(schema_path = "./schema/gateway.schema.graphql")]
+pub enum LinkRedirectStatus {
+    TemporaryRedirect,
+    PermanentRedirect,
+    Found,
+    MovedPermanently,
 }
 
 #[derive(cynic::QueryFragment)]
@@ -22,10 +51,101 @@ pub struct VisitLinkRes {
     variables = "VisitLinkReq"
 )]
 pub struct VisitLinkQuery {
-    #[arguments(shortUrl: $short_url)]
+    #[arguments(
+        shortUrl: $short_url,
+        query: $query,
+        userAgent: $user_agent,
+        ipAddress: $ip_address,
+        acceptLanguage: $accept_language,
+        visitorId: $visitor_id
+    )]
     pub visit_link: VisitLinkRes,
 }
 
-pub fn query_builder(short_url: String) -> cynic::Operation<VisitLinkQuery, VisitLinkReq> {
-    VisitLinkQuery::build(VisitLinkReq { short_url })
+pub fn query_builder(req: VisitLinkReq) -> cynic::Operation<VisitLinkQuery, VisitLinkReq> {
+    VisitLinkQuery::build(req)
+}
+
+#[derive(cynic::QueryVariables)]
+pub struct PreviewLinkReq {
+    pub short_url: String,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(schema_path = "./schema/gateway.schema.graphql")]
+pub struct LinkPreview {
+    pub short_url: String,
+    pub title: String,
+    pub long_url: String,
+    pub visits: i32,
+    pub og_metadata: LinkOgMetadata,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(schema_path = "./schema/gateway.schema.graphql")]
+pub struct LinkOgMetadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(
+    schema_path = "./schema/gateway.schema.graphql",
+    graphql_type = "QueryRootV1",
+    variables = "PreviewLinkReq"
+)]
+pub struct PreviewLinkQuery {
+    #[arguments(shortUrl: $short_url)]
+    pub preview_link: LinkPreview,
+}
+
+pub fn preview_query_builder(
+    req: PreviewLinkReq,
+) -> cynic::Operation<PreviewLinkQuery, PreviewLinkReq> {
+    PreviewLinkQuery::build(req)
+}
+
+#[derive(cynic::QueryVariables)]
+pub struct ReportLinkReq {
+    pub short_url: String,
+    pub reason: LinkReportReason,
+    pub details: Option<String>,
+}
+
+#[derive(cynic::Enum, Clone, Copy)]
+#[cynic(schema_path = "./schema/gateway.schema.graphql")]
+pub enum LinkReportReason {
+    Spam,
+    Phishing,
+    Malware,
+    IllegalContent,
+    Other,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(schema_path = "./schema/gateway.schema.graphql")]
+pub struct OpRes {
+    pub is_success: bool,
+}
+
+#[derive(cynic::QueryFragment)]
+#[cynic(
+    schema_path = "./schema/gateway.schema.graphql",
+    graphql_type = "MutationRootV1",
+    variables = "ReportLinkReq"
+)]
+pub struct ReportLinkMutation {
+    #[arguments(
+        shortUrl: $short_url,
+        reason: $reason,
+        details: $details
+    )]
+    pub report_link: OpRes,
+}
+
+pub fn report_mutation_builder(
+    req: ReportLinkReq,
+) -> cynic::Operation<ReportLinkMutation, ReportLinkReq> {
+    ReportLinkMutation::build(req)
 }
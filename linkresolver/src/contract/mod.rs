@@ -1 +1,4 @@
+pub mod error;
 pub mod gql_query;
+pub mod page;
+pub mod report;
@@ -0,0 +1,63 @@
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct Refresh {
+    pub seconds: u32,
+    pub url: String,
+}
+
+#[derive(Serialize)]
+pub struct ErrorPage<'a> {
+    pub site_link_url: &'a str,
+    pub code: &'a str,
+    pub message: &'a str,
+}
+
+#[derive(Serialize)]
+pub struct PreviewPage<'a> {
+    pub site_link_url: &'a str,
+    pub title: String,
+    pub short_url: String,
+    pub long_url: String,
+    pub destination_domain: String,
+    pub visits: i32,
+}
+
+#[derive(Serialize)]
+pub struct InterstitialPage<'a> {
+    pub site_link_url: &'a str,
+    pub title: String,
+    pub destination_domain: String,
+    pub refresh: Refresh,
+}
+
+#[derive(Serialize)]
+pub struct AppPage<'a> {
+    pub site_link_url: &'a str,
+    pub title: String,
+    pub app_url: String,
+    pub fallback_url: String,
+}
+
+#[derive(Serialize)]
+pub struct OpenGraph {
+    pub url: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub image: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct CardPage<'a> {
+    pub site_link_url: &'a str,
+    pub title: String,
+    pub og: OpenGraph,
+    pub refresh: Refresh,
+}
+
+#[derive(Serialize)]
+pub struct ReportPage<'a> {
+    pub site_link_url: &'a str,
+    pub short_url: String,
+    pub reported: bool,
+}
@@ -0,0 +1,32 @@
+use serde::Deserialize;
+use tools_link::proto::link::LinkReportReason;
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportReason {
+    Spam,
+    Phishing,
+    Malware,
+    IllegalContent,
+    Other,
+}
+
+impl ReportReason {
+    pub fn to_grpc_enum(self) -> i32 {
+        let reason = match self {
+            ReportReason::Spam => LinkReportReason::Spam,
+            ReportReason::Phishing => LinkReportReason::Phishing,
+            ReportReason::Malware => LinkReportReason::Malware,
+            ReportReason::IllegalContent => LinkReportReason::IllegalContent,
+            ReportReason::Other => LinkReportReason::Other,
+        };
+        reason.into()
+    }
+}
+
+/// A report sent from the report page as a form, or from elsewhere as JSON.
+#[derive(Deserialize)]
+pub struct ReportLinkReq {
+    pub reason: ReportReason,
+    pub details: Option<String>,
+}
@@ -1,2 +1,7 @@
+pub mod not_found;
+pub mod preview;
+pub mod report;
 pub mod resolver;
+pub mod robots;
 pub mod root;
+pub mod well_known;
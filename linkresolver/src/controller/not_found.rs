@@ -0,0 +1,8 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+
+use crate::{dto::AppData, helper, service::link_service::LinkServiceError};
+
+/// Renders the not found page for the paths that aren't links.
+pub async fn not_found(req: HttpRequest, data: web::Data<AppData>) -> HttpResponse {
+    helper::error_page(&req, &data, LinkServiceError::NotFound(None))
+}
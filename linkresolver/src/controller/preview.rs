@@ -0,0 +1,29 @@
+use actix_web::{get, web, HttpRequest, HttpResponse};
+
+use crate::{contract, dto::AppData, helper, service::link_service, template};
+
+#[get("/{short_url}+")]
+pub async fn preview_link(
+    req: HttpRequest,
+    data: web::Data<AppData>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    let preview = match link_service::preview_link(&data, path.into_inner()).await {
+        Ok(preview) => preview,
+        Err(e) => return helper::error_page(&req, &data, e),
+    };
+
+    template::render(
+        &data.templates,
+        &mut HttpResponse::Ok(),
+        template::PREVIEW,
+        &contract::page::PreviewPage {
+            site_link_url: &data.site_link_url,
+            destination_domain: helper::destination_domain(&preview.long_url),
+            title: preview.title,
+            short_url: preview.short_url,
+            long_url: preview.long_url,
+            visits: preview.visits,
+        },
+    )
+}
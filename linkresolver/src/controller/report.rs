@@ -0,0 +1,68 @@
+use actix_web::{get, post, web, Either, HttpRequest, HttpResponse};
+use tools_link::proto::link::ReportLinkReq;
+
+use crate::{
+    contract::{self, page::ReportPage},
+    dto::AppData,
+    helper,
+    service::link_service,
+    template,
+};
+
+#[get("/{short_url}/report")]
+pub async fn report_link_page(data: web::Data<AppData>, path: web::Path<String>) -> HttpResponse {
+    template::render(
+        &data.templates,
+        &mut HttpResponse::Ok(),
+        template::REPORT,
+        &ReportPage {
+            site_link_url: &data.site_link_url,
+            short_url: path.into_inner(),
+            reported: false,
+        },
+    )
+}
+
+#[post("/{short_url}/report")]
+pub async fn report_link(
+    req: HttpRequest,
+    data: web::Data<AppData>,
+    path: web::Path<String>,
+    body: Either<
+        web::Json<contract::report::ReportLinkReq>,
+        web::Form<contract::report::ReportLinkReq>,
+    >,
+) -> HttpResponse {
+    let short_url = path.into_inner();
+    let body = body.into_inner();
+    let reporter_ip = helper::client_ip(&req, &data);
+
+    let res = link_service::report_link(
+        &data,
+        ReportLinkReq {
+            short_url: short_url.to_owned(),
+            reason: body.reason.to_grpc_enum(),
+            details: body.details,
+            reporter_ip,
+        },
+    )
+    .await;
+    if let Err(e) = res {
+        return helper::error_page(&req, &data, e);
+    }
+
+    if helper::prefers_json(&req) {
+        return HttpResponse::Accepted().finish();
+    }
+
+    template::render(
+        &data.templates,
+        &mut HttpResponse::Ok(),
+        template::REPORT,
+        &ReportPage {
+            site_link_url: &data.site_link_url,
+            short_url,
+            reported: true,
+        },
+    )
+}
@@ -1,51 +1,457 @@
-use actix_web::{get, web, HttpResponse};
-use cynic::http::ReqwestExt;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::{contract, dto::AppData, static_file};
+use actix_web::{
+    cookie::{time::Duration, Cookie},
+    get,
+    http::header::{CacheControl, CacheDirective, Expires},
+    web, HttpRequest, HttpResponse,
+};
+use tools_link::proto::link::{LinkRedirectStatus, VisitLinkReq};
+use uuid::Uuid;
 
-#[get("/{short_url}")]
-pub async fn resolve_link(data: web::Data<AppData>, path: web::Path<String>) -> HttpResponse {
-    let app_mode = data.app_mode.as_str();
-    let gateway_service = data.gql_addrs.as_str();
+use crate::{
+    cache::CachedLink,
+    contract,
+    dto::AppData,
+    helper,
+    service::link_service::{self, LinkServiceError},
+    template,
+};
+
+const VISITOR_COOKIE_NAME: &str = "visitor_id";
+const VISITOR_COOKIE_MAX_AGE_DAYS: i64 = 365;
+const INTERSTITIAL_SECONDS: u32 = 5;
 
+#[get("/{short_url}")]
+pub async fn resolve_link(
+    req: HttpRequest,
+    data: web::Data<AppData>,
+    path: web::Path<String>,
+) -> HttpResponse {
     let short_url = path.into_inner();
 
     if short_url == "favicon.ico" {
         return HttpResponse::NotFound().finish();
     }
 
-    let query = contract::gql_query::query_builder(short_url);
-
-    let res = match reqwest::Client::new()
-        .post(gateway_service)
-        .run_graphql(query)
-        .await
+    // Crawlers get a page with the metadata of the link instead of following the redirect
+    let user_agent = req
+        .headers()
+        .get("user-agent")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned);
+    if user_agent
+        .as_deref()
+        .map(helper::is_crawler)
+        .unwrap_or(false)
     {
+        let url = {
+            let connection_info = req.connection_info();
+            format!(
+                "{}://{}{}",
+                connection_info.scheme(),
+                connection_info.host(),
+                req.path()
+            )
+        };
+        return resolve_link_for_crawler(&req, &data, short_url, url).await;
+    }
+
+    // Phones are sent to the app of the link when it has one
+    let app_platform = user_agent.as_deref().and_then(helper::app_platform);
+
+    // Pass the query string along, the link decides whether it is forwarded
+    let query_string = match req.query_string() {
+        "" => None,
+        query_string => Some(query_string.to_owned()),
+    };
+
+    // Pass what the redirect rules of the link are matched against
+    let header = |name: &str| {
+        req.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned)
+    };
+    let ip_address = helper::client_ip(&req, &data);
+
+    // Identify the visitor so that they keep getting the same variant of a link
+    let (visitor_id, visitor_cookie) = match req.cookie(VISITOR_COOKIE_NAME) {
+        Some(visitor_cookie) => (visitor_cookie.value().to_owned(), None),
+        None => {
+            let visitor_id = Uuid::new_v4().to_string();
+            let visitor_cookie = Cookie::build(VISITOR_COOKIE_NAME, visitor_id.to_owned())
+                .path("/")
+                .max_age(Duration::days(VISITOR_COOKIE_MAX_AGE_DAYS))
+                .http_only(true)
+                .finish();
+            (visitor_id, Some(visitor_cookie))
+        }
+    };
+
+    // Serve the cached links without the link service, their visits are reported in the background
+    if let (Some(link_cache), Some(visit_sender)) = (&data.link_cache, &data.visit_sender) {
+        match link_cache.get(&short_url) {
+            Some(Some(link)) => {
+                let _ = visit_sender.send(short_url);
+                return visit(&data, link, app_platform, visitor_cookie);
+            }
+            Some(None) => return helper::error_page(&req, &data, LinkServiceError::NotFound(None)),
+            None => {}
+        }
+    }
+    let link_cache = data
+        .link_cache
+        .as_ref()
+        .map(|link_cache| (link_cache, link_cache.invalidations()));
+
+    let res = link_service::visit_link(
+        &data,
+        VisitLinkReq {
+            short_url: short_url.to_owned(),
+            query: query_string,
+            user_agent,
+            ip_address,
+            accept_language: header("accept-language"),
+            visitor_id: Some(visitor_id),
+        },
+    )
+    .await;
+
+    let res = match res {
         Ok(res) => res,
-        Err(_) => return HttpResponse::InternalServerError().finish(),
+        Err(e) => {
+            if let (Some((link_cache, invalidations)), LinkServiceError::NotFound(_)) =
+                (link_cache, &e)
+            {
+                link_cache.insert(&short_url, None, invalidations);
+            }
+            return helper::error_page(&req, &data, e);
+        }
+    };
+    let link = CachedLink {
+        redirect_status: res.redirect_status(),
+        long_url: res.long_url,
+        title: res.title,
+        interstitial: res.interstitial,
+        referrer_policy: res.referrer_policy,
+        no_index: res.no_index,
+        deep_link: res.deep_link.unwrap_or_default(),
     };
+    if let Some((link_cache, invalidations)) = link_cache {
+        if res.cacheable {
+            link_cache.insert(&short_url, Some(link.to_owned()), invalidations);
+        }
+    }
 
-    if let Some(data) = res.data {
-        return HttpResponse::TemporaryRedirect()
-            .insert_header(("location", data.visit_link.long_url))
-            .finish();
+    visit(&data, link, app_platform, visitor_cookie)
+}
+
+/// Sends the visitor to the long url of a link, through the interstitial page when the
+/// link has one, or to its app first when the visitor is on a phone.
+fn visit(
+    data: &AppData,
+    link: CachedLink,
+    app_platform: Option<helper::AppPlatform>,
+    visitor_cookie: Option<Cookie<'static>>,
+) -> HttpResponse {
+    let app_page = app_page(data, &link, app_platform);
+    let mut http_res = match (
+        link.interstitial || app_page.is_some(),
+        link.redirect_status,
+    ) {
+        (true, _) => HttpResponse::Ok(),
+        (false, LinkRedirectStatus::TemporaryRedirect) => HttpResponse::TemporaryRedirect(),
+        (false, LinkRedirectStatus::PermanentRedirect) => HttpResponse::PermanentRedirect(),
+        (false, LinkRedirectStatus::Found) => HttpResponse::Found(),
+        (false, LinkRedirectStatus::MovedPermanently) => HttpResponse::MovedPermanently(),
+    };
+    if let Some(visitor_cookie) = visitor_cookie {
+        http_res.cookie(visitor_cookie);
     }
 
-    let mut http_res = &mut HttpResponse::NotFound();
-    if let Some(errors) = res.errors {
-        if app_mode == "DEBUG" {
-            let err = errors
-                .iter()
-                .map(|error| {
-                    format!(
-                        "message: {}, locations: {:#?}, path: {:#?}, extensions: {:#?}",
-                        error.message, error.locations, error.path, error.extensions
-                    )
-                })
-                .collect::<Vec<String>>()
-                .join(", ");
-            http_res = http_res.insert_header(("LINK-ERRORS", err));
+    // Let browsers keep permanent redirects, the others must come back so that their visits
+    // are counted
+    let permanent = matches!(
+        link.redirect_status,
+        LinkRedirectStatus::PermanentRedirect | LinkRedirectStatus::MovedPermanently
+    );
+    match permanent && !link.interstitial && app_page.is_none() {
+        true => http_res
+            .insert_header(CacheControl(vec![
+                CacheDirective::Private,
+                CacheDirective::MaxAge(data.permanent_redirect_max_age.as_secs() as u32),
+            ]))
+            .insert_header(Expires(
+                (SystemTime::now() + data.permanent_redirect_max_age).into(),
+            )),
+        false => http_res
+            .insert_header(CacheControl(vec![CacheDirective::NoStore]))
+            .insert_header(Expires(UNIX_EPOCH.into())),
+    };
+    if let Some(referrer_policy) = link.referrer_policy {
+        http_res.insert_header(("referrer-policy", referrer_policy));
+    }
+    if link.no_index {
+        http_res.insert_header(("x-robots-tag", "noindex"));
+    }
+
+    // Try to open the app before falling back to the browser
+    if let Some(app_page) = app_page {
+        return template::render(&data.templates, &mut http_res, template::APP, &app_page);
+    }
+
+    // Tell the visitor where they are going before redirecting them
+    if link.interstitial {
+        return template::render(
+            &data.templates,
+            &mut http_res,
+            template::INTERSTITIAL,
+            &contract::page::InterstitialPage {
+                site_link_url: &data.site_link_url,
+                title: link.title,
+                destination_domain: helper::destination_domain(&link.long_url),
+                refresh: contract::page::Refresh {
+                    seconds: INTERSTITIAL_SECONDS,
+                    url: link.long_url,
+                },
+            },
+        );
+    }
+
+    http_res.insert_header(("location", link.long_url)).finish()
+}
+
+/// Gets the page that opens the app of a link on the platform of the visitor, `None` when
+/// the link has no app.
+fn app_page<'a>(
+    data: &'a AppData,
+    link: &CachedLink,
+    app_platform: Option<helper::AppPlatform>,
+) -> Option<contract::page::AppPage<'a>> {
+    let app_uri = link.deep_link.app_uri.as_ref()?;
+    let (app_url, fallback_url) = match app_platform? {
+        helper::AppPlatform::Android => (
+            match &link.deep_link.android_package {
+                Some(package) => helper::android_intent(app_uri, package, &link.long_url),
+                None => app_uri.to_owned(),
+            },
+            link.long_url.to_owned(),
+        ),
+        helper::AppPlatform::Ios => (
+            app_uri.to_owned(),
+            link.deep_link
+                .ios_fallback_url
+                .to_owned()
+                .unwrap_or_else(|| link.long_url.to_owned()),
+        ),
+    };
+
+    Some(contract::page::AppPage {
+        site_link_url: &data.site_link_url,
+        title: link.title.to_owned(),
+        app_url,
+        fallback_url,
+    })
+}
+
+async fn resolve_link_for_crawler(
+    req: &HttpRequest,
+    data: &AppData,
+    short_url: String,
+    url: String,
+) -> HttpResponse {
+    let preview = match link_service::preview_link(data, short_url).await {
+        Ok(preview) => preview,
+        Err(e) => return helper::error_page(req, data, e),
+    };
+    let og_metadata = preview.og_metadata.unwrap_or_default();
+    let title = og_metadata.title.unwrap_or(preview.title);
+
+    template::render(
+        &data.templates,
+        &mut HttpResponse::Ok(),
+        template::CARD,
+        &contract::page::CardPage {
+            site_link_url: &data.site_link_url,
+            title: title.to_owned(),
+            og: contract::page::OpenGraph {
+                url,
+                title,
+                description: og_metadata.description,
+                image: og_metadata.image,
+            },
+            refresh: contract::page::Refresh {
+                seconds: 0,
+                url: preview.long_url,
+            },
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use actix_web::{
+        http::{header, StatusCode},
+        test, web, App,
+    };
+    use tools_link::proto::link::{LinkDeepLink, LinkRedirectStatus};
+
+    use crate::{
+        cache::{CachedLink, LinkCache},
+        dto::AppData,
+        template,
+    };
+
+    const LONG_URL: &str = "https://example.com/destination";
+    const PERMANENT_REDIRECT_MAX_AGE: Duration = Duration::from_secs(86400);
+    const EPOCH: &str = "Thu, 01 Jan 1970 00:00:00 GMT";
+    const IPHONE: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 16_0 like Mac OS X)";
+
+    fn link(redirect_status: LinkRedirectStatus) -> CachedLink {
+        CachedLink {
+            long_url: LONG_URL.to_string(),
+            title: "Example".to_string(),
+            interstitial: false,
+            redirect_status,
+            referrer_policy: None,
+            no_index: false,
+            deep_link: LinkDeepLink::default(),
+        }
+    }
+
+    /// Visits a link that is in the cache, so that no link service is needed.
+    async fn visit(link: CachedLink, user_agent: Option<&str>) -> actix_web::dev::ServiceResponse {
+        let link_cache = Arc::new(LinkCache::new(
+            16,
+            Duration::from_secs(60),
+            Duration::from_secs(60),
+        ));
+        link_cache.insert("abc", Some(link), link_cache.invalidations());
+        let (visit_sender, _) = tokio::sync::mpsc::unbounded_channel();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(AppData {
+                    app_mode: "DEBUG".to_string(),
+                    gql_addrs: String::new(),
+                    site_link_url: "http://localhost:3002".to_string(),
+                    permanent_redirect_max_age: PERMANENT_REDIRECT_MAX_AGE,
+                    templates: template::registry(None).unwrap(),
+                    apple_app_site_association: None,
+                    asset_links: None,
+                    http_client: reqwest::Client::new(),
+                    trusted_proxies: vec![],
+                    link_service: None,
+                    link_cache: Some(link_cache),
+                    visit_sender: Some(visit_sender),
+                }))
+                .service(super::resolve_link),
+        )
+        .await;
+
+        let mut req = test::TestRequest::get().uri("/abc");
+        if let Some(user_agent) = user_agent {
+            req = req.insert_header((header::USER_AGENT, user_agent));
         }
+        test::call_service(&app, req.to_request()).await
+    }
+
+    fn header<'a>(res: &'a actix_web::dev::ServiceResponse, name: &str) -> Option<&'a str> {
+        res.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    }
+
+    #[actix_web::test]
+    async fn redirects_with_the_status_of_the_link() {
+        for (redirect_status, status) in [
+            (
+                LinkRedirectStatus::MovedPermanently,
+                StatusCode::MOVED_PERMANENTLY,
+            ),
+            (LinkRedirectStatus::Found, StatusCode::FOUND),
+            (
+                LinkRedirectStatus::TemporaryRedirect,
+                StatusCode::TEMPORARY_REDIRECT,
+            ),
+            (
+                LinkRedirectStatus::PermanentRedirect,
+                StatusCode::PERMANENT_REDIRECT,
+            ),
+        ] {
+            let res = visit(link(redirect_status), None).await;
+            assert_eq!(res.status(), status);
+            assert_eq!(header(&res, "location"), Some(LONG_URL));
+        }
+    }
+
+    #[actix_web::test]
+    async fn lets_browsers_keep_permanent_redirects() {
+        for redirect_status in [
+            LinkRedirectStatus::MovedPermanently,
+            LinkRedirectStatus::PermanentRedirect,
+        ] {
+            let res = visit(link(redirect_status), None).await;
+            assert_eq!(
+                header(&res, "cache-control"),
+                Some("private, max-age=86400")
+            );
+            let expires = header(&res, "expires").unwrap();
+            assert_ne!(expires, EPOCH);
+        }
+    }
+
+    #[actix_web::test]
+    async fn keeps_browsers_from_caching_the_other_visits() {
+        let interstitial = CachedLink {
+            interstitial: true,
+            ..link(LinkRedirectStatus::PermanentRedirect)
+        };
+        let app = CachedLink {
+            deep_link: LinkDeepLink {
+                app_uri: Some("example://open".to_string()),
+                android_package: None,
+                ios_fallback_url: None,
+            },
+            ..link(LinkRedirectStatus::PermanentRedirect)
+        };
+
+        for (link, user_agent, status) in [
+            (link(LinkRedirectStatus::Found), None, StatusCode::FOUND),
+            (
+                link(LinkRedirectStatus::TemporaryRedirect),
+                None,
+                StatusCode::TEMPORARY_REDIRECT,
+            ),
+            (interstitial, None, StatusCode::OK),
+            (app, Some(IPHONE), StatusCode::OK),
+        ] {
+            let res = visit(link, user_agent).await;
+            assert_eq!(res.status(), status);
+            assert_eq!(header(&res, "cache-control"), Some("no-store"));
+            assert_eq!(header(&res, "expires"), Some(EPOCH));
+        }
+    }
+
+    #[actix_web::test]
+    async fn sends_the_referrer_policy_and_the_robots_tag_of_the_link() {
+        let res = visit(
+            CachedLink {
+                referrer_policy: Some("no-referrer".to_string()),
+                no_index: true,
+                ..link(LinkRedirectStatus::Found)
+            },
+            None,
+        )
+        .await;
+        assert_eq!(header(&res, "referrer-policy"), Some("no-referrer"));
+        assert_eq!(header(&res, "x-robots-tag"), Some("noindex"));
+
+        let res = visit(link(LinkRedirectStatus::Found), None).await;
+        assert_eq!(header(&res, "referrer-policy"), None);
+        assert_eq!(header(&res, "x-robots-tag"), None);
     }
-    http_res.body(static_file::NOT_FOUND)
 }
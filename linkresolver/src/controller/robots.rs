@@ -0,0 +1,36 @@
+use actix_web::{get, HttpResponse};
+
+// Keep crawlers on the short urls, which lead elsewhere, but out of the preview pages
+const ROBOTS: &str = "User-agent: *\nDisallow: /*+$\n";
+
+#[get("/robots.txt")]
+pub async fn robots() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .body(ROBOTS)
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{http::header, test, App};
+
+    #[actix_web::test]
+    async fn serves_the_robots_file() {
+        let app = test::init_service(App::new().service(super::robots)).await;
+
+        let res = test::call_service(
+            &app,
+            test::TestRequest::get().uri("/robots.txt").to_request(),
+        )
+        .await;
+        assert!(res.status().is_success());
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/plain; charset=utf-8"
+        );
+        assert_eq!(
+            test::read_body(res).await,
+            "User-agent: *\nDisallow: /*+$\n"
+        );
+    }
+}
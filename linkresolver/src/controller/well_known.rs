@@ -0,0 +1,66 @@
+use actix_web::{get, web, HttpResponse};
+use serde_json::json;
+
+use crate::dto::AppData;
+
+/// Builds the file that lets the Apple apps open the short urls, but not the preview pages.
+pub fn apple_app_site_association(app_ids: &[String]) -> Option<String> {
+    if app_ids.is_empty() {
+        return None;
+    }
+
+    let details: Vec<_> = app_ids
+        .iter()
+        .map(|app_id| {
+            json!({
+                "appID": app_id,
+                "appIDs": [app_id],
+                "paths": ["NOT /*+", "*"],
+                "components": [
+                    { "/": "/*+", "exclude": true },
+                    { "/": "*" },
+                ],
+            })
+        })
+        .collect();
+
+    Some(json!({ "applinks": { "apps": [], "details": details } }).to_string())
+}
+
+/// Builds the file that lets the Android app open the short urls.
+pub fn asset_links(package: &str, cert_fingerprints: &[String]) -> Option<String> {
+    if cert_fingerprints.is_empty() {
+        return None;
+    }
+
+    Some(
+        json!([{
+            "relation": ["delegate_permission/common.handle_all_urls"],
+            "target": {
+                "namespace": "android_app",
+                "package_name": package,
+                "sha256_cert_fingerprints": cert_fingerprints,
+            },
+        }])
+        .to_string(),
+    )
+}
+
+#[get("/{path:(\\.well-known/)?apple-app-site-association}")]
+pub async fn apple_app_site_association_file(data: web::Data<AppData>) -> HttpResponse {
+    json_file(&data.apple_app_site_association)
+}
+
+#[get("/.well-known/assetlinks.json")]
+pub async fn asset_links_file(data: web::Data<AppData>) -> HttpResponse {
+    json_file(&data.asset_links)
+}
+
+fn json_file(file: &Option<String>) -> HttpResponse {
+    match file {
+        Some(file) => HttpResponse::Ok()
+            .content_type("application/json")
+            .body(file.to_owned()),
+        None => HttpResponse::NotFound().finish(),
+    }
+}
@@ -1,5 +1,21 @@
+use std::{net::IpAddr, sync::Arc, time::Duration};
+
+use handlebars::Handlebars;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{cache::LinkCache, service::link_service::LinkServicePool};
+
 pub struct AppData {
     pub app_mode: String,
     pub gql_addrs: String,
     pub site_link_url: String,
+    pub permanent_redirect_max_age: Duration,
+    pub templates: Handlebars<'static>,
+    pub apple_app_site_association: Option<String>,
+    pub asset_links: Option<String>,
+    pub http_client: reqwest::Client,
+    pub trusted_proxies: Vec<IpAddr>,
+    pub link_service: Option<LinkServicePool>,
+    pub link_cache: Option<Arc<LinkCache>>,
+    pub visit_sender: Option<UnboundedSender<String>>,
 }
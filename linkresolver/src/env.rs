@@ -1,4 +1,4 @@
-use std::env;
+use std::{env, net::IpAddr, path::PathBuf, time::Duration};
 
 pub struct Env;
 
@@ -26,4 +26,129 @@ impl Env {
     pub fn site_link_url() -> String {
         env::var("SITE_LINK_URL").unwrap()
     }
+
+    pub fn theme_dir() -> Option<PathBuf> {
+        match env::var("THEME_DIR").unwrap().as_str() {
+            "" => None,
+            theme_dir => Some(PathBuf::from(theme_dir)),
+        }
+    }
+
+    pub fn permanent_redirect_max_age() -> Duration {
+        Duration::from_secs(
+            env::var("PERMANENT_REDIRECT_MAX_AGE")
+                .unwrap()
+                .parse()
+                .unwrap(),
+        )
+    }
+
+    pub fn apple_app_ids() -> Vec<String> {
+        split_list(&env::var("APPLE_APP_IDS").unwrap())
+    }
+
+    pub fn android_app_package() -> Option<String> {
+        match env::var("ANDROID_APP_PACKAGE").unwrap().as_str() {
+            "" => None,
+            android_app_package => Some(android_app_package.to_owned()),
+        }
+    }
+
+    pub fn android_app_cert_fingerprints() -> Vec<String> {
+        split_list(&env::var("ANDROID_APP_CERT_FINGERPRINTS").unwrap())
+    }
+
+    /// The proxies in front of the link resolver, only their forwarded headers are read for
+    /// the address of the visitor.
+    pub fn trusted_proxies() -> Vec<IpAddr> {
+        split_list(&env::var("TRUSTED_PROXIES").unwrap_or_default())
+            .iter()
+            .filter_map(|addr| addr.parse().ok())
+            .collect()
+    }
+
+    pub fn link_service_mode() -> LinkServiceMode {
+        LinkServiceMode(env::var("LINK_SERVICE_MODE").unwrap())
+    }
+
+    pub fn link_service_addrs() -> String {
+        env::var("LINK_SERVICE_ADDRS").unwrap()
+    }
+
+    pub fn link_service_pool_size() -> usize {
+        env::var("LINK_SERVICE_POOL_SIZE").unwrap().parse().unwrap()
+    }
+
+    pub fn grpc_connect_timeout() -> Duration {
+        Duration::from_millis(env::var("GRPC_CONNECT_TIMEOUT").unwrap().parse().unwrap())
+    }
+
+    pub fn grpc_request_timeout() -> Duration {
+        Duration::from_millis(env::var("GRPC_REQUEST_TIMEOUT").unwrap().parse().unwrap())
+    }
+
+    pub fn use_msg_broker() -> UseMsgBroker {
+        UseMsgBroker(env::var("USE_MSG_BROKER").unwrap())
+    }
+
+    pub fn kafka_addrs() -> String {
+        env::var("KAFKA_ADDRS").unwrap()
+    }
+
+    pub fn kafka_group_id() -> String {
+        env::var("KAFKA_GROUP_ID").unwrap()
+    }
+
+    pub fn kafka_msg_timeout() -> String {
+        env::var("KAFKA_MSG_TIMEOUT").unwrap()
+    }
+
+    pub fn rabbitmq_addrs() -> String {
+        env::var("RABBITMQ_ADDRS").unwrap()
+    }
+
+    pub fn link_cache_capacity() -> usize {
+        env::var("LINK_CACHE_CAPACITY").unwrap().parse().unwrap()
+    }
+
+    pub fn link_cache_ttl() -> Duration {
+        Duration::from_secs(env::var("LINK_CACHE_TTL").unwrap().parse().unwrap())
+    }
+
+    pub fn link_cache_not_found_ttl() -> Duration {
+        Duration::from_secs(
+            env::var("LINK_CACHE_NOT_FOUND_TTL")
+                .unwrap()
+                .parse()
+                .unwrap(),
+        )
+    }
+}
+
+fn split_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+pub struct LinkServiceMode(String);
+
+impl LinkServiceMode {
+    pub fn is_grpc(&self) -> bool {
+        self.0 == "GRPC"
+    }
+}
+
+pub struct UseMsgBroker(String);
+
+impl UseMsgBroker {
+    pub fn is_kafka(&self) -> bool {
+        self.0 == "KAFKA"
+    }
+
+    pub fn is_rabbitmq(&self) -> bool {
+        self.0 == "RABBITMQ"
+    }
 }
@@ -0,0 +1,243 @@
+use std::fmt::Debug;
+
+use actix_web::{
+    http::{
+        header::{Accept, Header},
+        StatusCode,
+    },
+    HttpRequest, HttpResponse,
+};
+use cynic::GraphQlError;
+
+use crate::{
+    contract::{error::ErrorRes, page::ErrorPage},
+    dto::AppData,
+    service::link_service::LinkServiceError,
+    template,
+};
+
+/// How many seconds the visitors are asked to wait when the links are unavailable or they
+/// are rate limited.
+const RETRY_AFTER_SECONDS: u32 = 30;
+
+/// Renders the page of a link that can't be visited, or its JSON when the client prefers
+/// JSON. The errors of the link service are only shown in debug mode.
+pub fn error_page(req: &HttpRequest, data: &AppData, error: LinkServiceError) -> HttpResponse {
+    let (status, code, message, template_name, errors) = match error {
+        LinkServiceError::NotFound(errors) => (
+            StatusCode::NOT_FOUND,
+            "NOT_FOUND",
+            "The link is not found.",
+            template::NOT_FOUND,
+            errors,
+        ),
+        LinkServiceError::Expired(errors) => (
+            StatusCode::GONE,
+            "EXPIRED",
+            "The link has expired.",
+            template::EXPIRED,
+            errors,
+        ),
+        LinkServiceError::Disabled(errors) => (
+            StatusCode::FORBIDDEN,
+            "DISABLED",
+            "The link has been disabled.",
+            template::DISABLED,
+            errors,
+        ),
+        LinkServiceError::RateLimited(errors) => (
+            StatusCode::TOO_MANY_REQUESTS,
+            "RATE_LIMITED",
+            "Too many requests, try again later.",
+            template::RATE_LIMITED,
+            errors,
+        ),
+        LinkServiceError::Rejected(errors) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "UNAVAILABLE",
+            "The link can't be reached right now, try again later.",
+            template::UNAVAILABLE,
+            errors,
+        ),
+        LinkServiceError::Unreachable => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "UNAVAILABLE",
+            "The link can't be reached right now, try again later.",
+            template::UNAVAILABLE,
+            None,
+        ),
+    };
+    let errors = errors.filter(|_| data.app_mode == "DEBUG");
+
+    let mut http_res = HttpResponse::build(status);
+    if matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+    ) {
+        http_res.insert_header(("retry-after", RETRY_AFTER_SECONDS));
+    }
+
+    if prefers_json(req) {
+        return http_res.json(ErrorRes {
+            code,
+            message,
+            errors,
+        });
+    }
+
+    if let Some(errors) = errors {
+        http_res.insert_header(("LINK-ERRORS", errors));
+    }
+
+    template::render(
+        &data.templates,
+        &mut http_res,
+        template_name,
+        &ErrorPage {
+            site_link_url: &data.site_link_url,
+            code,
+            message,
+        },
+    )
+}
+
+/// Gives the IP address of the visitor. The forwarded headers are only read when the request
+/// comes from a trusted proxy, anyone else could set them to any address.
+pub fn client_ip(req: &HttpRequest, data: &AppData) -> Option<String> {
+    let peer_addr = req.peer_addr()?.ip();
+    if !data.trusted_proxies.contains(&peer_addr) {
+        return Some(peer_addr.to_string());
+    }
+
+    req.connection_info()
+        .realip_remote_addr()
+        .map(str::to_owned)
+}
+
+/// Checks if the client asks for JSON over HTML.
+pub fn prefers_json(req: &HttpRequest) -> bool {
+    Accept::parse(req)
+        .map(|accept| accept.preference().essence_str() == "application/json")
+        .unwrap_or(false)
+}
+
+pub fn gql_errors<E: Debug>(errors: &Option<Vec<GraphQlError<E>>>) -> Option<String> {
+    errors.as_ref().map(|errors| {
+        errors
+            .iter()
+            .map(|error| {
+                format!(
+                    "message: {}, locations: {:#?}, path: {:#?}, extensions: {:#?}",
+                    error.message, error.locations, error.path, error.extensions
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    })
+}
+
+/// Gets the first code the gateway added to its errors.
+pub fn gql_error_code(errors: &Option<Vec<GraphQlError<serde_json::Value>>>) -> Option<&str> {
+    errors
+        .iter()
+        .flatten()
+        .filter_map(|error| error.extensions.as_ref())
+        .find_map(|extensions| extensions["code"].as_str())
+}
+
+/// User agents of the crawlers that build link previews for chats and social networks.
+const CRAWLER_USER_AGENTS: [&str; 18] = [
+    "facebookexternalhit",
+    "facebot",
+    "twitterbot",
+    "slackbot",
+    "slack-imgproxy",
+    "discordbot",
+    "telegrambot",
+    "whatsapp",
+    "linkedinbot",
+    "skypeuripreview",
+    "pinterest",
+    "redditbot",
+    "applebot",
+    "googlebot",
+    "bingbot",
+    "embedly",
+    "iframely",
+    "mastodon",
+];
+
+pub fn is_crawler(user_agent: &str) -> bool {
+    let user_agent = user_agent.to_ascii_lowercase();
+    CRAWLER_USER_AGENTS
+        .iter()
+        .any(|crawler| user_agent.contains(crawler))
+}
+
+pub enum AppPlatform {
+    Android,
+    Ios,
+}
+
+/// Gets the phone platform a deep link can open an app on.
+pub fn app_platform(user_agent: &str) -> Option<AppPlatform> {
+    let user_agent = user_agent.to_ascii_lowercase();
+    if user_agent.contains("android") {
+        Some(AppPlatform::Android)
+    } else if ["iphone", "ipad", "ipod"]
+        .iter()
+        .any(|device| user_agent.contains(device))
+    {
+        Some(AppPlatform::Ios)
+    } else {
+        None
+    }
+}
+
+/// Turns the app uri of a deep link into an Android intent that opens the package, or
+/// the Play Store when the package isn't installed, and Chrome goes to the fallback url
+/// when neither works.
+pub fn android_intent(app_uri: &str, package: &str, fallback_url: &str) -> String {
+    let (scheme, rest) = app_uri
+        .split_once("://")
+        .or_else(|| app_uri.split_once(':'))
+        .unwrap_or((app_uri, ""));
+    let rest = rest.split('#').next().unwrap_or_default();
+
+    format!(
+        "intent://{rest}#Intent;scheme={scheme};package={package};S.browser_fallback_url={};end",
+        percent_encode(fallback_url)
+    )
+}
+
+/// Encodes everything but the unreserved characters of a url.
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+/// Gets the host of a url without the user info and the port.
+pub fn destination_domain(url: &str) -> String {
+    let authority = url
+        .split_once("://")
+        .map_or(url, |(_, rest)| rest)
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default();
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = match host.strip_prefix('[') {
+        Some(ipv6_host) => ipv6_host.split(']').next().unwrap_or_default(),
+        None => host.split(':').next().unwrap_or_default(),
+    };
+
+    host.to_lowercase()
+}
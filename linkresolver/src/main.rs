@@ -1,14 +1,27 @@
-use std::io::Result;
+use std::{
+    fmt::Display,
+    future::Future,
+    io::{Error, ErrorKind, Result},
+    sync::Arc,
+};
 
+use actix_files::Files;
 use actix_web::{middleware::Logger, web, App, HttpServer};
+use tools_link::contract::{LINK_EVENT_TOPIC, LINK_VISIT_TOPIC};
 
-use crate::dto::AppData;
+use crate::{
+    dto::AppData,
+    service::{kafka_consumer, link_service, rabbitmq_consumer, visit_reporter},
+};
 
+mod cache;
 mod contract;
 mod controller;
 mod dto;
 mod env;
-mod static_file;
+mod helper;
+mod service;
+mod template;
 
 #[actix_web::main]
 async fn main() -> Result<()> {
@@ -18,6 +31,90 @@ async fn main() -> Result<()> {
     let service_addrs = env::Env::service_addrs();
     let service_gql_addrs = env::Env::service_gql_addrs();
     let site_link_url = env::Env::site_link_url();
+    let permanent_redirect_max_age = env::Env::permanent_redirect_max_age();
+    let trusted_proxies = env::Env::trusted_proxies();
+    let use_msg_broker = env::Env::use_msg_broker();
+    let theme_dir = env::Env::theme_dir();
+    let templates = template::registry(theme_dir.as_deref())
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    let http_client = reqwest::Client::new();
+
+    // Let the apps open the short urls instead of the browser
+    let apple_app_site_association =
+        controller::well_known::apple_app_site_association(&env::Env::apple_app_ids());
+    let asset_links = env::Env::android_app_package().and_then(|android_app_package| {
+        controller::well_known::asset_links(
+            &android_app_package,
+            &env::Env::android_app_cert_fingerprints(),
+        )
+    });
+
+    // Visit the links through the link service instead of the gateway
+    let link_service_mode = env::Env::link_service_mode();
+    let link_service_pool_size = env::Env::link_service_pool_size();
+    let grpc_request_timeout = env::Env::grpc_request_timeout();
+    let link_service_endpoint = match link_service_mode.is_grpc() {
+        true => Some(
+            link_service::endpoint(
+                &env::Env::link_service_addrs(),
+                env::Env::grpc_connect_timeout(),
+                grpc_request_timeout,
+            )
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?,
+        ),
+        false => None,
+    };
+
+    // Links are only cached with a message broker, it brings the link events that invalidate
+    // them and takes the visits of the cached links to the link service
+    let link_cache = Arc::new(cache::LinkCache::new(
+        env::Env::link_cache_capacity(),
+        env::Env::link_cache_ttl(),
+        env::Env::link_cache_not_found_ttl(),
+    ));
+    let mut kafka_producer: Option<rdkafka::producer::FutureProducer> = None;
+    let mut rabbitmq_channel: Option<lapin::Channel> = None;
+    if use_msg_broker.is_kafka() {
+        let kafka_addrs = env::Env::kafka_addrs();
+        let kafka_group_id = env::Env::kafka_group_id();
+        let kafka_msg_timeout = env::Env::kafka_msg_timeout();
+        kafka_producer = Some(
+            rdkafka::ClientConfig::new()
+                .set("bootstrap.servers", &kafka_addrs)
+                .set("message.timeout.ms", &kafka_msg_timeout)
+                .create()
+                .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?,
+        );
+        tokio::spawn(log_consumer_error(kafka_consumer::link_events(
+            kafka_consumer::Config {
+                brokers: kafka_addrs,
+                group_id: kafka_group_id,
+            },
+            link_cache.to_owned(),
+        )));
+    } else if use_msg_broker.is_rabbitmq() {
+        let rabbitmq_addrs = env::Env::rabbitmq_addrs();
+        let channel = create_rabbitmq_channel(&rabbitmq_addrs)
+            .await
+            .map_err(|e| Error::new(ErrorKind::NotConnected, e))?;
+        tokio::spawn(log_consumer_error(rabbitmq_consumer::link_events(
+            channel.to_owned(),
+            link_cache.to_owned(),
+        )));
+        rabbitmq_channel = Some(channel);
+    }
+    let (link_cache, visit_sender) = match kafka_producer.is_some() || rabbitmq_channel.is_some() {
+        true => {
+            let (visit_sender, visit_receiver) = tokio::sync::mpsc::unbounded_channel();
+            tokio::spawn(visit_reporter::report_visits(
+                visit_receiver,
+                kafka_producer,
+                rabbitmq_channel,
+            ));
+            (Some(link_cache), Some(visit_sender))
+        }
+        false => (None, None),
+    };
 
     println!("{app_name} {service_name} is running on {service_addrs} in {app_mode}.");
 
@@ -28,11 +125,81 @@ async fn main() -> Result<()> {
                 app_mode: app_mode.to_owned(),
                 gql_addrs: service_gql_addrs.to_owned(),
                 site_link_url: site_link_url.to_owned(),
+                permanent_redirect_max_age,
+                templates: templates.to_owned(),
+                apple_app_site_association: apple_app_site_association.to_owned(),
+                asset_links: asset_links.to_owned(),
+                http_client: http_client.to_owned(),
+                trusted_proxies: trusted_proxies.to_owned(),
+                link_service: link_service_endpoint.as_ref().map(|endpoint| {
+                    link_service::LinkServicePool::new(
+                        endpoint,
+                        link_service_pool_size,
+                        grpc_request_timeout,
+                    )
+                }),
+                link_cache: link_cache.to_owned(),
+                visit_sender: visit_sender.to_owned(),
             }))
+            .configure(|cfg| {
+                // Serve the static assets of the theme
+                if let Some(theme_dir) = &theme_dir {
+                    cfg.service(Files::new(
+                        template::THEME_STATIC_PATH,
+                        theme_dir.join(template::THEME_STATIC_DIR),
+                    ));
+                }
+            })
             .service(controller::root::root)
+            .service(controller::robots::robots)
+            .service(controller::well_known::apple_app_site_association_file)
+            .service(controller::well_known::asset_links_file)
+            .service(controller::preview::preview_link)
+            .service(controller::report::report_link_page)
+            .service(controller::report::report_link)
             .service(controller::resolver::resolve_link)
+            .default_service(web::to(controller::not_found::not_found))
     })
     .bind(service_addrs)?
     .run()
     .await
 }
+
+async fn create_rabbitmq_channel(rabbitmq_addrs: &str) -> lapin::Result<lapin::Channel> {
+    let rabbitmq_connection = lapin::Connection::connect(
+        rabbitmq_addrs,
+        lapin::ConnectionProperties::default()
+            .with_executor(tokio_executor_trait::Tokio::current())
+            .with_reactor(tokio_reactor_trait::Tokio),
+    )
+    .await?;
+    let rabbitmq_channel = rabbitmq_connection.create_channel().await?;
+    rabbitmq_channel
+        .confirm_select(lapin::options::ConfirmSelectOptions::default())
+        .await?;
+    rabbitmq_channel
+        .exchange_declare(
+            LINK_EVENT_TOPIC,
+            lapin::ExchangeKind::Fanout,
+            lapin::options::ExchangeDeclareOptions::default(),
+            lapin::types::FieldTable::default(),
+        )
+        .await?;
+    rabbitmq_channel
+        .queue_declare(
+            LINK_VISIT_TOPIC,
+            lapin::options::QueueDeclareOptions::default(),
+            lapin::types::FieldTable::default(),
+        )
+        .await?;
+
+    Ok(rabbitmq_channel)
+}
+
+async fn log_consumer_error<E: Display>(
+    consumer: impl Future<Output = std::result::Result<(), E>>,
+) {
+    if let Err(e) = consumer.await {
+        eprintln!("Link event consumer stopped: {e}");
+    }
+}
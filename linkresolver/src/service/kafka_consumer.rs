@@ -0,0 +1,49 @@
+use std::{error::Error, sync::Arc};
+
+use rdkafka::{
+    consumer::{Consumer, StreamConsumer},
+    ClientConfig, Message,
+};
+use tools_link::contract::LINK_EVENT_TOPIC;
+use uuid::Uuid;
+
+use crate::cache::LinkCache;
+
+#[derive(Clone)]
+pub struct Config {
+    pub brokers: String,
+    pub group_id: String,
+}
+
+pub async fn link_events(
+    config: Config,
+    link_cache: Arc<LinkCache>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    // Every resolver has its own cache, so every one of them reads all the events
+    let consumer: StreamConsumer = ClientConfig::new()
+        .set(
+            "group.id",
+            format!("{}-{}", config.group_id, Uuid::new_v4()),
+        )
+        .set("bootstrap.servers", &config.brokers)
+        .set("session.timeout.ms", "6000")
+        .set("enable.auto.commit", "false")
+        .set("auto.offset.reset", "latest")
+        .create()?;
+
+    consumer.subscribe(&[LINK_EVENT_TOPIC])?;
+
+    loop {
+        match consumer.recv().await {
+            Ok(message) => {
+                if let Some(payload) = message.payload() {
+                    super::invalidate(&link_cache, payload);
+                }
+            }
+            Err(e) => {
+                eprintln!("Kafka receive stream error: {e}");
+                link_cache.clear();
+            }
+        }
+    }
+}
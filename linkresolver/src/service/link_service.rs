@@ -0,0 +1,273 @@
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use cynic::{GraphQlResponse, Operation};
+use reqwest::StatusCode;
+use serde::{de::DeserializeOwned, Serialize};
+use tonic::{
+    transport::{Channel, Endpoint},
+    Code, Request, Status,
+};
+use tools_link::proto::link::{self as proto, LinkServiceClient};
+
+use crate::{contract::gql_query, dto::AppData, helper};
+
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(30);
+
+pub enum LinkServiceError {
+    /// The link doesn't exist.
+    NotFound(Option<String>),
+    /// The link exists but has expired.
+    Expired(Option<String>),
+    /// The link exists but has been disabled.
+    Disabled(Option<String>),
+    /// Too many requests were made.
+    RateLimited(Option<String>),
+    /// The gateway answered with errors.
+    Rejected(Option<String>),
+    /// Neither the link service nor the gateway answered.
+    Unreachable,
+}
+
+impl LinkServiceError {
+    /// Gets the error of a link that can't be visited, other failures of the link service
+    /// are left to the gateway.
+    fn from_status(status: &Status) -> Option<Self> {
+        let message = Some(status.message().to_owned());
+        match status.code() {
+            Code::NotFound => Some(Self::NotFound(message)),
+            Code::FailedPrecondition => Some(Self::Expired(message)),
+            Code::PermissionDenied => Some(Self::Disabled(message)),
+            Code::ResourceExhausted => Some(Self::RateLimited(message)),
+            _ => None,
+        }
+    }
+
+    /// Gets the error of a link that can't be visited from the code the gateway adds to
+    /// its errors.
+    fn from_gql_errors(errors: &Option<Vec<cynic::GraphQlError<serde_json::Value>>>) -> Self {
+        let message = helper::gql_errors(errors);
+        match helper::gql_error_code(errors) {
+            Some("NOT_FOUND") => Self::NotFound(message),
+            Some("EXPIRED") => Self::Expired(message),
+            Some("DISABLED") => Self::Disabled(message),
+            Some("RATE_LIMITED") => Self::RateLimited(message),
+            _ => Self::Rejected(message),
+        }
+    }
+}
+
+pub fn endpoint(
+    service_addrs: &str,
+    connect_timeout: Duration,
+    request_timeout: Duration,
+) -> Result<Endpoint, tonic::transport::Error> {
+    Ok(Endpoint::from_shared(service_addrs.to_owned())?
+        .connect_timeout(connect_timeout)
+        .timeout(request_timeout)
+        .tcp_keepalive(Some(KEEP_ALIVE_INTERVAL))
+        .http2_keep_alive_interval(KEEP_ALIVE_INTERVAL)
+        .keep_alive_while_idle(true))
+}
+
+/// Channels to the link service that stay open between requests, the requests are spread
+/// over them in turn.
+pub struct LinkServicePool {
+    channels: Vec<Channel>,
+    next: AtomicUsize,
+    request_timeout: Duration,
+}
+
+impl LinkServicePool {
+    /// Creates the channels without connecting them, they connect on the first request and
+    /// reconnect on their own, so the link service doesn't have to be up first.
+    pub fn new(endpoint: &Endpoint, size: usize, request_timeout: Duration) -> Self {
+        Self {
+            channels: (0..size.max(1)).map(|_| endpoint.connect_lazy()).collect(),
+            next: AtomicUsize::new(0),
+            request_timeout,
+        }
+    }
+
+    fn client(&self) -> LinkServiceClient<Channel> {
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.channels.len();
+        LinkServiceClient::new(self.channels[index].to_owned())
+    }
+
+    fn request<T>(&self, message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        request.set_timeout(self.request_timeout);
+        request
+    }
+}
+
+/// Visits a link through the link service, or through the gateway when the link service
+/// isn't used or fails.
+pub async fn visit_link(
+    data: &AppData,
+    req: proto::VisitLinkReq,
+) -> Result<proto::VisitLinkRes, LinkServiceError> {
+    if let Some(link_service) = &data.link_service {
+        match link_service
+            .client()
+            .visit_link(link_service.request(req.to_owned()))
+            .await
+        {
+            Ok(res) => return Ok(res.into_inner()),
+            Err(e) => match LinkServiceError::from_status(&e) {
+                Some(error) => return Err(error),
+                None => eprintln!("Failed to visit the link through the link service: {e}"),
+            },
+        }
+    }
+
+    let short_url = req.short_url.to_owned();
+    let query = gql_query::query_builder(gql_query::VisitLinkReq {
+        short_url: req.short_url,
+        query: req.query,
+        user_agent: req.user_agent,
+        ip_address: req.ip_address,
+        accept_language: req.accept_language,
+        visitor_id: req.visitor_id,
+    });
+    let res_data = run_graphql(data, query, None).await?;
+
+    Ok(proto::VisitLinkRes {
+        short_url,
+        long_url: res_data.visit_link.long_url,
+        title: res_data.visit_link.title,
+        interstitial: res_data.visit_link.interstitial,
+        cacheable: res_data.visit_link.cacheable,
+        redirect_status: match res_data.visit_link.redirect_status {
+            gql_query::LinkRedirectStatus::TemporaryRedirect => {
+                proto::LinkRedirectStatus::TemporaryRedirect
+            }
+            gql_query::LinkRedirectStatus::PermanentRedirect => {
+                proto::LinkRedirectStatus::PermanentRedirect
+            }
+            gql_query::LinkRedirectStatus::Found => proto::LinkRedirectStatus::Found,
+            gql_query::LinkRedirectStatus::MovedPermanently => {
+                proto::LinkRedirectStatus::MovedPermanently
+            }
+        } as i32,
+        referrer_policy: res_data.visit_link.referrer_policy,
+        no_index: res_data.visit_link.no_index,
+        deep_link: Some(proto::LinkDeepLink {
+            app_uri: res_data.visit_link.deep_link.app_uri,
+            android_package: res_data.visit_link.deep_link.android_package,
+            ios_fallback_url: res_data.visit_link.deep_link.ios_fallback_url,
+        }),
+    })
+}
+
+/// Gets what a link leads to without counting a visit, through the link service or through
+/// the gateway when the link service isn't used or fails.
+pub async fn preview_link(
+    data: &AppData,
+    short_url: String,
+) -> Result<proto::PreviewLinkRes, LinkServiceError> {
+    if let Some(link_service) = &data.link_service {
+        match link_service
+            .client()
+            .preview_link(link_service.request(proto::PreviewLinkReq {
+                short_url: short_url.to_owned(),
+            }))
+            .await
+        {
+            Ok(res) => return Ok(res.into_inner()),
+            Err(e) => match LinkServiceError::from_status(&e) {
+                Some(error) => return Err(error),
+                None => eprintln!("Failed to preview the link through the link service: {e}"),
+            },
+        }
+    }
+
+    let query = gql_query::preview_query_builder(gql_query::PreviewLinkReq { short_url });
+    let res_data = run_graphql(data, query, None).await?;
+    let preview = res_data.preview_link;
+
+    Ok(proto::PreviewLinkRes {
+        short_url: preview.short_url,
+        title: preview.title,
+        long_url: preview.long_url,
+        visits: preview.visits,
+        og_metadata: Some(proto::LinkOgMetadata {
+            title: preview.og_metadata.title,
+            description: preview.og_metadata.description,
+            image: preview.og_metadata.image,
+        }),
+    })
+}
+
+/// Reports an abusive link to the moderators through the link service, or through the
+/// gateway when the link service isn't used or fails.
+pub async fn report_link(
+    data: &AppData,
+    req: proto::ReportLinkReq,
+) -> Result<(), LinkServiceError> {
+    if let Some(link_service) = &data.link_service {
+        match link_service
+            .client()
+            .report_link(link_service.request(req.to_owned()))
+            .await
+        {
+            Ok(res) if res.get_ref().is_success => return Ok(()),
+            Ok(_) => return Err(LinkServiceError::Rejected(None)),
+            Err(e) => match LinkServiceError::from_status(&e) {
+                Some(error) => return Err(error),
+                None => eprintln!("Failed to report the link through the link service: {e}"),
+            },
+        }
+    }
+
+    let query = gql_query::report_mutation_builder(gql_query::ReportLinkReq {
+        reason: match req.reason() {
+            proto::LinkReportReason::Spam => gql_query::LinkReportReason::Spam,
+            proto::LinkReportReason::Phishing => gql_query::LinkReportReason::Phishing,
+            proto::LinkReportReason::Malware => gql_query::LinkReportReason::Malware,
+            proto::LinkReportReason::IllegalContent => gql_query::LinkReportReason::IllegalContent,
+            proto::LinkReportReason::Other => gql_query::LinkReportReason::Other,
+        },
+        short_url: req.short_url,
+        details: req.details,
+    });
+    let res_data = run_graphql(data, query, req.reporter_ip.as_deref()).await?;
+
+    match res_data.report_link.is_success {
+        true => Ok(()),
+        false => Err(LinkServiceError::Rejected(None)),
+    }
+}
+
+/// Runs a query on the gateway, reading the error extensions that tell a link that can't be
+/// visited from a failure. The IP address of the visitor is forwarded for the gateway to read
+/// it as the address of the request.
+async fn run_graphql<Q: DeserializeOwned, V: Serialize>(
+    data: &AppData,
+    operation: Operation<Q, V>,
+    client_ip: Option<&str>,
+) -> Result<Q, LinkServiceError> {
+    let mut req = data
+        .http_client
+        .post(data.gql_addrs.as_str())
+        .json(&operation);
+    if let Some(client_ip) = client_ip {
+        req = req.header("x-forwarded-for", client_ip);
+    }
+    let res = req
+        .send()
+        .await
+        .map_err(|_| LinkServiceError::Unreachable)?;
+    if res.status() == StatusCode::TOO_MANY_REQUESTS {
+        return Err(LinkServiceError::RateLimited(None));
+    }
+    let res = res
+        .json::<GraphQlResponse<Q, serde_json::Value>>()
+        .await
+        .map_err(|_| LinkServiceError::Unreachable)?;
+
+    res.data
+        .ok_or_else(|| LinkServiceError::from_gql_errors(&res.errors))
+}
@@ -0,0 +1,23 @@
+use tools_link::contract::LinkEvent;
+
+use crate::cache::LinkCache;
+
+pub mod kafka_consumer;
+pub mod link_service;
+pub mod rabbitmq_consumer;
+pub mod visit_reporter;
+
+fn invalidate(link_cache: &LinkCache, payload: &[u8]) {
+    match serde_json::from_slice::<Vec<LinkEvent>>(payload) {
+        Ok(events) => {
+            for event in events {
+                link_cache.invalidate(&event.short_url);
+            }
+        }
+        // Don't keep links that might have changed
+        Err(e) => {
+            eprintln!("Failed to read link events: {e}");
+            link_cache.clear();
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use futures::StreamExt;
+use lapin::{
+    options::{BasicConsumeOptions, QueueBindOptions, QueueDeclareOptions},
+    types::FieldTable,
+    Channel, Result,
+};
+use tools_link::contract::LINK_EVENT_TOPIC;
+
+use crate::cache::LinkCache;
+
+pub async fn link_events(channel: Channel, link_cache: Arc<LinkCache>) -> Result<()> {
+    // Every resolver has its own cache, so every one of them gets a queue bound to the
+    // exchange of the events
+    let queue = channel
+        .queue_declare(
+            "",
+            QueueDeclareOptions {
+                exclusive: true,
+                auto_delete: true,
+                ..QueueDeclareOptions::default()
+            },
+            FieldTable::default(),
+        )
+        .await?;
+    channel
+        .queue_bind(
+            queue.name().as_str(),
+            LINK_EVENT_TOPIC,
+            "",
+            QueueBindOptions::default(),
+            FieldTable::default(),
+        )
+        .await?;
+
+    let consumer = &mut channel
+        .basic_consume(
+            queue.name().as_str(),
+            "consumer_tag",
+            BasicConsumeOptions {
+                no_ack: true,
+                ..BasicConsumeOptions::default()
+            },
+            FieldTable::default(),
+        )
+        .await?;
+
+    while let Some(delivery) = consumer.next().await {
+        match delivery {
+            Ok(delivery) => super::invalidate(&link_cache, &delivery.data),
+            Err(e) => {
+                eprintln!("Failed to consume queue message: {e}");
+                link_cache.clear();
+            }
+        }
+    }
+
+    Ok(())
+}
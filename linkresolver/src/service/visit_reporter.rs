@@ -0,0 +1,84 @@
+use std::{collections::HashMap, time::Duration};
+
+use lapin::Channel;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use tokio::sync::mpsc::UnboundedReceiver;
+use tools_link::contract::{LinkVisit, LINK_VISIT_TOPIC};
+
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Counts the visits of the cached links and sends them to the link service in batches.
+/// The visits are kept for the next batch when they can't be sent.
+pub async fn report_visits(
+    mut receiver: UnboundedReceiver<String>,
+    kafka_producer: Option<FutureProducer>,
+    rabbitmq_channel: Option<Channel>,
+) {
+    let mut visits = HashMap::<String, i32>::new();
+    let mut interval = tokio::time::interval(REPORT_INTERVAL);
+    loop {
+        tokio::select! {
+            short_url = receiver.recv() => match short_url {
+                Some(short_url) => *visits.entry(short_url).or_default() += 1,
+                None => break,
+            },
+            _ = interval.tick() => {
+                if visits.is_empty() {
+                    continue;
+                }
+                match publish(&kafka_producer, &rabbitmq_channel, &visits).await {
+                    Ok(()) => visits.clear(),
+                    Err(e) => eprintln!("Failed to report link visits: {e}"),
+                }
+            }
+        }
+    }
+
+    if let Err(e) = publish(&kafka_producer, &rabbitmq_channel, &visits).await {
+        eprintln!("Failed to report link visits: {e}");
+    }
+}
+
+async fn publish(
+    kafka_producer: &Option<FutureProducer>,
+    rabbitmq_channel: &Option<Channel>,
+    visits: &HashMap<String, i32>,
+) -> Result<(), String> {
+    if visits.is_empty() {
+        return Ok(());
+    }
+
+    let payload = serde_json::to_string(
+        &visits
+            .iter()
+            .map(|(short_url, count)| LinkVisit {
+                short_url: short_url.to_owned(),
+                count: *count,
+            })
+            .collect::<Vec<LinkVisit>>(),
+    )
+    .map_err(|e| e.to_string())?;
+    if let Some(kafka_producer) = kafka_producer {
+        kafka_producer
+            .send_result(FutureRecord::<(), String>::to(LINK_VISIT_TOPIC).payload(&payload))
+            .map_err(|e| e.0.to_string())?
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.0.to_string())?;
+    } else if let Some(rabbitmq_channel) = rabbitmq_channel {
+        rabbitmq_channel
+            .basic_publish(
+                "",
+                LINK_VISIT_TOPIC,
+                lapin::options::BasicPublishOptions::default(),
+                payload.as_bytes(),
+                lapin::BasicProperties::default(),
+            )
+            .await
+            .map_err(|e| e.to_string())?
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
@@ -1 +0,0 @@
-pub const NOT_FOUND: &'static [u8] = include_bytes!("../static/not_found/index.html");
@@ -0,0 +1,71 @@
+use std::path::Path;
+
+use actix_web::{http::StatusCode, HttpResponse, HttpResponseBuilder};
+use handlebars::Handlebars;
+use serde::Serialize;
+
+pub const NOT_FOUND: &str = "not_found";
+pub const EXPIRED: &str = "expired";
+pub const DISABLED: &str = "disabled";
+pub const UNAVAILABLE: &str = "unavailable";
+pub const RATE_LIMITED: &str = "rate_limited";
+pub const PREVIEW: &str = "preview";
+pub const INTERSTITIAL: &str = "interstitial";
+pub const APP: &str = "app";
+pub const CARD: &str = "card";
+pub const REPORT: &str = "report";
+
+/// Where the static assets of a theme are served from.
+pub const THEME_STATIC_PATH: &str = "/_theme";
+pub const THEME_STATIC_DIR: &str = "static";
+const THEME_TEMPLATES_DIR: &str = "templates";
+
+const TEMPLATES: [(&str, &str); 11] = [
+    ("layout", include_str!("../templates/layout.hbs")),
+    (NOT_FOUND, include_str!("../templates/not_found.hbs")),
+    (EXPIRED, include_str!("../templates/expired.hbs")),
+    (DISABLED, include_str!("../templates/disabled.hbs")),
+    (UNAVAILABLE, include_str!("../templates/unavailable.hbs")),
+    (RATE_LIMITED, include_str!("../templates/rate_limited.hbs")),
+    (PREVIEW, include_str!("../templates/preview.hbs")),
+    (INTERSTITIAL, include_str!("../templates/interstitial.hbs")),
+    (APP, include_str!("../templates/app.hbs")),
+    (CARD, include_str!("../templates/card.hbs")),
+    (REPORT, include_str!("../templates/report.hbs")),
+];
+
+/// Registers the built-in templates, a theme replaces them with the templates of the same
+/// name in its `templates` directory.
+pub fn registry(
+    theme_dir: Option<&Path>,
+) -> Result<Handlebars<'static>, Box<handlebars::TemplateError>> {
+    let mut registry = Handlebars::new();
+    for (name, template) in TEMPLATES {
+        let theme_template = theme_dir
+            .map(|theme_dir| {
+                theme_dir
+                    .join(THEME_TEMPLATES_DIR)
+                    .join(format!("{name}.hbs"))
+            })
+            .filter(|theme_template| theme_template.is_file());
+        match theme_template {
+            Some(theme_template) => registry.register_template_file(name, theme_template),
+            None => registry.register_template_string(name, template),
+        }
+        .map_err(Box::new)?;
+    }
+
+    Ok(registry)
+}
+
+pub fn render<T: Serialize>(
+    registry: &Handlebars<'static>,
+    http_res: &mut HttpResponseBuilder,
+    name: &str,
+    data: &T,
+) -> HttpResponse {
+    match registry.render(name, data) {
+        Ok(html) => http_res.content_type("text/html; charset=utf-8").body(html),
+        Err(_) => HttpResponse::new(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}